@@ -0,0 +1,71 @@
+//! ANSI color control for terminal output
+//!
+//! Whether output is colored is decided once per process with [`set_mode`].
+//! Until then, and under [`ColorMode::Auto`], colors are used only when
+//! stdout is a terminal, so piped or redirected output stays plain.
+
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Red, for removed lines and failures
+pub const RED: &str = "31";
+/// Green, for added lines and success
+pub const GREEN: &str = "32";
+/// Yellow, for modified entries
+pub const YELLOW: &str = "33";
+/// Cyan, for renamed entries
+pub const CYAN: &str = "36";
+/// Bold, for headers
+pub const BOLD: &str = "1";
+/// Dim, for hints
+pub const DIM: &str = "2";
+
+/// No mode set yet; behaves like [`ColorMode::Auto`]
+const UNSET: u8 = 0;
+const ON: u8 = 1;
+const OFF: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNSET);
+
+/// When to emit ANSI color codes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Color only when stdout is a terminal
+    #[default]
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+/// Choose whether output is colored for the rest of the process
+pub fn set_mode(mode: ColorMode) {
+    let on = match mode {
+        ColorMode::Auto => std::io::stdout().is_terminal(),
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+    };
+    STATE.store(if on { ON } else { OFF }, Ordering::Relaxed);
+}
+
+/// Whether output is currently colored
+#[must_use]
+pub fn enabled() -> bool {
+    match STATE.load(Ordering::Relaxed) {
+        ON => true,
+        OFF => false,
+        _ => std::io::stdout().is_terminal(),
+    }
+}
+
+/// Wrap `text` in the ANSI `style` code, or return it unchanged when colors are off
+#[must_use]
+pub fn paint(style: &str, text: impl Display) -> String {
+    if enabled() {
+        format!("\x1b[{style}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
@@ -1,26 +1,35 @@
 //! File comparison, diff generation, and conflict detection
 //!
 //! This module provides read-only analysis of files to determine:
-//! - Content differences via SHA-256 hashing
+//! - Content differences via SHA-256 hashing, optionally ignoring the key
+//!   order of YAML frontmatter
 //! - Which file is newer via timestamp comparison
 //! - Visual diffs for changed files
 //! - Conflict classification and resolution strategy determination
 
+mod content;
 mod diff;
 mod directory;
+mod frontmatter;
 mod hash;
+mod rename;
 mod timestamp;
 
 #[cfg(test)]
 mod integration_tests;
 
+use std::fs;
 use std::path::Path;
 
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
-pub use diff::DiffGenerator;
+pub use content::BinaryDetector;
+pub use diff::{DiffGenerator, DiffHunk, DiffLine, DiffStat, FileDiff, LineTag};
 pub use directory::{DirectoryComparator, DirectoryComparison};
-pub use hash::FileHasher;
+pub use frontmatter::FrontmatterComparator;
+pub use hash::{EMPTY_HASH, FileHash, FileHasher};
+pub use rename::{Rename, RenameDetector};
 pub use timestamp::TimestampComparator;
 
 use crate::error::Result;
@@ -37,6 +46,8 @@ pub enum ConflictStrategy {
     Skip,
     /// Keep the newer file based on modification time
     Newer,
+    /// Keep the destination and write the source next to it as `name.incoming.ext`
+    KeepBoth,
 }
 
 /// Result of comparing two files
@@ -48,6 +59,8 @@ pub enum ComparisonResult {
     SourceOnly,
     /// Only destination file exists
     DestinationOnly,
+    /// Neither file exists, e.g. both were removed after a plan was made
+    NeitherExists,
     /// Both files exist with different content (conflict)
     Conflict {
         /// Whether source is newer than destination
@@ -82,26 +95,32 @@ impl FileComparator {
         source: &Path,
         destination: &Path,
         strategy: ConflictStrategy,
+    ) -> Result<ComparisonResult> {
+        Self::compare_with(source, destination, strategy, &FileHasher::same_content)
+    }
+
+    /// Compare two files like [`Self::compare`], deciding whether existing
+    /// files have the same content with `same_content` instead of hashing
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if file I/O operations or `same_content` fail.
+    pub fn compare_with(
+        source: &Path,
+        destination: &Path,
+        strategy: ConflictStrategy,
+        same_content: &dyn Fn(&Path, &Path) -> Result<bool>,
     ) -> Result<ComparisonResult> {
         let source_exists = source.exists();
         let dest_exists = destination.exists();
 
         match (source_exists, dest_exists) {
-            (false, false) => {
-                anyhow::bail!(
-                    "Neither source nor destination file exists: source={}, dest={}",
-                    source.display(),
-                    destination.display()
-                )
-            }
+            (false, false) => Ok(ComparisonResult::NeitherExists),
             (true, false) => Ok(ComparisonResult::SourceOnly),
             (false, true) => Ok(ComparisonResult::DestinationOnly),
             (true, true) => {
                 // Both exist - check if content differs
-                let source_hash = FileHasher::hash(source)?;
-                let dest_hash = FileHasher::hash(destination)?;
-
-                if source_hash == dest_hash {
+                if same_content(source, destination)? {
                     Ok(ComparisonResult::Identical)
                 } else {
                     // Conflict - both exist with different content
@@ -115,6 +134,64 @@ impl FileComparator {
         }
     }
 
+    /// Compare two files like [`Self::compare`], treating files whose YAML
+    /// frontmatter differs only in key order as identical
+    ///
+    /// The frontmatter is compared as parsed YAML and the body byte for byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if file I/O operations fail.
+    pub fn compare_semantic(
+        source: &Path,
+        destination: &Path,
+        strategy: ConflictStrategy,
+    ) -> Result<ComparisonResult> {
+        let result = Self::compare(source, destination, strategy)?;
+        if matches!(result, ComparisonResult::Conflict { .. })
+            && FrontmatterComparator::equivalent(source, destination)?
+        {
+            return Ok(ComparisonResult::Identical);
+        }
+        Ok(result)
+    }
+
+    /// Compare a source symlink with the destination by where they point
+    ///
+    /// Used for preserved symlinks whose target does not exist, so there is
+    /// no content to hash. The destination is identical only if it is a
+    /// symlink with the same target; recency uses the links' own timestamps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either link cannot be read.
+    pub fn compare_links(
+        source: &Path,
+        destination: &Path,
+        strategy: ConflictStrategy,
+    ) -> Result<ComparisonResult> {
+        let source_meta = fs::symlink_metadata(source)
+            .with_context(|| format!("Failed to read metadata for {}", source.display()))?;
+        let Ok(dest_meta) = fs::symlink_metadata(destination) else {
+            return Ok(ComparisonResult::SourceOnly);
+        };
+
+        let target = fs::read_link(source)
+            .with_context(|| format!("Failed to read symlink {}", source.display()))?;
+        if dest_meta.is_symlink() && fs::read_link(destination).is_ok_and(|dest| dest == target) {
+            return Ok(ComparisonResult::Identical);
+        }
+
+        let source_newer = match (source_meta.modified(), dest_meta.modified()) {
+            (Ok(source_time), Ok(dest_time)) => source_time > dest_time,
+            _ => false,
+        };
+        Ok(ComparisonResult::Conflict {
+            source_newer,
+            strategy,
+        })
+    }
+
     /// Generate a colored diff between two files
     ///
     /// # Errors
@@ -0,0 +1,72 @@
+//! Content classification (text vs binary)
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::error::Result;
+
+/// Number of leading bytes inspected when classifying a file
+const SNIFF_LEN: u64 = 8192;
+
+/// Detects binary files using the same heuristic as git
+pub struct BinaryDetector;
+
+impl BinaryDetector {
+    /// Check whether a file looks binary
+    ///
+    /// A file is treated as binary if its first 8KB contain a NUL byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn is_binary(path: &Path) -> Result<bool> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open file for inspection: {}", path.display()))?;
+
+        let mut head = Vec::new();
+        file.take(SNIFF_LEN)
+            .read_to_end(&mut head)
+            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+        Ok(head.contains(&0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_text_file_is_not_binary() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("notes.md");
+        fs::write(&path, "# Notes\n\nplain text ✓\n").unwrap();
+
+        assert!(!BinaryDetector::is_binary(&path).unwrap());
+    }
+
+    #[test]
+    fn test_nul_byte_marks_binary() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("logo.png");
+        fs::write(&path, [0x89, b'P', b'N', b'G', 0x00, 0x01]).unwrap();
+
+        assert!(BinaryDetector::is_binary(&path).unwrap());
+    }
+
+    #[test]
+    fn test_empty_file_is_text() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("empty.md");
+        fs::write(&path, "").unwrap();
+
+        assert!(!BinaryDetector::is_binary(&path).unwrap());
+    }
+}
@@ -2,14 +2,103 @@
 
 use std::fmt::Write;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
+use serde::Serialize;
 use similar::{ChangeTag, TextDiff};
 
+use crate::color::{self, BOLD, CYAN, DIM, GREEN, RED, YELLOW};
 use crate::error::Result;
 
-use super::directory::DirectoryComparison;
+use super::directory::{DirectoryComparator, DirectoryComparison};
+
+/// Marker printed after a last line that has no trailing newline, as git does
+const NO_NEWLINE_MARKER: &str = "\\ No newline at end of file";
+
+/// Unchanged lines kept around each change, as in `diff -u`
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// First line of a diff whose files could only be decoded by guessing
+const LOSSY_MARKER: &str = "non-UTF-8, showing lossy diff";
+
+/// Line counts of a diff, as shown by `diff --stat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    /// Lines added to the destination
+    pub insertions: usize,
+    /// Lines removed from the destination
+    pub deletions: usize,
+}
+
+impl DiffStat {
+    /// Total number of changed lines
+    #[must_use]
+    pub const fn changes(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+impl std::ops::AddAssign for DiffStat {
+    fn add_assign(&mut self, other: Self) {
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+    }
+}
+
+/// Structured diff of one file, as emitted by `diff --format json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    /// Destination path the diff applies to
+    pub path: PathBuf,
+    /// Lines added to the destination
+    pub added: usize,
+    /// Lines removed from the destination
+    pub removed: usize,
+    /// Content is not valid UTF-8, so no hunks are listed
+    pub binary: bool,
+    /// Changed regions with surrounding context
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// One changed region of a file
+///
+/// Starts are 1-based line numbers as in a `@@` header; `old` is the
+/// destination and `new` the source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffHunk {
+    /// First destination line covered
+    pub old_start: usize,
+    /// Destination lines covered
+    pub old_lines: usize,
+    /// First source line covered
+    pub new_start: usize,
+    /// Source lines covered
+    pub new_lines: usize,
+    /// Context and changed lines, in order
+    pub lines: Vec<DiffLine>,
+}
+
+/// A line of a hunk and how it changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffLine {
+    /// Whether the line is kept, inserted, or deleted
+    pub tag: LineTag,
+    /// Line content without its trailing newline
+    pub content: String,
+}
+
+/// Change tag of a [`DiffLine`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineTag {
+    /// Context present on both sides
+    Equal,
+    /// Only in the source
+    Insert,
+    /// Only in the destination
+    Delete,
+}
 
 /// Diff generator for creating visual diffs
 pub struct DiffGenerator;
@@ -27,25 +116,29 @@ impl DiffGenerator {
         Self
     }
 
-    /// Generate a color-coded unified diff between two files
+    /// Generate a unified diff between two files, colored if colors are on
+    ///
+    /// Files that are not UTF-8 are decoded as UTF-16 when they start with a
+    /// byte order mark, and as latin-1 otherwise; a diff that had to guess
+    /// starts with a "non-UTF-8, showing lossy diff" line.
     ///
     /// # Errors
     ///
     /// Returns an error if files cannot be read.
     pub fn generate(source: &Path, destination: &Path) -> Result<String> {
-        let source_content = fs::read_to_string(source)
+        let (source_content, source_lossy) = read_text(source)
             .with_context(|| format!("Failed to read source file: {}", source.display()))?;
 
-        let dest_content = fs::read_to_string(destination).with_context(|| {
+        let (dest_content, dest_lossy) = read_text(destination).with_context(|| {
             format!("Failed to read destination file: {}", destination.display())
         })?;
 
-        Ok(Self::generate_from_content(
-            &source_content,
-            &dest_content,
-            source,
-            destination,
-        ))
+        let diff =
+            Self::generate_from_content(&source_content, &dest_content, source, destination);
+        if source_lossy || dest_lossy {
+            return Ok(format!("{}\n{diff}", color::paint(DIM, LOSSY_MARKER)));
+        }
+        Ok(diff)
     }
 
     /// Generate a diff from string contents
@@ -56,15 +149,15 @@ impl DiffGenerator {
         source_path: &Path,
         dest_path: &Path,
     ) -> String {
-        const DIFF_CONTEXT_LINES: usize = 3;
-
         let diff = TextDiff::from_lines(dest_content, source_content);
 
         let mut output = String::new();
 
-        writeln!(output, "\x1b[1m--- {}\x1b[0m", dest_path.display())
+        let dest_header = format!("--- {}", dest_path.display());
+        let source_header = format!("+++ {}", source_path.display());
+        writeln!(output, "{}", color::paint(BOLD, dest_header))
             .expect("Writing to String should never fail");
-        writeln!(output, "\x1b[1m+++ {}\x1b[0m", source_path.display())
+        writeln!(output, "{}", color::paint(BOLD, source_header))
             .expect("Writing to String should never fail");
 
         for (idx, group) in diff.grouped_ops(DIFF_CONTEXT_LINES).iter().enumerate() {
@@ -74,20 +167,17 @@ impl DiffGenerator {
 
             for op in group {
                 for change in diff.iter_changes(op) {
-                    let (sign, color) = match change.tag() {
-                        ChangeTag::Delete => ("-", "\x1b[31m"), // Red
-                        ChangeTag::Insert => ("+", "\x1b[32m"), // Green
-                        ChangeTag::Equal => (" ", "\x1b[0m"),   // No color
-                    };
-
-                    let newline = if change.value().ends_with('\n') {
-                        ""
-                    } else {
-                        "\n"
+                    let line = match change.tag() {
+                        ChangeTag::Delete => color::paint(RED, format!("-{}", change.value())),
+                        ChangeTag::Insert => color::paint(GREEN, format!("+{}", change.value())),
+                        ChangeTag::Equal => format!(" {}", change.value()),
                     };
 
-                    write!(output, "{color}{sign}{}{newline}\x1b[0m", change.value())
-                        .expect("Writing to String should never fail");
+                    output.push_str(&line);
+                    if change.missing_newline() {
+                        writeln!(output, "\n{NO_NEWLINE_MARKER}")
+                            .expect("Writing to String should never fail");
+                    }
                 }
             }
         }
@@ -101,10 +191,10 @@ impl DiffGenerator {
     ///
     /// Returns an error if files cannot be read.
     pub fn generate_plain(source: &Path, destination: &Path) -> Result<String> {
-        let source_content = fs::read_to_string(source)
+        let (source_content, _) = read_text(source)
             .with_context(|| format!("Failed to read source file: {}", source.display()))?;
 
-        let dest_content = fs::read_to_string(destination).with_context(|| {
+        let (dest_content, _) = read_text(destination).with_context(|| {
             format!("Failed to read destination file: {}", destination.display())
         })?;
 
@@ -120,6 +210,10 @@ impl DiffGenerator {
 
             write!(output, "{sign}{}", change.value())
                 .expect("Writing to String should never fail");
+            if change.missing_newline() {
+                writeln!(output, "\n{NO_NEWLINE_MARKER}")
+                    .expect("Writing to String should never fail");
+            }
         }
 
         Ok(output)
@@ -141,24 +235,42 @@ impl DiffGenerator {
     ) -> Result<String> {
         let mut output = String::new();
 
-        writeln!(
-            output,
-            "\x1b[1m📊 Skill directory diff: {skill_name}\x1b[0m\n"
-        )
-        .expect("Writing to String should never fail");
+        let title = format!(
+            "📊 Skill directory diff: {skill_name} ({}/{} files identical)",
+            comparison.unchanged.len(),
+            comparison.total_files()
+        );
+        writeln!(output, "{}\n", color::paint(BOLD, title))
+            .expect("Writing to String should never fail");
 
         if !comparison.added.is_empty() {
-            writeln!(output, "\x1b[32mFiles to add:\x1b[0m")
+            writeln!(output, "{}", color::paint(GREEN, "Files to add:"))
                 .expect("Writing to String should never fail");
             for file in &comparison.added {
-                writeln!(output, "  \x1b[32m+\x1b[0m {}", file.display())
+                writeln!(output, "  {} {}", color::paint(GREEN, "+"), file.display())
                     .expect("Writing to String should never fail");
             }
             output.push('\n');
         }
 
+        if !comparison.renamed.is_empty() {
+            writeln!(output, "{}", color::paint(CYAN, "Files to rename:"))
+                .expect("Writing to String should never fail");
+            for rename in &comparison.renamed {
+                writeln!(
+                    output,
+                    "  {} {} → {}",
+                    color::paint(CYAN, "→"),
+                    rename.from.display(),
+                    rename.to.display()
+                )
+                .expect("Writing to String should never fail");
+            }
+            output.push('\n');
+        }
+
         if !comparison.modified.is_empty() {
-            writeln!(output, "\x1b[33mFiles to modify:\x1b[0m")
+            writeln!(output, "{}", color::paint(YELLOW, "Files to modify:"))
                 .expect("Writing to String should never fail");
             for file in &comparison.modified {
                 let src_file = source_dir.join(file);
@@ -172,7 +284,8 @@ impl DiffGenerator {
 
                 writeln!(
                     output,
-                    "  \x1b[33m~\x1b[0m {}{lines_info}",
+                    "  {} {}{lines_info}",
+                    color::paint(YELLOW, "~"),
                     file.display()
                 )
                 .expect("Writing to String should never fail");
@@ -181,27 +294,281 @@ impl DiffGenerator {
         }
 
         if !comparison.removed.is_empty() {
-            writeln!(output, "\x1b[31mFiles to remove:\x1b[0m")
+            writeln!(output, "{}", color::paint(RED, "Files to remove:"))
                 .expect("Writing to String should never fail");
             for file in &comparison.removed {
-                writeln!(output, "  \x1b[31m-\x1b[0m {}", file.display())
+                writeln!(output, "  {} {}", color::paint(RED, "-"), file.display())
                     .expect("Writing to String should never fail");
             }
             output.push('\n');
         }
 
         if comparison.is_identical() {
-            writeln!(output, "\x1b[32mDirectories are identical\x1b[0m")
+            writeln!(output, "{}", color::paint(GREEN, "Directories are identical"))
                 .expect("Writing to String should never fail");
         } else if !comparison.modified.is_empty() {
+            let hint = "(Press 'c' at the prompt to see line-by-line content diffs for modified files)";
+            writeln!(output, "{}", color::paint(DIM, hint))
+                .expect("Writing to String should never fail");
+        }
+
+        Ok(output)
+    }
+
+    /// Count the lines syncing `source` over `destination` would change
+    ///
+    /// A missing destination counts as empty. For skill directories the
+    /// counts of all added, modified, and removed files are summed; renamed
+    /// files have identical content and add nothing. Content that is not
+    /// valid UTF-8 (binary files) contributes no lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file or directory cannot be read.
+    pub fn stat(source: &Path, destination: &Path) -> Result<DiffStat> {
+        let mut stat = DiffStat::default();
+        for (_, file) in Self::file_stats(source, destination)? {
+            stat += file;
+        }
+        Ok(stat)
+    }
+
+    /// Count changed lines per file, keyed by destination path
+    ///
+    /// A file pair yields one entry; a skill directory yields one per added,
+    /// modified, or removed file, counted as in [`Self::stat`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file or directory cannot be read.
+    pub fn file_stats(source: &Path, destination: &Path) -> Result<Vec<(PathBuf, DiffStat)>> {
+        if !source.is_dir() {
+            let stat = Self::stat_files(Some(source), Some(destination))?;
+            return Ok(vec![(destination.to_path_buf(), stat)]);
+        }
+
+        let comparison = DirectoryComparator::compare(source, destination)?;
+        let mut stats = Vec::new();
+        for file in &comparison.added {
+            let stat = Self::stat_files(Some(&source.join(file)), None)?;
+            stats.push((destination.join(file), stat));
+        }
+        for file in &comparison.modified {
+            let stat =
+                Self::stat_files(Some(&source.join(file)), Some(&destination.join(file)))?;
+            stats.push((destination.join(file), stat));
+        }
+        for file in &comparison.removed {
+            let stat = Self::stat_files(None, Some(&destination.join(file)))?;
+            stats.push((destination.join(file), stat));
+        }
+        Ok(stats)
+    }
+
+    /// The `limit` files with the most changed lines, largest first
+    ///
+    /// Files with the same count are ordered by path.
+    #[must_use]
+    pub fn largest_changes(
+        entries: &[(PathBuf, DiffStat)],
+        limit: usize,
+    ) -> Vec<(PathBuf, DiffStat)> {
+        let mut largest = entries.to_vec();
+        largest.sort_by(|(a_path, a), (b_path, b)| {
+            b.changes().cmp(&a.changes()).then_with(|| a_path.cmp(b_path))
+        });
+        largest.truncate(limit);
+        largest
+    }
+
+    /// Format a ranking from [`Self::largest_changes`] under a heading
+    #[must_use]
+    pub fn format_largest(entries: &[(PathBuf, DiffStat)]) -> String {
+        let mut output = format!("\n Largest changes (top {}):\n", entries.len());
+        for (rank, (path, stat)) in entries.iter().enumerate() {
             writeln!(
                 output,
-                "\x1b[2m(Press 'c' at the prompt to see line-by-line content diffs for modified files)\x1b[0m"
+                " {}. {} | {} line(s) (+{} -{})",
+                rank + 1,
+                path.display(),
+                stat.changes(),
+                stat.insertions,
+                stat.deletions
             )
             .expect("Writing to String should never fail");
         }
+        output
+    }
 
-        Ok(output)
+    /// Generate the diff of `source` over `destination` as a JSON array of files
+    ///
+    /// See [`Self::file_diffs`] for what the array holds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file or directory cannot be read.
+    pub fn generate_json(source: &Path, destination: &Path) -> Result<String> {
+        Self::format_json(&Self::file_diffs(source, destination)?)
+    }
+
+    /// Serialize structured diffs as a pretty-printed JSON array
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn format_json(diffs: &[FileDiff]) -> Result<String> {
+        serde_json::to_string_pretty(diffs).context("Failed to serialize diff")
+    }
+
+    /// Structured diffs of the files syncing `source` over `destination` changes
+    ///
+    /// A file yields one entry; a skill directory yields one per added,
+    /// modified, or removed file. Missing files count as empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file or directory cannot be read.
+    pub fn file_diffs(source: &Path, destination: &Path) -> Result<Vec<FileDiff>> {
+        if !source.is_dir() {
+            return Ok(vec![Self::file_diff(Some(source), destination)?]);
+        }
+
+        let comparison = DirectoryComparator::compare(source, destination)?;
+        let mut diffs = Vec::new();
+        for file in comparison.added.iter().chain(&comparison.modified) {
+            diffs.push(Self::file_diff(Some(&source.join(file)), &destination.join(file))?);
+        }
+        for file in &comparison.removed {
+            diffs.push(Self::file_diff(None, &destination.join(file))?);
+        }
+        diffs.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(diffs)
+    }
+
+    /// Structured diff of one file pair, with `None` or a missing path as empty
+    fn file_diff(source: Option<&Path>, destination: &Path) -> Result<FileDiff> {
+        let read = |path: Option<&Path>| -> Result<Option<String>> {
+            match path {
+                Some(path) if path.exists() => {
+                    let bytes = fs::read(path)
+                        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+                    Ok(String::from_utf8(bytes).ok())
+                }
+                _ => Ok(Some(String::new())),
+            }
+        };
+        let (Some(source_content), Some(dest_content)) = (read(source)?, read(Some(destination))?)
+        else {
+            return Ok(FileDiff {
+                path: destination.to_path_buf(),
+                added: 0,
+                removed: 0,
+                binary: true,
+                hunks: Vec::new(),
+            });
+        };
+
+        let diff = TextDiff::from_lines(&dest_content, &source_content);
+        let mut file = FileDiff {
+            path: destination.to_path_buf(),
+            added: 0,
+            removed: 0,
+            binary: false,
+            hunks: Vec::new(),
+        };
+        for group in diff.grouped_ops(DIFF_CONTEXT_LINES) {
+            let (Some(first), Some(last)) = (group.first(), group.last()) else {
+                continue;
+            };
+            let old = first.old_range().start..last.old_range().end;
+            let new = first.new_range().start..last.new_range().end;
+            let mut hunk = DiffHunk {
+                old_start: old.start + 1,
+                old_lines: old.len(),
+                new_start: new.start + 1,
+                new_lines: new.len(),
+                lines: Vec::new(),
+            };
+            for change in group.iter().flat_map(|op| diff.iter_changes(op)) {
+                let tag = match change.tag() {
+                    ChangeTag::Equal => LineTag::Equal,
+                    ChangeTag::Insert => {
+                        file.added += 1;
+                        LineTag::Insert
+                    }
+                    ChangeTag::Delete => {
+                        file.removed += 1;
+                        LineTag::Delete
+                    }
+                };
+                let content = change.value();
+                let content = content.strip_suffix('\n').unwrap_or(content);
+                hunk.lines.push(DiffLine {
+                    tag,
+                    content: content.to_string(),
+                });
+            }
+            file.hunks.push(hunk);
+        }
+        Ok(file)
+    }
+
+    /// Format per-path counts like `git diff --stat`, followed by a total line
+    #[must_use]
+    pub fn format_stat(entries: &[(PathBuf, DiffStat)]) -> String {
+        let width = entries
+            .iter()
+            .map(|(path, _)| path.display().to_string().chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut output = String::new();
+        let mut total = DiffStat::default();
+        for (path, stat) in entries {
+            writeln!(
+                output,
+                " {:<width$} | +{} -{}",
+                path.display().to_string(),
+                stat.insertions,
+                stat.deletions
+            )
+            .expect("Writing to String should never fail");
+            total += *stat;
+        }
+
+        let files = if entries.len() == 1 { "file" } else { "files" };
+        writeln!(
+            output,
+            " {} {files} changed, {} insertion(s)(+), {} deletion(s)(-)",
+            entries.len(),
+            total.insertions,
+            total.deletions
+        )
+        .expect("Writing to String should never fail");
+
+        output
+    }
+
+    /// Count changed lines between two optional files (absent means empty)
+    fn stat_files(source: Option<&Path>, destination: Option<&Path>) -> Result<DiffStat> {
+        let read = |path: Option<&Path>| -> Result<String> {
+            match path {
+                Some(path) if path.exists() => {
+                    let bytes = fs::read(path)
+                        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+                    Ok(String::from_utf8(bytes).unwrap_or_default())
+                }
+                _ => Ok(String::new()),
+            }
+        };
+        let source_content = read(source)?;
+        let dest_content = read(destination)?;
+
+        let (insertions, deletions) = Self::count_lines(&source_content, &dest_content);
+        Ok(DiffStat {
+            insertions,
+            deletions,
+        })
     }
 
     /// Count added and removed lines in a file diff
@@ -209,7 +576,12 @@ impl DiffGenerator {
         let source_content = fs::read_to_string(source)?;
         let dest_content = fs::read_to_string(destination)?;
 
-        let diff = TextDiff::from_lines(&dest_content, &source_content);
+        Ok(Self::count_lines(&source_content, &dest_content))
+    }
+
+    /// Count inserted and deleted lines going from `dest_content` to `source_content`
+    fn count_lines(source_content: &str, dest_content: &str) -> (usize, usize) {
+        let diff = TextDiff::from_lines(dest_content, source_content);
 
         let mut added = 0;
         let mut removed = 0;
@@ -222,8 +594,41 @@ impl DiffGenerator {
             }
         }
 
-        Ok((added, removed))
+        (added, removed)
+    }
+}
+
+/// Read `path` as text, decoding files that are not UTF-8
+///
+/// Returns the text and whether decoding was lossy: UTF-16 with a byte
+/// order mark is decoded exactly unless malformed, and anything else falls
+/// back to latin-1, which accepts every byte but may be the wrong guess.
+fn read_text(path: &Path) -> std::io::Result<(String, bool)> {
+    Ok(decode_text(fs::read(path)?))
+}
+
+fn decode_text(bytes: Vec<u8>) -> (String, bool) {
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return (text, false),
+        Err(e) => e.into_bytes(),
+    };
+
+    let from_bytes: Option<fn([u8; 2]) -> u16> = match bytes.get(..2) {
+        Some([0xFF, 0xFE]) => Some(u16::from_le_bytes),
+        Some([0xFE, 0xFF]) => Some(u16::from_be_bytes),
+        _ => None,
+    };
+    if let Some(from_bytes) = from_bytes {
+        let pairs = bytes[2..].chunks_exact(2);
+        let odd = !pairs.remainder().is_empty();
+        let units: Vec<u16> = pairs.map(|pair| from_bytes([pair[0], pair[1]])).collect();
+        return match String::from_utf16(&units) {
+            Ok(text) if !odd => (text, false),
+            _ => (String::from_utf16_lossy(&units), true),
+        };
     }
+
+    (bytes.iter().copied().map(char::from).collect(), true)
 }
 
 #[cfg(test)]
@@ -275,6 +680,7 @@ mod tests {
         fs::write(&dest, "old line\n").unwrap();
         fs::write(&source, "new line\n").unwrap();
 
+        color::set_mode(color::ColorMode::Always);
         let _generator = DiffGenerator::new();
         let diff = DiffGenerator::generate(&source, &dest).unwrap();
 
@@ -331,6 +737,231 @@ mod tests {
         assert!(diff.is_ok());
     }
 
+    #[test]
+    fn test_stat_counts_file_changes() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.md");
+        let dest = tmp.path().join("dest.md");
+
+        fs::write(&dest, "line 1\nline 2\nline 3\n").unwrap();
+        fs::write(&source, "line 1\nchanged\nline 3\nline 4\n").unwrap();
+
+        let stat = DiffGenerator::stat(&source, &dest).unwrap();
+        assert_eq!(
+            stat,
+            DiffStat {
+                insertions: 2,
+                deletions: 1
+            }
+        );
+
+        // A missing destination counts every source line as inserted
+        let stat = DiffGenerator::stat(&source, &tmp.path().join("missing.md")).unwrap();
+        assert_eq!(stat.insertions, 4);
+        assert_eq!(stat.deletions, 0);
+    }
+
+    #[test]
+    fn test_largest_changes_ranks_and_truncates() {
+        let stat = |insertions, deletions| DiffStat {
+            insertions,
+            deletions,
+        };
+        let entries = vec![
+            (PathBuf::from("agents/small.md"), stat(1, 0)),
+            (PathBuf::from("agents/large.md"), stat(10, 5)),
+            (PathBuf::from("skills/demo/b.md"), stat(3, 3)),
+            (PathBuf::from("skills/demo/a.md"), stat(0, 6)),
+            (PathBuf::from("commands/mid.md"), stat(4, 4)),
+        ];
+
+        let largest = DiffGenerator::largest_changes(&entries, 3);
+
+        let paths: Vec<_> = largest.iter().map(|(path, _)| path.to_str().unwrap()).collect();
+        assert_eq!(
+            paths,
+            ["agents/large.md", "commands/mid.md", "skills/demo/a.md"]
+        );
+        assert!(
+            DiffGenerator::format_largest(&largest)
+                .contains(" 1. agents/large.md | 15 line(s) (+10 -5)")
+        );
+        assert_eq!(DiffGenerator::largest_changes(&entries, 10).len(), 5);
+    }
+
+    #[test]
+    fn test_stat_aggregates_directory_changes() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("src");
+        let dest = tmp.path().join("dst");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&dest).unwrap();
+
+        // Modified: +1 -1
+        fs::write(source.join("SKILL.md"), "a\nnew\n").unwrap();
+        fs::write(dest.join("SKILL.md"), "a\nold\n").unwrap();
+        // Added: +2
+        fs::write(source.join("extra.md"), "x\ny\n").unwrap();
+        // Removed: -3
+        fs::write(dest.join("stale.md"), "1\n2\n3\n").unwrap();
+
+        let stat = DiffGenerator::stat(&source, &dest).unwrap();
+
+        assert_eq!(
+            stat,
+            DiffStat {
+                insertions: 3,
+                deletions: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_directory_summary_header_counts_identical_files() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("src");
+        let dest = tmp.path().join("dst");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&dest).unwrap();
+        for dir in [&source, &dest] {
+            fs::write(dir.join("same.md"), "same\n").unwrap();
+        }
+        fs::write(source.join("SKILL.md"), "new\n").unwrap();
+        fs::write(dest.join("SKILL.md"), "old\n").unwrap();
+        fs::write(source.join("extra.md"), "extra\n").unwrap();
+
+        let comparison = DirectoryComparator::compare(&source, &dest).unwrap();
+        let summary =
+            DiffGenerator::generate_directory_summary(&comparison, &source, &dest, "demo").unwrap();
+
+        assert!(summary.contains("demo (1/3 files identical)"));
+    }
+
+    #[test]
+    fn test_generate_json_lists_hunks() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.md");
+        let dest = tmp.path().join("dest.md");
+        fs::write(&dest, "a\nb\nc\nd\ne\nf\ng\nh\n").unwrap();
+        fs::write(&source, "a\nb\nc\nd\nE\nf\ng\nh\ni\n").unwrap();
+
+        let json = DiffGenerator::generate_json(&source, &dest).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!([{
+                "path": dest,
+                "added": 2,
+                "removed": 1,
+                "binary": false,
+                "hunks": [{
+                    "old_start": 2,
+                    "old_lines": 7,
+                    "new_start": 2,
+                    "new_lines": 8,
+                    "lines": [
+                        {"tag": "equal", "content": "b"},
+                        {"tag": "equal", "content": "c"},
+                        {"tag": "equal", "content": "d"},
+                        {"tag": "delete", "content": "e"},
+                        {"tag": "insert", "content": "E"},
+                        {"tag": "equal", "content": "f"},
+                        {"tag": "equal", "content": "g"},
+                        {"tag": "equal", "content": "h"},
+                        {"tag": "insert", "content": "i"},
+                    ],
+                }],
+            }])
+        );
+    }
+
+    #[test]
+    fn test_file_diffs_cover_directory_changes() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("src");
+        let dest = tmp.path().join("dst");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(source.join("new.md"), "new\n").unwrap();
+        fs::write(dest.join("old.md"), "old\n").unwrap();
+
+        let diffs = DiffGenerator::file_diffs(&source, &dest).unwrap();
+
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].path, dest.join("new.md"));
+        assert_eq!((diffs[0].added, diffs[0].removed), (1, 0));
+        assert_eq!(diffs[1].path, dest.join("old.md"));
+        assert_eq!((diffs[1].added, diffs[1].removed), (0, 1));
+    }
+
+    #[test]
+    fn test_format_stat() {
+        let entries = vec![
+            (
+                PathBuf::from("agents/reviewer.md"),
+                DiffStat {
+                    insertions: 3,
+                    deletions: 1,
+                },
+            ),
+            (
+                PathBuf::from("skills/demo"),
+                DiffStat {
+                    insertions: 10,
+                    deletions: 0,
+                },
+            ),
+        ];
+
+        let output = DiffGenerator::format_stat(&entries);
+
+        assert_eq!(
+            output,
+            " agents/reviewer.md | +3 -1\n \
+             skills/demo        | +10 -0\n \
+             2 files changed, 13 insertion(s)(+), 1 deletion(s)(-)\n"
+        );
+    }
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn test_diff_utf16le_files() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.md");
+        let dest = tmp.path().join("dest.md");
+
+        fs::write(&dest, utf16le("name: agent\nold line\n")).unwrap();
+        fs::write(&source, utf16le("name: agent\nnew line\n")).unwrap();
+
+        let diff = DiffGenerator::generate_plain(&source, &dest).unwrap();
+        assert_eq!(diff, " name: agent\n-old line\n+new line\n");
+
+        let diff = DiffGenerator::generate(&source, &dest).unwrap();
+        assert!(!diff.contains(LOSSY_MARKER));
+        assert!(diff.contains("+new line"));
+    }
+
+    #[test]
+    fn test_diff_labels_latin1_fallback() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.md");
+        let dest = tmp.path().join("dest.md");
+
+        fs::write(&dest, "caf\u{e9}\n").unwrap();
+        fs::write(&source, b"caf\xe9 au lait\n").unwrap();
+
+        let diff = DiffGenerator::generate(&source, &dest).unwrap();
+        assert!(diff.lines().next().unwrap().contains(LOSSY_MARKER));
+        assert!(diff.contains("-caf\u{e9}\n"));
+        assert!(diff.contains("+caf\u{e9} au lait\n"));
+    }
+
     #[test]
     fn test_diff_empty_files() {
         let tmp = TempDir::new().unwrap();
@@ -346,4 +977,25 @@ mod tests {
         // Empty files should have empty diff
         assert!(diff.is_empty() || diff.trim().is_empty());
     }
+
+    #[test]
+    fn test_diff_marks_missing_newline_at_eof() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.txt");
+        let dest = tmp.path().join("dest.txt");
+
+        fs::write(&dest, "line 1\nline 2\n").unwrap();
+        fs::write(&source, "line 1\nline 2").unwrap();
+
+        let plain = DiffGenerator::generate_plain(&source, &dest).unwrap();
+        assert_eq!(
+            plain,
+            " line 1\n-line 2\n+line 2\n\\ No newline at end of file\n"
+        );
+
+        color::set_mode(color::ColorMode::Always);
+        let colored = DiffGenerator::generate(&source, &dest).unwrap();
+        assert!(colored.contains("\x1b[32m+line 2\x1b[0m\n\\ No newline at end of file\n"));
+        assert!(!colored.contains("\n\n"));
+    }
 }
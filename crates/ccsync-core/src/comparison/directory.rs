@@ -4,16 +4,23 @@
 //! files that are added, modified, removed, or unchanged between
 //! source and destination directories.
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
 
 use crate::error::Result;
 
-use super::hash::FileHasher;
+use super::hash::{FileHash, FileHasher};
+use super::rename::{Rename, RenameDetector};
 use super::timestamp::TimestampComparator;
 
 /// Result of comparing two directories recursively
+///
+/// Every path is relative to the compared roots, so the same entry can be
+/// joined onto either side.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DirectoryComparison {
     /// Files present in source but not in destination
@@ -22,6 +29,8 @@ pub struct DirectoryComparison {
     pub modified: Vec<PathBuf>,
     /// Files present in destination but not in source
     pub removed: Vec<PathBuf>,
+    /// Destination files whose content reappears at a new path in source
+    pub renamed: Vec<Rename>,
     /// Files with identical content in both locations
     pub unchanged: Vec<PathBuf>,
 }
@@ -30,13 +39,35 @@ impl DirectoryComparison {
     /// Check if directories are identical (no changes)
     #[must_use]
     pub const fn is_identical(&self) -> bool {
-        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+        self.added.is_empty()
+            && self.modified.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
     }
 
     /// Count total number of changes
     #[must_use]
     pub const fn change_count(&self) -> usize {
-        self.added.len() + self.modified.len() + self.removed.len()
+        self.added.len() + self.modified.len() + self.removed.len() + self.renamed.len()
+    }
+
+    /// Count files on either side, with a renamed file counted once
+    #[must_use]
+    pub const fn total_files(&self) -> usize {
+        self.change_count() + self.unchanged.len()
+    }
+
+    /// Fraction of files identical on both sides, from 0.0 to 1.0
+    ///
+    /// Two empty directories are fully identical (1.0).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn unchanged_ratio(&self) -> f64 {
+        let total = self.total_files();
+        if total == 0 {
+            return 1.0;
+        }
+        self.unchanged.len() as f64 / total as f64
     }
 }
 
@@ -46,36 +77,64 @@ pub struct DirectoryComparator;
 impl DirectoryComparator {
     /// Compare two directories recursively
     ///
-    /// Returns paths relative to the source/destination roots.
+    /// Returns paths relative to the source/destination roots, each list
+    /// sorted by path. A removed file whose content reappears as an added file
+    /// is reported once in `renamed` rather than in both `added` and `removed`.
     ///
     /// # Errors
     ///
     /// Returns an error if directory traversal or file operations fail.
     pub fn compare(source: &Path, destination: &Path) -> Result<DirectoryComparison> {
+        Self::compare_filtered(source, destination, &|_| true)
+    }
+
+    /// Compare two directories recursively, ignoring files rejected by `include`
+    ///
+    /// `include` receives the path of each file on disk: the source file when
+    /// it exists, otherwise the destination file. A file excluded on the source
+    /// side is ignored on both sides, so it is never reported as removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if directory traversal or file operations fail.
+    pub fn compare_filtered(
+        source: &Path,
+        destination: &Path,
+        include: &dyn Fn(&Path) -> bool,
+    ) -> Result<DirectoryComparison> {
         let mut added = Vec::new();
         let mut modified = Vec::new();
         let mut removed = Vec::new();
         let mut unchanged = Vec::new();
 
         // Collect all files in source
-        let source_files = Self::collect_files(source)?;
-        let dest_files = if destination.exists() {
+        let mut source_files = Self::collect_files(source)?;
+        let mut dest_files = if destination.exists() {
             Self::collect_files(destination)?
         } else {
-            HashSet::new()
+            BTreeSet::new()
         };
 
-        // Files in source
+        // Drop excluded files from both sides
+        let excluded: HashSet<PathBuf> = source_files
+            .iter()
+            .filter(|rel_path| !include(&source.join(rel_path)))
+            .cloned()
+            .collect();
+        source_files.retain(|rel_path| !excluded.contains(rel_path));
+        dest_files.retain(|rel_path| {
+            !excluded.contains(rel_path)
+                && (source_files.contains(rel_path) || include(&destination.join(rel_path)))
+        });
+
+        // Files in source, in path order
         for rel_path in &source_files {
             let source_file = source.join(rel_path);
             let dest_file = destination.join(rel_path);
 
             if dest_files.contains(rel_path) {
                 // File exists in both - check if modified
-                let source_hash = FileHasher::hash(&source_file)?;
-                let dest_hash = FileHasher::hash(&dest_file)?;
-
-                if source_hash == dest_hash {
+                if FileHasher::same_content(&source_file, &dest_file)? {
                     unchanged.push(rel_path.clone());
                 } else {
                     modified.push(rel_path.clone());
@@ -93,10 +152,13 @@ impl DirectoryComparator {
             }
         }
 
+        let renamed = RenameDetector::detect(source, destination, &mut added, &mut removed)?;
+
         Ok(DirectoryComparison {
             added,
             modified,
             removed,
+            renamed,
             unchanged,
         })
     }
@@ -119,9 +181,45 @@ impl DirectoryComparator {
         }
     }
 
+    /// Get the modification time of the newest file in a directory tree
+    ///
+    /// Returns `None` if the directory contains no files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if file metadata cannot be read.
+    pub fn newest_modified_time(dir: &Path) -> Result<Option<SystemTime>> {
+        Self::find_newest_file(dir)?
+            .map(|path| TimestampComparator::get_modified_time(&path))
+            .transpose()
+    }
+
+    /// Hash a whole directory tree into one digest
+    ///
+    /// The digest covers each file's relative path and content hash, in
+    /// sorted path order, so two trees have the same digest exactly when they
+    /// hold the same files with the same content. Renaming, adding, removing,
+    /// or editing any file changes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree cannot be walked or a file cannot be read.
+    pub fn tree_hash(dir: &Path) -> Result<FileHash> {
+        let mut files: Vec<_> = Self::collect_files(dir)?.into_iter().collect();
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        for rel_path in files {
+            hasher.update(rel_path.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(FileHasher::hash(&dir.join(&rel_path))?);
+        }
+        Ok(hasher.finalize().into())
+    }
+
     /// Collect all files in a directory tree (relative paths)
-    fn collect_files(dir: &Path) -> Result<HashSet<PathBuf>> {
-        let mut files = HashSet::new();
+    fn collect_files(dir: &Path) -> Result<BTreeSet<PathBuf>> {
+        let mut files = BTreeSet::new();
         Self::collect_files_recursive(dir, dir, &mut files)?;
         Ok(files)
     }
@@ -130,7 +228,7 @@ impl DirectoryComparator {
     fn collect_files_recursive(
         base: &Path,
         current: &Path,
-        files: &mut HashSet<PathBuf>,
+        files: &mut BTreeSet<PathBuf>,
     ) -> Result<()> {
         for entry in fs::read_dir(current)? {
             let entry = entry?;
@@ -202,6 +300,34 @@ mod tests {
         assert!(result.unchanged.iter().any(|p| p == Path::new("file1.txt")));
     }
 
+    #[test]
+    fn test_unchanged_ratio_with_mixed_buckets() {
+        let paths = |names: &[&str]| names.iter().map(PathBuf::from).collect::<Vec<_>>();
+        let comparison = DirectoryComparison {
+            added: paths(&["new.md"]),
+            modified: paths(&["SKILL.md"]),
+            removed: paths(&["old.md"]),
+            renamed: vec![Rename {
+                from: "a.md".into(),
+                to: "b.md".into(),
+            }],
+            unchanged: paths(&["1.md", "2.md", "3.md", "4.md", "5.md", "6.md"]),
+        };
+
+        assert_eq!(comparison.total_files(), 10);
+        assert!((comparison.unchanged_ratio() - 0.6).abs() < f64::EPSILON);
+
+        let empty = DirectoryComparison {
+            added: Vec::new(),
+            modified: Vec::new(),
+            removed: Vec::new(),
+            renamed: Vec::new(),
+            unchanged: Vec::new(),
+        };
+        assert_eq!(empty.total_files(), 0);
+        assert!((empty.unchanged_ratio() - 1.0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_compare_added_files() {
         let tmp = TempDir::new().unwrap();
@@ -220,6 +346,31 @@ mod tests {
         assert_eq!(result.change_count(), 1);
     }
 
+    #[test]
+    fn test_compare_lists_paths_in_sorted_order() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dst = tmp.path().join("dst");
+        fs::create_dir_all(src.join("nested")).unwrap();
+        fs::create_dir_all(dst.join("nested")).unwrap();
+
+        for name in ["z.txt", "nested/b.txt", "m.txt", "a.txt", "nested/a.txt"] {
+            fs::write(src.join(name), name).unwrap();
+            fs::write(dst.join(format!("old-{}", name.replace('/', "-"))), "old").unwrap();
+        }
+
+        let result = DirectoryComparator::compare(&src, &dst).unwrap();
+
+        let mut added = result.added.clone();
+        added.sort();
+        assert_eq!(result.added, added);
+        assert_eq!(result.added.first().unwrap(), Path::new("a.txt"));
+        let mut removed = result.removed.clone();
+        removed.sort();
+        assert_eq!(result.removed, removed);
+        assert_eq!(result.removed.len(), 5);
+    }
+
     #[test]
     fn test_compare_modified_files() {
         let tmp = TempDir::new().unwrap();
@@ -296,4 +447,62 @@ mod tests {
         assert_eq!(result.added.len(), 1);
         assert_eq!(result.removed.len(), 0);
     }
+
+    #[test]
+    fn test_compare_reports_renamed_files_once() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dst = tmp.path().join("dst");
+
+        fs::create_dir(&src).unwrap();
+        fs::create_dir(&dst).unwrap();
+
+        fs::write(src.join("new-name.md"), "renamed content").unwrap();
+        fs::write(dst.join("old-name.md"), "renamed content").unwrap();
+
+        let result = DirectoryComparator::compare(&src, &dst).unwrap();
+
+        assert!(result.added.is_empty());
+        assert!(result.removed.is_empty());
+        assert_eq!(
+            result.renamed,
+            vec![Rename {
+                from: PathBuf::from("old-name.md"),
+                to: PathBuf::from("new-name.md"),
+            }]
+        );
+        assert_eq!(result.change_count(), 1);
+        assert!(!result.is_identical());
+    }
+
+    #[test]
+    fn test_tree_hash_is_stable_across_copies() {
+        let tmp = TempDir::new().unwrap();
+        for side in ["a", "b"] {
+            let dir = tmp.path().join(side);
+            fs::create_dir_all(dir.join("nested")).unwrap();
+            fs::write(dir.join("SKILL.md"), "skill").unwrap();
+            fs::write(dir.join("nested/helper.py"), "print()").unwrap();
+        }
+
+        let a = DirectoryComparator::tree_hash(&tmp.path().join("a")).unwrap();
+
+        assert_eq!(a, DirectoryComparator::tree_hash(&tmp.path().join("a")).unwrap());
+        assert_eq!(a, DirectoryComparator::tree_hash(&tmp.path().join("b")).unwrap());
+    }
+
+    #[test]
+    fn test_tree_hash_detects_edits_and_renames() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        fs::write(dir.join("SKILL.md"), "skill").unwrap();
+        let original = DirectoryComparator::tree_hash(dir).unwrap();
+
+        fs::write(dir.join("SKILL.md"), "edited").unwrap();
+        let edited = DirectoryComparator::tree_hash(dir).unwrap();
+        assert_ne!(edited, original);
+
+        fs::rename(dir.join("SKILL.md"), dir.join("README.md")).unwrap();
+        assert_ne!(DirectoryComparator::tree_hash(dir).unwrap(), edited);
+    }
 }
@@ -0,0 +1,114 @@
+//! Semantic comparison of markdown files with YAML frontmatter
+//!
+//! Agent and command files start with a `---` delimited YAML block. Two files
+//! whose frontmatter differs only in key order describe the same thing, so
+//! the block is compared as parsed YAML while the body stays byte-for-byte.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use serde_yaml::Value;
+
+use crate::error::Result;
+
+/// Line that opens and closes a frontmatter block
+const DELIMITER: &str = "---";
+
+/// Compares files by parsed frontmatter and exact body
+pub struct FrontmatterComparator;
+
+impl FrontmatterComparator {
+    /// Whether two files are equal once their frontmatter is parsed
+    ///
+    /// Files without frontmatter, with frontmatter that is not valid YAML, or
+    /// that are not UTF-8 are equal only if their bytes are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either file cannot be read.
+    pub fn equivalent(a: &Path, b: &Path) -> Result<bool> {
+        let a = fs::read(a).with_context(|| format!("Failed to read file: {}", a.display()))?;
+        let b = fs::read(b).with_context(|| format!("Failed to read file: {}", b.display()))?;
+        Ok(Self::equivalent_content(&a, &b))
+    }
+
+    /// Whether two file contents are equal once their frontmatter is parsed
+    #[must_use]
+    pub fn equivalent_content(a: &[u8], b: &[u8]) -> bool {
+        if a == b {
+            return true;
+        }
+        let (Ok(a), Ok(b)) = (std::str::from_utf8(a), std::str::from_utf8(b)) else {
+            return false;
+        };
+        let (Some((a_yaml, a_body)), Some((b_yaml, b_body))) = (split(a), split(b)) else {
+            return false;
+        };
+        if a_body != b_body {
+            return false;
+        }
+
+        match (
+            serde_yaml::from_str::<Value>(a_yaml),
+            serde_yaml::from_str::<Value>(b_yaml),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Split content into its frontmatter and the body after the closing line
+fn split(content: &str) -> Option<(&str, &str)> {
+    let rest = content
+        .strip_prefix(DELIMITER)?
+        .strip_prefix('\n')
+        .or_else(|| content.strip_prefix("---\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end_matches(['\r', '\n']) == DELIMITER {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reordered_frontmatter_is_equivalent() {
+        let a = "---\nname: reviewer\ntools: [Read, Grep]\nmodel:\n  id: x\n  temp: 0\n---\nBody\n";
+        let b = "---\nmodel:\n  temp: 0\n  id: x\ntools: [Read, Grep]\nname: reviewer\n---\nBody\n";
+
+        assert!(FrontmatterComparator::equivalent_content(a.as_bytes(), b.as_bytes()));
+    }
+
+    #[test]
+    fn test_different_frontmatter_or_body_is_not_equivalent() {
+        let base = "---\nname: reviewer\ntools: [Read, Grep]\n---\nBody\n";
+        let other_value = "---\nname: writer\ntools: [Read, Grep]\n---\nBody\n";
+        let other_order = "---\nname: reviewer\ntools: [Grep, Read]\n---\nBody\n";
+        let other_body = "---\ntools: [Read, Grep]\nname: reviewer\n---\nBody!\n";
+
+        for other in [other_value, other_order, other_body] {
+            assert!(!FrontmatterComparator::equivalent_content(
+                base.as_bytes(),
+                other.as_bytes()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_files_without_frontmatter_compare_bytes() {
+        assert!(!FrontmatterComparator::equivalent_content(b"a: 1\nb: 2\n", b"b: 2\na: 1\n"));
+        assert!(!FrontmatterComparator::equivalent_content(
+            b"---\na: 1\nb: 2\n",
+            b"---\nb: 2\na: 1\n"
+        ));
+    }
+}
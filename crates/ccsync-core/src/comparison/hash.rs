@@ -12,6 +12,12 @@ use crate::error::Result;
 /// File hash result
 pub type FileHash = [u8; 32];
 
+/// SHA-256 of empty input, returned for zero-byte files without reading them
+pub const EMPTY_HASH: FileHash = [
+    0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f, 0xb9, 0x24,
+    0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52, 0xb8, 0x55,
+];
+
 /// File hasher
 pub struct FileHasher;
 
@@ -30,10 +36,20 @@ impl FileHasher {
 
     /// Compute SHA-256 hash of a file by streaming its contents
     ///
+    /// Regular files that are empty according to their metadata hash to
+    /// [`EMPTY_HASH`] without being opened. Holes in sparse files read back
+    /// as zeros, so the logical content is hashed.
+    ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be read.
     pub fn hash(path: &Path) -> Result<FileHash> {
+        let metadata = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read metadata: {}", path.display()))?;
+        if metadata.is_file() && metadata.len() == 0 {
+            return Ok(EMPTY_HASH);
+        }
+
         let file = File::open(path)
             .with_context(|| format!("Failed to open file for hashing: {}", path.display()))?;
 
@@ -55,6 +71,32 @@ impl FileHasher {
 
         Ok(hasher.finalize().into())
     }
+
+    /// Check whether two files have identical content
+    ///
+    /// Files of different sizes are reported as different without being read;
+    /// only equal-sized, non-empty files are hashed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either file cannot be inspected or read.
+    pub fn same_content(a: &Path, b: &Path) -> Result<bool> {
+        let len = file_len(a)?;
+        if len != file_len(b)? {
+            return Ok(false);
+        }
+        if len == 0 {
+            return Ok(true);
+        }
+
+        Ok(Self::hash(a)? == Self::hash(b)?)
+    }
+}
+
+fn file_len(path: &Path) -> Result<u64> {
+    let metadata = std::fs::metadata(path)
+        .with_context(|| format!("Failed to read metadata: {}", path.display()))?;
+    Ok(metadata.len())
 }
 
 #[cfg(test)]
@@ -117,8 +159,61 @@ mod tests {
         fs::write(&file, "").unwrap();
 
         let _hasher = FileHasher::new();
-        let hash = FileHasher::hash(&file);
+        let hash = FileHasher::hash(&file).unwrap();
 
-        assert!(hash.is_ok());
+        assert_eq!(hash, EMPTY_HASH);
+        assert_eq!(hash, <[u8; 32]>::from(Sha256::digest(b"")));
+    }
+
+    #[test]
+    fn test_hash_matches_sha256_of_content() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("hello.txt");
+        fs::write(&file, "hello").unwrap();
+
+        let hash = FileHasher::hash(&file).unwrap();
+
+        assert_eq!(hash, <[u8; 32]>::from(Sha256::digest(b"hello")));
+        assert_eq!(hash[..4], [0x2c, 0xf2, 0x4d, 0xba]);
+    }
+
+    #[test]
+    fn test_sparse_file_hashes_its_logical_content() {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let tmp = TempDir::new().unwrap();
+        let sparse = tmp.path().join("sparse.bin");
+        let dense = tmp.path().join("dense.bin");
+
+        // A hole followed by data, as created by seeking past the end
+        let mut file = File::create(&sparse).unwrap();
+        file.seek(SeekFrom::Start(1024 * 1024)).unwrap();
+        file.write_all(b"tail").unwrap();
+        drop(file);
+        let mut content = vec![0u8; 1024 * 1024];
+        content.extend_from_slice(b"tail");
+        fs::write(&dense, &content).unwrap();
+
+        assert_eq!(
+            FileHasher::hash(&sparse).unwrap(),
+            FileHasher::hash(&dense).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_same_content_compares_sizes_then_hashes() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("a.txt");
+        let b = tmp.path().join("b.txt");
+        let c = tmp.path().join("c.txt");
+        let d = tmp.path().join("d.txt");
+        fs::write(&a, "same").unwrap();
+        fs::write(&b, "same").unwrap();
+        fs::write(&c, "diff").unwrap();
+        fs::write(&d, "longer content").unwrap();
+
+        assert!(FileHasher::same_content(&a, &b).unwrap());
+        assert!(!FileHasher::same_content(&a, &c).unwrap());
+        assert!(!FileHasher::same_content(&a, &d).unwrap());
     }
 }
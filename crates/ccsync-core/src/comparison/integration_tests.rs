@@ -53,6 +53,17 @@ fn test_compare_destination_only() {
     assert_eq!(result, ComparisonResult::DestinationOnly);
 }
 
+#[test]
+fn test_compare_neither_exists() {
+    let tmp = TempDir::new().unwrap();
+    let source = tmp.path().join("source.txt");
+    let dest = tmp.path().join("dest.txt");
+
+    let result = FileComparator::compare(&source, &dest, ConflictStrategy::Fail).unwrap();
+
+    assert_eq!(result, ComparisonResult::NeitherExists);
+}
+
 #[test]
 fn test_compare_conflict_source_newer() {
     let tmp = TempDir::new().unwrap();
@@ -122,6 +133,7 @@ fn test_diff_generation_with_changes() {
     fs::write(&dest, "line 1\nline 2\nline 3\n").unwrap();
     fs::write(&source, "line 1\nmodified line 2\nline 3\n").unwrap();
 
+    crate::color::set_mode(crate::color::ColorMode::Always);
     let _comparator = FileComparator::new();
     let diff = FileComparator::generate_diff(&source, &dest).unwrap();
 
@@ -183,3 +195,39 @@ fn test_multiple_comparisons() {
 
     assert_eq!(result1, result2);
 }
+
+#[test]
+fn test_compare_same_size_different_content_is_conflict() {
+    let tmp = TempDir::new().unwrap();
+    let source = tmp.path().join("source.txt");
+    let dest = tmp.path().join("dest.txt");
+
+    fs::write(&source, "content A").unwrap();
+    fs::write(&dest, "content B").unwrap();
+
+    let result = FileComparator::compare(&source, &dest, ConflictStrategy::Fail).unwrap();
+
+    assert!(matches!(result, ComparisonResult::Conflict { .. }));
+}
+
+#[test]
+fn test_compare_different_sizes_short_circuits_to_conflict() {
+    let tmp = TempDir::new().unwrap();
+    let source = tmp.path().join("source.txt");
+    let dest = tmp.path().join("dest.txt");
+
+    fs::write(&source, "short").unwrap();
+    thread::sleep(Duration::from_millis(10));
+    fs::write(&dest, "a much longer destination").unwrap();
+
+    let result = FileComparator::compare(&source, &dest, ConflictStrategy::Newer).unwrap();
+
+    // The timestamp check still runs after the size mismatch
+    assert_eq!(
+        result,
+        ComparisonResult::Conflict {
+            source_newer: false,
+            strategy: ConflictStrategy::Newer,
+        }
+    );
+}
@@ -0,0 +1,130 @@
+//! Rename detection for directory comparisons
+//!
+//! A file that disappears from the destination while a content-identical file
+//! appears in the source is treated as a rename rather than a removal plus an
+//! addition, so the destination copy can be moved in place instead of copied.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+
+use super::hash::{FileHash, FileHasher};
+
+/// A file moved from one relative path to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    /// Previous path, relative to the destination root
+    pub from: PathBuf,
+    /// New path, relative to the source root
+    pub to: PathBuf,
+}
+
+/// Pairs added and removed files with identical content
+pub struct RenameDetector;
+
+impl RenameDetector {
+    /// Match added source files against removed destination files by hash
+    ///
+    /// Matched paths are taken out of `added` and `removed` and returned as
+    /// renames. Each removed file is matched at most once; pairing follows
+    /// sorted path order so results are deterministic.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any candidate file cannot be hashed.
+    pub fn detect(
+        source: &Path,
+        destination: &Path,
+        added: &mut Vec<PathBuf>,
+        removed: &mut Vec<PathBuf>,
+    ) -> Result<Vec<Rename>> {
+        if added.is_empty() || removed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        added.sort();
+        removed.sort();
+
+        let mut candidates: HashMap<FileHash, Vec<PathBuf>> = HashMap::new();
+        for rel_path in removed.iter().rev() {
+            let hash = FileHasher::hash(&destination.join(rel_path))?;
+            candidates.entry(hash).or_default().push(rel_path.clone());
+        }
+
+        let mut renames = Vec::new();
+        let mut remaining_added = Vec::with_capacity(added.len());
+        for rel_path in added.drain(..) {
+            let hash = FileHasher::hash(&source.join(&rel_path))?;
+            match candidates.get_mut(&hash).and_then(Vec::pop) {
+                Some(from) => renames.push(Rename { from, to: rel_path }),
+                None => remaining_added.push(rel_path),
+            }
+        }
+        *added = remaining_added;
+        removed.retain(|path| !renames.iter().any(|rename| &rename.from == path));
+
+        Ok(renames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_pairs_identical_content() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dst = tmp.path().join("dst");
+        fs::create_dir(&src).unwrap();
+        fs::create_dir(&dst).unwrap();
+
+        fs::write(src.join("new-name.md"), "same content").unwrap();
+        fs::write(dst.join("old-name.md"), "same content").unwrap();
+        fs::write(src.join("fresh.md"), "fresh").unwrap();
+        fs::write(dst.join("stale.md"), "stale").unwrap();
+
+        let mut added = vec![PathBuf::from("new-name.md"), PathBuf::from("fresh.md")];
+        let mut removed = vec![PathBuf::from("old-name.md"), PathBuf::from("stale.md")];
+
+        let renames = RenameDetector::detect(&src, &dst, &mut added, &mut removed).unwrap();
+
+        assert_eq!(
+            renames,
+            vec![Rename {
+                from: PathBuf::from("old-name.md"),
+                to: PathBuf::from("new-name.md"),
+            }]
+        );
+        assert_eq!(added, vec![PathBuf::from("fresh.md")]);
+        assert_eq!(removed, vec![PathBuf::from("stale.md")]);
+    }
+
+    #[test]
+    fn test_detect_matches_each_removed_file_once() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dst = tmp.path().join("dst");
+        fs::create_dir(&src).unwrap();
+        fs::create_dir(&dst).unwrap();
+
+        fs::write(src.join("a.md"), "dup").unwrap();
+        fs::write(src.join("b.md"), "dup").unwrap();
+        fs::write(dst.join("old.md"), "dup").unwrap();
+
+        let mut added = vec![PathBuf::from("b.md"), PathBuf::from("a.md")];
+        let mut removed = vec![PathBuf::from("old.md")];
+
+        let renames = RenameDetector::detect(&src, &dst, &mut added, &mut removed).unwrap();
+
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].to, PathBuf::from("a.md"));
+        assert_eq!(added, vec![PathBuf::from("b.md")]);
+        assert!(removed.is_empty());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! This module handles:
 //! - Config file discovery from multiple locations
-//! - TOML parsing with serde
+//! - TOML and YAML parsing with serde
 //! - Config merging with precedence rules
 //! - Gitignore-style pattern matching
 //! - Direction and type-specific rules
@@ -11,17 +11,19 @@
 mod discovery;
 mod merge;
 mod patterns;
+mod rules;
 mod types;
 mod validation;
 
 #[cfg(test)]
 mod integration_tests;
 
-pub use discovery::ConfigDiscovery;
+pub use discovery::{ConfigDiscovery, ConfigFiles};
 pub use merge::ConfigMerger;
 #[allow(unused_imports)] // Will be used by sync engine (Task 6)
-pub use patterns::PatternMatcher;
-pub use types::{Config, SyncDirection};
+pub use patterns::{IGNORE_FILE, PatternDecision, PatternMatcher};
+pub use rules::RuleMatcher;
+pub use types::{Config, FileType, OutputFormat, SyncDirection, SyncRule};
 pub use validation::ConfigValidator;
 
 use crate::error::Result;
@@ -38,15 +40,26 @@ impl ConfigManager {
 
     /// Load and merge configuration from all sources
     ///
+    /// If `profile` is given, the named profile is overlaid on the merged config.
+    ///
     /// # Errors
     ///
-    /// Returns an error if config files are invalid or cannot be read.
-    pub fn load(cli_config_path: Option<&std::path::Path>) -> Result<Config> {
+    /// Returns an error if config files are invalid or cannot be read, or if
+    /// the requested profile is not defined.
+    pub fn load(
+        cli_config_path: Option<&std::path::Path>,
+        profile: Option<&str>,
+    ) -> Result<Config> {
         // Discover all config files
         let config_files = ConfigDiscovery::discover(cli_config_path)?;
 
         // Parse and merge configs
-        let merged = ConfigMerger::merge(&config_files)?;
+        let mut merged = ConfigMerger::merge(&config_files)?;
+
+        // Overlay the selected profile last so it wins over every file
+        if let Some(name) = profile {
+            ConfigMerger::apply_profile(&mut merged, name)?;
+        }
 
         // Validate the final configuration
         ConfigValidator::validate(&merged)?;
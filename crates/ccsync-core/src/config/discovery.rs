@@ -9,14 +9,27 @@ use crate::error::Result;
 pub struct ConfigFiles {
     /// Config from CLI flag (highest precedence)
     pub cli: Option<PathBuf>,
-    /// Project-local config (.ccsync.local.toml)
+    /// Project-local config (.ccsync.local.toml or .ccsync.local.yaml)
     pub local: Option<PathBuf>,
-    /// Project config (.ccsync.toml)
+    /// Project config (.ccsync.toml or .ccsync.yaml)
     pub project: Option<PathBuf>,
     /// Global XDG config
     pub global: Option<PathBuf>,
 }
 
+/// Accepted file names for the project-local config
+const LOCAL_CONFIG_NAMES: &[&str] = &[
+    ".ccsync.local.toml",
+    ".ccsync.local.yaml",
+    ".ccsync.local.yml",
+];
+
+/// Accepted file names for the project config
+const PROJECT_CONFIG_NAMES: &[&str] = &[".ccsync.toml", ".ccsync.yaml", ".ccsync.yml"];
+
+/// Accepted file names for the global config (inside the XDG `ccsync` directory)
+const GLOBAL_CONFIG_NAMES: &[&str] = &["config.toml", "config.yaml", "config.yml"];
+
 /// Config file discovery
 pub struct ConfigDiscovery;
 
@@ -39,7 +52,8 @@ impl ConfigDiscovery {
     ///
     /// # Errors
     ///
-    /// Returns an error if a CLI config path is specified but doesn't exist.
+    /// Returns an error if a CLI config path is specified but doesn't exist,
+    /// or if both a TOML and a YAML config exist at the same level.
     pub fn discover(cli_path: Option<&Path>) -> Result<ConfigFiles> {
         let cli = if let Some(p) = cli_path {
             if !p.exists() {
@@ -53,9 +67,9 @@ impl ConfigDiscovery {
             None
         };
 
-        let local = Self::find_file(".ccsync.local.toml");
-        let project = Self::find_file(".ccsync.toml");
-        let global = Self::find_global_config();
+        let local = Self::find_file(LOCAL_CONFIG_NAMES)?;
+        let project = Self::find_file(PROJECT_CONFIG_NAMES)?;
+        let global = Self::find_global_config()?;
 
         Ok(ConfigFiles {
             cli,
@@ -67,18 +81,17 @@ impl ConfigDiscovery {
 
     /// Find a config file in the current directory or parent directories
     ///
+    /// The nearest directory containing any of `names` wins.
+    ///
     /// Note: Does not follow symlinks for security reasons
-    fn find_file(name: &str) -> Option<PathBuf> {
-        let mut current = std::env::current_dir().ok()?;
+    fn find_file(names: &[&str]) -> Result<Option<PathBuf>> {
+        let Ok(mut current) = std::env::current_dir() else {
+            return Ok(None);
+        };
 
         loop {
-            let candidate = current.join(name);
-
-            // Use symlink_metadata to avoid following symlinks (security)
-            if let Ok(metadata) = candidate.symlink_metadata()
-                && metadata.is_file()
-            {
-                return Some(candidate);
+            if let Some(found) = Self::find_in_dir(&current, names)? {
+                return Ok(Some(found));
             }
 
             // Move to parent directory
@@ -87,24 +100,51 @@ impl ConfigDiscovery {
             }
         }
 
-        None
+        Ok(None)
     }
 
     /// Find global config in XDG config directory
     ///
     /// Note: Does not follow symlinks for security reasons
-    fn find_global_config() -> Option<PathBuf> {
-        let config_dir = dirs::config_dir()?;
-        let global_config = config_dir.join("ccsync").join("config.toml");
-
-        // Use symlink_metadata to avoid following symlinks (security)
-        if let Ok(metadata) = global_config.symlink_metadata()
-            && metadata.is_file()
-        {
-            return Some(global_config);
-        }
+    fn find_global_config() -> Result<Option<PathBuf>> {
+        let Some(config_dir) = dirs::config_dir() else {
+            return Ok(None);
+        };
+
+        Self::find_in_dir(&config_dir.join("ccsync"), GLOBAL_CONFIG_NAMES)
+    }
 
-        None
+    /// Find exactly one of `names` in a single directory
+    ///
+    /// TOML and YAML variants are mutually exclusive at the same level: rather
+    /// than silently picking one, discovery fails so the choice is explicit.
+    ///
+    /// Note: Does not follow symlinks for security reasons
+    fn find_in_dir(dir: &Path, names: &[&str]) -> Result<Option<PathBuf>> {
+        let found: Vec<PathBuf> = names
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|candidate| {
+                // Use symlink_metadata to avoid following symlinks (security)
+                candidate
+                    .symlink_metadata()
+                    .is_ok_and(|metadata| metadata.is_file())
+            })
+            .collect();
+
+        match found.as_slice() {
+            [] => Ok(None),
+            [single] => Ok(Some(single.clone())),
+            [first, rest @ ..] => {
+                let others: Vec<String> = rest.iter().map(|p| p.display().to_string()).collect();
+                anyhow::bail!(
+                    "Ambiguous config: {} conflicts with {} in the same directory; \
+                     keep only one (TOML and YAML configs cannot be mixed at the same level)",
+                    first.display(),
+                    others.join(", ")
+                )
+            }
+        }
     }
 }
 
@@ -148,6 +188,31 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("does not exist"));
     }
 
+    #[test]
+    fn test_find_in_dir_yaml() {
+        let tmp = TempDir::new().unwrap();
+        let yaml = tmp.path().join(".ccsync.yaml");
+        fs::write(&yaml, "ignore: []").unwrap();
+
+        let found = ConfigDiscovery::find_in_dir(tmp.path(), PROJECT_CONFIG_NAMES).unwrap();
+
+        assert_eq!(found, Some(yaml));
+    }
+
+    #[test]
+    fn test_find_in_dir_toml_and_yaml_is_ambiguous() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join(".ccsync.toml"), "").unwrap();
+        fs::write(tmp.path().join(".ccsync.yml"), "").unwrap();
+
+        let result = ConfigDiscovery::find_in_dir(tmp.path(), PROJECT_CONFIG_NAMES);
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Ambiguous config"));
+        assert!(err.contains(".ccsync.toml"));
+        assert!(err.contains(".ccsync.yml"));
+    }
+
     // Note: Tests for find_file() that search from current directory are omitted
     // to avoid test environment pollution from std::env::set_current_dir().
     // The find_file() function is tested implicitly through the discover() tests
@@ -31,7 +31,7 @@ include = true
     .unwrap();
 
     let _manager = ConfigManager::new();
-    let config = ConfigManager::load(Some(&config_file)).unwrap();
+    let config = ConfigManager::load(Some(&config_file), None).unwrap();
 
     // Check that expected patterns are present (may include additional patterns from global config)
     assert!(config.ignore.contains(&"*.tmp".to_string()));
@@ -57,7 +57,7 @@ preserve_symlinks = true
     .unwrap();
 
     let _manager = ConfigManager::new();
-    let result = ConfigManager::load(Some(&config_file));
+    let result = ConfigManager::load(Some(&config_file), None);
 
     assert!(result.is_err());
     assert!(
@@ -75,9 +75,27 @@ fn test_config_with_rules() {
         include: vec![],
         follow_symlinks: Some(false),
         preserve_symlinks: Some(false),
+        hardlink_identical: None,
+        preserve_ownership: None,
         dry_run: Some(false),
         non_interactive: Some(false),
+        include_hidden: None,
+        max_file_size: None,
+        copy_retries: None,
+        track_baseline: None,
+        skill_marker: None,
+        command_extensions: None,
+        semantic_frontmatter: None,
+        jobs: None,
+        pre_sync: None,
+        post_sync: None,
+        output_format: None,
         conflict_strategy: None,
+        modified_since: None,
+        path_match: Vec::new(),
+        exclude: Vec::new(),
+        show_diff: None,
+        prune: None,
         rules: vec![
             SyncRule {
                 patterns: vec!["agents/*.md".to_string()],
@@ -92,9 +110,42 @@ fn test_config_with_rules() {
                 include: false,
             },
         ],
+        profiles: std::collections::BTreeMap::new(),
     };
 
     assert_eq!(config.rules.len(), 2);
     assert_eq!(config.rules[0].patterns[0], "agents/*.md");
     assert_eq!(config.rules[1].file_type, Some(FileType::Binary));
 }
+
+#[test]
+fn test_load_with_profile() {
+    let tmp = TempDir::new().unwrap();
+    let config_file = tmp.path().join("config.toml");
+
+    fs::write(
+        &config_file,
+        r#"
+ignore = ["*.tmp"]
+
+[profiles.work]
+ignore = ["personal/**"]
+preserve_symlinks = true
+"#,
+    )
+    .unwrap();
+
+    let config = ConfigManager::load(Some(&config_file), Some("work")).unwrap();
+    assert!(config.ignore.contains(&"*.tmp".to_string()));
+    assert!(config.ignore.contains(&"personal/**".to_string()));
+    assert_eq!(config.preserve_symlinks, Some(true));
+
+    let result = ConfigManager::load(Some(&config_file), Some("missing"));
+    assert!(result.is_err());
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown config profile 'missing'")
+    );
+}
@@ -14,6 +14,24 @@
 //! 4. CLI config (--config flag)
 //!
 //! Higher precedence configs fully override boolean values from lower precedence configs.
+//!
+//! # Profiles
+//!
+//! `[profiles.<name>]` tables are collected from every config file (profiles with
+//! the same name are merged using the rules above). A selected profile is applied
+//! last, on top of the fully merged base config, with the same additive/override
+//! semantics.
+//!
+//! # Expansion
+//!
+//! `ignore`/`include` entries (including those in rules and profiles) that start
+//! with `~` or reference `$VARS` are expanded when the file is loaded. An unset
+//! variable is a load error rather than a literal `$VAR` pattern.
+//!
+//! # Formats
+//!
+//! Each file is parsed as YAML when its extension is `.yaml` or `.yml`, and as
+//! TOML otherwise. Both formats deserialize into the same `Config` type.
 
 use std::fs;
 use std::path::Path;
@@ -92,9 +110,39 @@ impl ConfigMerger {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-        let config: Config = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        let mut config = Self::parse(&content, path)?;
+        Self::expand_patterns(&mut config)
+            .with_context(|| format!("Failed to expand config file: {}", path.display()))?;
+
+        Self::overlay(base, config);
+
+        Ok(())
+    }
+
+    /// Apply a named profile on top of an already merged config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no profile with the given name is defined.
+    pub fn apply_profile(config: &mut Config, name: &str) -> Result<()> {
+        let Some(profile) = config.profiles.get(name).cloned() else {
+            let available: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+            if available.is_empty() {
+                anyhow::bail!("Unknown config profile '{name}': no profiles are defined");
+            }
+            anyhow::bail!(
+                "Unknown config profile '{name}' (available: {})",
+                available.join(", ")
+            );
+        };
+
+        Self::overlay(config, profile);
 
+        Ok(())
+    }
+
+    /// Overlay a higher-precedence config onto the base
+    fn overlay(base: &mut Config, config: Config) {
         // Merge: additive for arrays (with deduplication), override for Option<bool>
         base.ignore.extend(config.ignore);
         base.ignore.sort();
@@ -113,15 +161,107 @@ impl ConfigMerger {
         if config.preserve_symlinks.is_some() {
             base.preserve_symlinks = config.preserve_symlinks;
         }
+        if config.hardlink_identical.is_some() {
+            base.hardlink_identical = config.hardlink_identical;
+        }
+        if config.preserve_ownership.is_some() {
+            base.preserve_ownership = config.preserve_ownership;
+        }
         if config.dry_run.is_some() {
             base.dry_run = config.dry_run;
         }
         if config.non_interactive.is_some() {
             base.non_interactive = config.non_interactive;
         }
+        if config.include_hidden.is_some() {
+            base.include_hidden = config.include_hidden;
+        }
+        if config.max_file_size.is_some() {
+            base.max_file_size = config.max_file_size;
+        }
+        if config.copy_retries.is_some() {
+            base.copy_retries = config.copy_retries;
+        }
+        if config.track_baseline.is_some() {
+            base.track_baseline = config.track_baseline;
+        }
+        if config.skill_marker.is_some() {
+            base.skill_marker = config.skill_marker;
+        }
+        if config.semantic_frontmatter.is_some() {
+            base.semantic_frontmatter = config.semantic_frontmatter;
+        }
+        if config.jobs.is_some() {
+            base.jobs = config.jobs;
+        }
+        if config.pre_sync.is_some() {
+            base.pre_sync = config.pre_sync;
+        }
+        if config.post_sync.is_some() {
+            base.post_sync = config.post_sync;
+        }
+        if config.output_format.is_some() {
+            base.output_format = config.output_format;
+        }
+        if config.command_extensions.is_some() {
+            base.command_extensions = config.command_extensions;
+        }
+
+        // Profiles with the same name are merged using the same rules
+        for (name, profile) in config.profiles {
+            Self::overlay(base.profiles.entry(name).or_default(), profile);
+        }
+    }
+
+    /// Expand `~` and environment variables in pattern values
+    fn expand_patterns(config: &mut Config) -> Result<()> {
+        let rule_patterns = config.rules.iter_mut().flat_map(|rule| &mut rule.patterns);
+        for pattern in config
+            .ignore
+            .iter_mut()
+            .chain(config.include.iter_mut())
+            .chain(rule_patterns)
+        {
+            *pattern = Self::expand(pattern)?;
+        }
+
+        for profile in config.profiles.values_mut() {
+            Self::expand_patterns(profile)?;
+        }
 
         Ok(())
     }
+
+    /// Expand a single value if it starts with `~` or references a variable
+    fn expand(value: &str) -> Result<String> {
+        if !value.starts_with('~') && !value.contains('$') {
+            return Ok(value.to_string());
+        }
+
+        shellexpand::full(value)
+            .map(std::borrow::Cow::into_owned)
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Cannot expand '{value}': environment variable '{}' is not set",
+                    e.var_name
+                )
+            })
+    }
+
+    /// Parse config content, choosing the format from the file extension
+    fn parse(content: &str, path: &Path) -> Result<Config> {
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+
+        if is_yaml {
+            serde_yaml::from_str(content)
+                .with_context(|| format!("Failed to parse YAML config file: {}", path.display()))
+        } else {
+            toml::from_str(content)
+                .with_context(|| format!("Failed to parse config file: {}", path.display()))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -199,6 +339,28 @@ follow_symlinks = true
         assert!(config.ignore.contains(&"*.log".to_string()));
     }
 
+    #[test]
+    fn test_unknown_key_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let toml = tmp.path().join(".ccsync");
+        fs::write(&toml, "ignroe = [\"*.tmp\"]\n").unwrap();
+        let yaml = tmp.path().join(".ccsync.yaml");
+        fs::write(&yaml, "rules:\n  - patterns: [\"agents/*\"]\n    incldue: false\n").unwrap();
+
+        for (path, key) in [(toml, "ignroe"), (yaml, "incldue")] {
+            let files = ConfigFiles {
+                cli: Some(path.clone()),
+                local: None,
+                project: None,
+                global: None,
+            };
+
+            let err = format!("{:#}", ConfigMerger::merge(&files).unwrap_err());
+            assert!(err.contains(&path.display().to_string()), "{err}");
+            assert!(err.contains(&format!("unknown field `{key}`")), "{err}");
+        }
+    }
+
     #[test]
     fn test_merge_boolean_override() {
         let tmp = TempDir::new().unwrap();
@@ -222,4 +384,235 @@ follow_symlinks = true
         // Project config should override global
         assert_eq!(config.follow_symlinks, Some(true));
     }
+
+    #[test]
+    fn test_merge_yaml_project_config() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join(".ccsync.yaml");
+        fs::write(
+            &project,
+            r#"
+ignore:
+  - "*.tmp"
+  - "agents/git-*"
+follow_symlinks: true
+rules:
+  - patterns: ["skills/**"]
+    direction: to-global
+    include: false
+"#,
+        )
+        .unwrap();
+
+        let files = ConfigFiles {
+            cli: None,
+            local: None,
+            project: Some(project),
+            global: None,
+        };
+
+        let config = ConfigMerger::merge(&files).unwrap();
+
+        assert_eq!(config.ignore, vec!["*.tmp", "agents/git-*"]);
+        assert_eq!(config.follow_symlinks, Some(true));
+        assert_eq!(config.rules.len(), 1);
+        assert_eq!(config.rules[0].patterns, vec!["skills/**"]);
+        assert_eq!(
+            config.rules[0].direction,
+            Some(crate::config::SyncDirection::ToGlobal)
+        );
+        assert!(!config.rules[0].include);
+    }
+
+    #[test]
+    fn test_merge_yaml_over_toml() {
+        let tmp = TempDir::new().unwrap();
+
+        let global = tmp.path().join("config.toml");
+        fs::write(&global, "ignore = [\"*.log\"]\nfollow_symlinks = false").unwrap();
+
+        let local = tmp.path().join(".ccsync.local.yml");
+        fs::write(&local, "ignore: [\"*.tmp\"]\nfollow_symlinks: true\n").unwrap();
+
+        let files = ConfigFiles {
+            cli: None,
+            local: Some(local),
+            project: None,
+            global: Some(global),
+        };
+
+        let config = ConfigMerger::merge(&files).unwrap();
+
+        assert_eq!(config.ignore, vec!["*.log", "*.tmp"]);
+        assert_eq!(config.follow_symlinks, Some(true));
+    }
+
+    #[test]
+    fn test_merge_invalid_yaml() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join(".ccsync.yaml");
+        fs::write(&project, "ignore: [unterminated").unwrap();
+
+        let files = ConfigFiles {
+            cli: None,
+            local: None,
+            project: Some(project),
+            global: None,
+        };
+
+        let result = ConfigMerger::merge(&files);
+
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Failed to parse YAML config file")
+        );
+    }
+
+    #[test]
+    fn test_apply_profile_overlay() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project.toml");
+        fs::write(
+            &project,
+            r#"
+ignore = ["*.tmp"]
+follow_symlinks = false
+dry_run = true
+
+[profiles.work]
+ignore = ["personal/**"]
+follow_symlinks = true
+
+[profiles.personal]
+ignore = ["work/**"]
+"#,
+        )
+        .unwrap();
+
+        let files = ConfigFiles {
+            cli: None,
+            local: None,
+            project: Some(project),
+            global: None,
+        };
+
+        let mut config = ConfigMerger::merge(&files).unwrap();
+        ConfigMerger::apply_profile(&mut config, "work").unwrap();
+
+        // Arrays are additive, booleans are overridden only when set by the profile
+        assert_eq!(config.ignore, vec!["*.tmp", "personal/**"]);
+        assert_eq!(config.follow_symlinks, Some(true));
+        assert_eq!(config.dry_run, Some(true));
+    }
+
+    #[test]
+    fn test_profiles_merge_across_files() {
+        let tmp = TempDir::new().unwrap();
+
+        let global = tmp.path().join("global.toml");
+        fs::write(
+            &global,
+            "[profiles.work]\nignore = [\"a/**\"]\ndry_run = false",
+        )
+        .unwrap();
+
+        let project = tmp.path().join("project.toml");
+        fs::write(
+            &project,
+            "[profiles.work]\nignore = [\"b/**\"]\ndry_run = true",
+        )
+        .unwrap();
+
+        let files = ConfigFiles {
+            cli: None,
+            local: None,
+            project: Some(project),
+            global: Some(global),
+        };
+
+        let mut config = ConfigMerger::merge(&files).unwrap();
+        ConfigMerger::apply_profile(&mut config, "work").unwrap();
+
+        assert_eq!(config.ignore, vec!["a/**", "b/**"]);
+        assert_eq!(config.dry_run, Some(true));
+    }
+
+    #[test]
+    fn test_apply_unknown_profile() {
+        let mut config = Config::default();
+        config
+            .profiles
+            .insert("work".to_string(), Config::default());
+
+        let result = ConfigMerger::apply_profile(&mut config, "home");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Unknown config profile 'home'"));
+        assert!(err.contains("available: work"));
+    }
+
+    #[test]
+    fn test_merge_expands_home_in_patterns() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project.toml");
+        fs::write(
+            &project,
+            r#"
+ignore = ["~/secrets/**", "*.tmp"]
+
+[[rules]]
+patterns = ["~/notes/*.md"]
+include = true
+"#,
+        )
+        .unwrap();
+
+        let files = ConfigFiles {
+            cli: None,
+            local: None,
+            project: Some(project),
+            global: None,
+        };
+
+        let config = ConfigMerger::merge(&files).unwrap();
+        let home = dirs::home_dir().unwrap();
+
+        assert!(
+            config
+                .ignore
+                .contains(&format!("{}/secrets/**", home.display()))
+        );
+        assert!(config.ignore.contains(&"*.tmp".to_string()));
+        assert_eq!(
+            config.rules[0].patterns,
+            vec![format!("{}/notes/*.md", home.display())]
+        );
+    }
+
+    #[test]
+    fn test_merge_unset_variable_errors() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project.toml");
+        fs::write(
+            &project,
+            r#"include = ["$CCSYNC_TEST_SURELY_UNSET_VARIABLE/**"]"#,
+        )
+        .unwrap();
+
+        let files = ConfigFiles {
+            cli: None,
+            local: None,
+            project: Some(project),
+            global: None,
+        };
+
+        let err = format!("{:#}", ConfigMerger::merge(&files).unwrap_err());
+
+        assert!(err.contains("Failed to expand config file"));
+        assert!(err.contains("CCSYNC_TEST_SURELY_UNSET_VARIABLE"));
+        assert!(err.contains("is not set"));
+    }
 }
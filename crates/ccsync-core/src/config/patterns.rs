@@ -1,30 +1,113 @@
 //! Gitignore-style pattern matching using the ignore crate
+//!
+//! Plain patterns such as `*.tmp` or `agents/git-*` are compiled into two
+//! `GlobSet`s (ignore and include), which is much cheaper to query than a
+//! `Gitignore` when there are many patterns. Patterns that rely on gitignore
+//! syntax (`/` anchoring, trailing `/` for directories, `!` or `#` prefixes)
+//! fall back to a `Gitignore`.
 
 use std::path::Path;
 
 use anyhow::Context;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::Match;
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
 use crate::error::Result;
 
+/// Name of the per-directory ignore file read during scanning
+pub const IGNORE_FILE: &str = ".ccsyncignore";
+
 /// Pattern matcher for file inclusion/exclusion
 pub struct PatternMatcher {
     gitignore: Option<Gitignore>,
+    globs: Option<GlobSets>,
+    /// Patterns checked before all others, which no include pattern overrides
+    excludes: Option<Box<Self>>,
+}
+
+/// Compiled ignore and include patterns for the fast path
+struct GlobSets {
+    ignore: GlobSet,
+    include: GlobSet,
+    /// Source of each glob, in set order, to name the one that matched
+    ignore_patterns: Vec<String>,
+    include_patterns: Vec<String>,
+}
+
+/// Which pattern, if any, decided whether a path is included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternDecision {
+    /// No pattern matched the path or its parents, so it is included
+    Unmatched,
+    /// Excluded by this ignore pattern
+    Ignored(String),
+    /// Included by this include pattern, overriding any ignore pattern
+    Included(String),
+}
+
+impl PatternDecision {
+    /// Whether the path is included
+    #[must_use]
+    pub const fn is_included(&self) -> bool {
+        !matches!(self, Self::Ignored(_))
+    }
 }
 
 impl PatternMatcher {
     /// Create a new pattern matcher
     #[must_use]
     pub const fn new() -> Self {
-        Self { gitignore: None }
+        Self {
+            gitignore: None,
+            globs: None,
+            excludes: None,
+        }
     }
 
     /// Build pattern matcher from ignore and include patterns
     ///
+    /// Include patterns re-include paths that an ignore pattern matched.
+    ///
     /// # Errors
     ///
     /// Returns an error if patterns are invalid.
     pub fn with_patterns(ignore_patterns: &[String], include_patterns: &[String]) -> Result<Self> {
+        if ignore_patterns.iter().all(|p| is_plain_glob(p))
+            && include_patterns.iter().all(|p| is_plain_glob(p))
+        {
+            let globs = GlobSets {
+                ignore: build_glob_set(ignore_patterns, "ignore")?,
+                include: build_glob_set(include_patterns, "include")?,
+                ignore_patterns: ignore_patterns.to_vec(),
+                include_patterns: include_patterns.to_vec(),
+            };
+            return Ok(Self {
+                gitignore: None,
+                globs: Some(globs),
+                excludes: None,
+            });
+        }
+
+        Self::with_gitignore(ignore_patterns, include_patterns)
+    }
+
+    /// Also exclude paths matching `patterns`, whatever the include patterns say
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern is invalid.
+    pub fn with_excludes(mut self, patterns: &[String]) -> Result<Self> {
+        self.excludes = if patterns.is_empty() {
+            None
+        } else {
+            Some(Box::new(Self::with_patterns(patterns, &[])?))
+        };
+        Ok(self)
+    }
+
+    /// Build a pattern matcher that always uses a `Gitignore`
+    fn with_gitignore(ignore_patterns: &[String], include_patterns: &[String]) -> Result<Self> {
         let mut builder = GitignoreBuilder::new("");
 
         // Add ignore patterns
@@ -45,16 +128,162 @@ impl PatternMatcher {
 
         Ok(Self {
             gitignore: Some(gitignore),
+            globs: None,
+            excludes: None,
         })
     }
 
+    /// Load the `.ccsyncignore` file in `dir`, if there is one
+    ///
+    /// Its patterns are anchored at `dir`, so they only apply to its subtree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or contains an invalid pattern.
+    pub fn from_ignore_file(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(IGNORE_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let mut builder = GitignoreBuilder::new(dir);
+        if let Some(err) = builder.add(&path) {
+            return Err(err).with_context(|| format!("Invalid {}", path.display()));
+        }
+        let gitignore = builder
+            .build()
+            .with_context(|| format!("Invalid {}", path.display()))?;
+
+        Ok(Some(Self {
+            gitignore: Some(gitignore),
+            globs: None,
+            excludes: None,
+        }))
+    }
+
+    /// Decide a path under an ignore file's directory, or one of its parents
+    ///
+    /// Returns `Some(false)` if the path is ignored, `Some(true)` if a `!`
+    /// pattern re-includes it, and `None` if no pattern applies.
+    #[must_use]
+    pub fn decide_in_subtree(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let gitignore = self.gitignore.as_ref()?;
+        if !path.starts_with(gitignore.path()) {
+            return None;
+        }
+
+        match gitignore.matched_path_or_any_parents(path, is_dir) {
+            Match::None => None,
+            Match::Ignore(_) => Some(false),
+            Match::Whitelist(_) => Some(true),
+        }
+    }
+
     /// Check if a path should be included based on patterns
+    ///
+    /// The path itself is matched first, then each parent directory; the
+    /// nearest match decides. So `skills/experimental/` excludes the skill
+    /// directory and every file in it, while an include pattern for a file
+    /// inside still re-includes that file. A trailing `/` only matches
+    /// directories, which is what `is_dir` tells apart. Paths matching one of
+    /// [`Self::with_excludes`] are excluded before any of that.
     #[must_use]
     pub fn should_include(&self, path: &Path, is_dir: bool) -> bool {
+        if self
+            .excludes
+            .as_ref()
+            .is_some_and(|excludes| !excludes.should_include(path, is_dir))
+        {
+            return false;
+        }
+        if let Some(globs) = &self.globs {
+            let path = path.strip_prefix(".").unwrap_or(path);
+            for candidate in path.ancestors().take_while(|p| !p.as_os_str().is_empty()) {
+                if globs.include.is_match(candidate) {
+                    return true;
+                }
+                if globs.ignore.is_match(candidate) {
+                    return false;
+                }
+            }
+            return true;
+        }
+
         self.gitignore
             .as_ref()
-            .is_none_or(|gi| !gi.matched(path, is_dir).is_ignore())
+            .is_none_or(|gi| !gi.matched_path_or_any_parents(path, is_dir).is_ignore())
     }
+
+    /// Decide a path like [`Self::should_include`], naming the pattern that
+    /// decided it
+    ///
+    /// When several patterns match the same path, the last one listed wins.
+    #[must_use]
+    pub fn decide(&self, path: &Path, is_dir: bool) -> PatternDecision {
+        if let Some(excludes) = &self.excludes {
+            let decision = excludes.decide(path, is_dir);
+            if !decision.is_included() {
+                return decision;
+            }
+        }
+        if let Some(globs) = &self.globs {
+            let path = path.strip_prefix(".").unwrap_or(path);
+            for candidate in path.ancestors().take_while(|p| !p.as_os_str().is_empty()) {
+                if let Some(&index) = globs.include.matches(candidate).last() {
+                    return PatternDecision::Included(globs.include_patterns[index].clone());
+                }
+                if let Some(&index) = globs.ignore.matches(candidate).last() {
+                    return PatternDecision::Ignored(globs.ignore_patterns[index].clone());
+                }
+            }
+            return PatternDecision::Unmatched;
+        }
+
+        let Some(gitignore) = &self.gitignore else {
+            return PatternDecision::Unmatched;
+        };
+        match gitignore.matched_path_or_any_parents(path, is_dir) {
+            Match::None => PatternDecision::Unmatched,
+            Match::Ignore(glob) => PatternDecision::Ignored(glob.original().to_string()),
+            Match::Whitelist(glob) => {
+                let pattern = glob.original();
+                PatternDecision::Included(pattern.strip_prefix('!').unwrap_or(pattern).to_string())
+            }
+        }
+    }
+}
+
+/// Whether a pattern means the same as a glob, without gitignore-only syntax
+fn is_plain_glob(pattern: &str) -> bool {
+    !pattern.is_empty()
+        && pattern.trim() == pattern
+        && !pattern.starts_with(['/', '!', '#', '\\'])
+        && !pattern.ends_with('/')
+}
+
+/// Compile plain patterns the way `Gitignore` does
+///
+/// Patterns without a `/` match at any depth, and a trailing `/**` only
+/// matches inside the directory.
+fn build_glob_set(patterns: &[String], kind: &str) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let mut glob = pattern.clone();
+        if !glob.contains('/') {
+            glob = format!("**/{glob}");
+        }
+        if glob.ends_with("/**") {
+            glob.push_str("/*");
+        }
+        let glob = GlobBuilder::new(&glob)
+            .literal_separator(true)
+            .backslash_escape(true)
+            .allow_unclosed_class(true)
+            .build()
+            .with_context(|| format!("Invalid {kind} pattern: '{pattern}'"))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
 }
 
 impl Default for PatternMatcher {
@@ -100,6 +329,32 @@ mod tests {
         assert!(matcher.should_include(&PathBuf::from("src"), true));
     }
 
+    #[test]
+    fn test_directory_patterns_cover_their_contents() {
+        let slash = PatternMatcher::with_patterns(&["skills/experimental/".to_string()], &[]).unwrap();
+        let plain = PatternMatcher::with_patterns(&["skills/experimental".to_string()], &[]).unwrap();
+
+        for matcher in [&slash, &plain] {
+            assert!(!matcher.should_include(Path::new("skills/experimental"), true));
+            assert!(!matcher.should_include(Path::new("skills/experimental/SKILL.md"), false));
+            assert!(!matcher.should_include(Path::new("skills/experimental/a/b.md"), false));
+            assert!(matcher.should_include(Path::new("skills/experimental-2/SKILL.md"), false));
+        }
+
+        // A trailing slash only matches directories
+        assert!(slash.should_include(Path::new("skills/experimental"), false));
+        assert!(!plain.should_include(Path::new("skills/experimental"), false));
+
+        // An include pattern still re-includes a path inside an ignored directory
+        let matcher = PatternMatcher::with_patterns(
+            &["skills/experimental".to_string()],
+            &["skills/experimental/SKILL.md".to_string()],
+        )
+        .unwrap();
+        assert!(matcher.should_include(Path::new("skills/experimental/SKILL.md"), false));
+        assert!(!matcher.should_include(Path::new("skills/experimental/notes.md"), false));
+    }
+
     #[test]
     fn test_relative_path_wildcards() {
         let matcher = PatternMatcher::with_patterns(&["agents/git-*".to_string()], &[]).unwrap();
@@ -112,4 +367,152 @@ mod tests {
         assert!(matcher.should_include(&PathBuf::from("agents/other-agent.md"), false));
         assert!(matcher.should_include(&PathBuf::from("skills/test.md"), false));
     }
+
+    /// Check that the fast path decides like `Gitignore` for every path
+    fn assert_parity(ignore: &[&str], include: &[&str], paths: &[&str]) {
+        let ignore: Vec<String> = ignore.iter().map(ToString::to_string).collect();
+        let include: Vec<String> = include.iter().map(ToString::to_string).collect();
+        let fast = PatternMatcher::with_patterns(&ignore, &include).unwrap();
+        let slow = PatternMatcher::with_gitignore(&ignore, &include).unwrap();
+        assert!(fast.globs.is_some(), "expected the glob set fast path");
+
+        for path in paths {
+            let path = Path::new(path);
+            assert_eq!(
+                fast.should_include(path, false),
+                slow.should_include(path, false),
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn test_glob_set_matches_gitignore_on_existing_cases() {
+        let paths = [
+            "file.tmp",
+            "file.txt",
+            "important.tmp",
+            "nested/dir/file.tmp",
+            "nested/important.tmp",
+            "agents/git-commit.md",
+            "agents/git-helper.md",
+            "agents/other-agent.md",
+            "agents/sub/git-x.md",
+            "skills/test.md",
+            "./agents/git-commit.md",
+        ];
+        assert_parity(&["*.tmp"], &[], &paths);
+        assert_parity(&["*.tmp"], &["important.tmp"], &paths);
+        assert_parity(&["agents/git-*"], &[], &paths);
+        assert_parity(&["**/*.md"], &["agents/**"], &paths);
+        assert_parity(&["agents/**"], &["agents/git-[ch]*"], &paths);
+    }
+
+    #[test]
+    fn test_decide_names_the_deciding_pattern() {
+        let ignore = vec!["agents/git-*".to_string(), "*.tmp".to_string()];
+        let include = vec!["agents/git-commit.md".to_string()];
+        let fast = PatternMatcher::with_patterns(&ignore, &include).unwrap();
+        let slow = PatternMatcher::with_gitignore(&ignore, &include).unwrap();
+
+        for matcher in [fast, slow] {
+            let decide = |path: &str| matcher.decide(Path::new(path), false);
+            assert_eq!(
+                decide("agents/git-commit.md"),
+                PatternDecision::Included("agents/git-commit.md".to_string())
+            );
+            assert_eq!(
+                decide("agents/git-helper.md"),
+                PatternDecision::Ignored("agents/git-*".to_string())
+            );
+            assert_eq!(
+                decide("skills/notes/draft.tmp"),
+                PatternDecision::Ignored("*.tmp".to_string())
+            );
+            assert_eq!(decide("agents/reviewer.md"), PatternDecision::Unmatched);
+
+            for path in ["agents/git-commit.md", "agents/git-helper.md", "agents/reviewer.md"] {
+                let path = Path::new(path);
+                assert_eq!(
+                    matcher.decide(path, false).is_included(),
+                    matcher.should_include(path, false)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_excludes_override_include_patterns() {
+        let matcher = PatternMatcher::with_patterns(&[], &["agents/**".to_string()])
+            .unwrap()
+            .with_excludes(&["agents/git-*".to_string()])
+            .unwrap();
+
+        assert!(matcher.should_include(Path::new("agents/reviewer.md"), false));
+        assert!(!matcher.should_include(Path::new("agents/git-a.md"), false));
+        assert_eq!(
+            matcher.decide(Path::new("agents/git-a.md"), false),
+            PatternDecision::Ignored("agents/git-*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decide_reports_directory_patterns() {
+        let matcher = PatternMatcher::with_patterns(&["skills/experimental/".to_string()], &[]).unwrap();
+
+        assert_eq!(
+            matcher.decide(Path::new("skills/experimental/SKILL.md"), false),
+            PatternDecision::Ignored("skills/experimental/".to_string())
+        );
+        assert_eq!(
+            matcher.decide(Path::new("skills/stable/SKILL.md"), false),
+            PatternDecision::Unmatched
+        );
+    }
+
+    #[test]
+    fn test_gitignore_syntax_falls_back() {
+        for pattern in ["/agents/*.md", "node_modules/", "\\#literal", " padded"] {
+            let matcher = PatternMatcher::with_patterns(&[pattern.to_string()], &[]).unwrap();
+            assert!(matcher.globs.is_none(), "{pattern}");
+            assert!(matcher.gitignore.is_some(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_glob_is_reported() {
+        let err = PatternMatcher::with_patterns(&["a{b".to_string()], &[]).err();
+        assert!(err.unwrap().to_string().contains("Invalid ignore pattern"));
+    }
+
+    #[test]
+    fn test_large_pattern_set_matches_gitignore_semantics() {
+        let ignore: Vec<String> = (0..500).map(|i| format!("agents/gen-{i}-*")).collect();
+        let include: Vec<String> = (0..500)
+            .map(|i| format!("agents/gen-{i}-keep.md"))
+            .collect();
+        let paths: Vec<PathBuf> = (0..2000)
+            .map(|i| PathBuf::from(format!("agents/gen-{}-{}.md", i % 600, i % 3)))
+            .chain((0..500).map(|i| PathBuf::from(format!("agents/gen-{i}-keep.md"))))
+            .collect();
+
+        let fast = PatternMatcher::with_patterns(&ignore, &include).unwrap();
+        let slow = PatternMatcher::with_gitignore(&ignore, &include).unwrap();
+        assert!(fast.globs.is_some());
+
+        let fast_included = paths
+            .iter()
+            .filter(|path| fast.should_include(path, false))
+            .count();
+        let slow_included = paths
+            .iter()
+            .filter(|path| slow.should_include(path, false))
+            .count();
+
+        assert_eq!(fast_included, slow_included);
+        assert!(fast.should_include(Path::new("agents/gen-5-keep.md"), false));
+        assert!(!fast.should_include(Path::new("agents/gen-5-0.md"), false));
+        assert!(fast.should_include(Path::new("agents/gen-550-0.md"), false));
+    }
 }
@@ -0,0 +1,230 @@
+//! Evaluation of direction- and type-scoped sync rules
+//!
+//! Rules are checked in config order and the last matching rule wins, so
+//! rules from higher-precedence config files (merged later) override earlier
+//! ones. A rule only takes part when its direction matches the sync in
+//! progress and its file type matches the path; rules without a direction or
+//! type (or with type `any`) apply to everything their patterns match.
+
+use std::cell::OnceCell;
+use std::path::Path;
+
+use anyhow::Context;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use super::types::{FileType, SyncDirection, SyncRule};
+use crate::error::Result;
+
+/// A rule with its patterns compiled
+struct CompiledRule {
+    patterns: Gitignore,
+    direction: Option<SyncDirection>,
+    file_type: Option<FileType>,
+    include: bool,
+}
+
+impl CompiledRule {
+    /// Whether the rule is restricted to a concrete file type
+    const fn is_typed(&self) -> bool {
+        !matches!(self.file_type, None | Some(FileType::Any))
+    }
+}
+
+/// Matcher that decides inclusion for paths covered by sync rules
+#[derive(Default)]
+pub struct RuleMatcher {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleMatcher {
+    /// Compile the patterns of every rule
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rule pattern is invalid.
+    pub fn new(rules: &[SyncRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(idx, rule)| {
+                let mut builder = GitignoreBuilder::new("");
+                for pattern in &rule.patterns {
+                    builder
+                        .add_line(None, pattern)
+                        .with_context(|| format!("Invalid pattern '{pattern}' in rule {idx}"))?;
+                }
+
+                Ok(CompiledRule {
+                    patterns: builder.build()?,
+                    direction: rule.direction,
+                    file_type: rule.file_type,
+                    include: rule.include,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// Whether no rules are configured
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Decide whether a path is included when syncing in `direction`
+    ///
+    /// A pattern matches the path itself or any of its parent directories.
+    /// `classify` is only called when a type-scoped rule's patterns match;
+    /// returning `None` (e.g. for a directory) means no typed rule applies.
+    ///
+    /// Returns `None` when no applicable rule matches the path, leaving the
+    /// decision to the plain ignore/include patterns.
+    #[must_use]
+    pub fn decide(
+        &self,
+        path: &Path,
+        is_dir: bool,
+        direction: SyncDirection,
+        classify: impl Fn() -> Option<FileType>,
+    ) -> Option<bool> {
+        let file_type = OnceCell::new();
+
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.direction.is_none_or(|d| d == direction))
+            .filter(|rule| {
+                rule.patterns
+                    .matched_path_or_any_parents(path, is_dir)
+                    .is_ignore()
+            })
+            .find(|rule| !rule.is_typed() || *file_type.get_or_init(&classify) == rule.file_type)
+            .map(|rule| rule.include)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, direction: Option<SyncDirection>, include: bool) -> SyncRule {
+        SyncRule {
+            patterns: vec![pattern.to_string()],
+            direction,
+            file_type: None,
+            include,
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    const fn text() -> Option<FileType> {
+        Some(FileType::Text)
+    }
+
+    #[test]
+    fn test_rule_applies_only_in_its_direction() {
+        let matcher =
+            RuleMatcher::new(&[rule("*.secret.md", Some(SyncDirection::ToGlobal), false)]).unwrap();
+        let path = Path::new("agents/keys.secret.md");
+
+        assert_eq!(
+            matcher.decide(path, false, SyncDirection::ToGlobal, text),
+            Some(false)
+        );
+        assert_eq!(
+            matcher.decide(path, false, SyncDirection::ToLocal, text),
+            None
+        );
+        assert_eq!(
+            matcher.decide(
+                Path::new("agents/plain.md"),
+                false,
+                SyncDirection::ToGlobal,
+                text
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let matcher = RuleMatcher::new(&[
+            rule("agents/*", None, false),
+            rule("agents/keep.md", None, true),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            matcher.decide(
+                Path::new("agents/keep.md"),
+                false,
+                SyncDirection::ToLocal,
+                text
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            matcher.decide(
+                Path::new("agents/other.md"),
+                false,
+                SyncDirection::ToLocal,
+                text
+            ),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_typed_rule_only_matches_its_file_type() {
+        let matcher = RuleMatcher::new(&[SyncRule {
+            patterns: vec!["skills/**".to_string()],
+            direction: None,
+            file_type: Some(FileType::Binary),
+            include: false,
+        }])
+        .unwrap();
+        let path = Path::new("skills/demo/assets/logo.png");
+
+        assert_eq!(
+            matcher.decide(path, false, SyncDirection::ToLocal, || Some(
+                FileType::Binary
+            )),
+            Some(false)
+        );
+        assert_eq!(
+            matcher.decide(path, false, SyncDirection::ToLocal, text),
+            None
+        );
+        // Directories have no file type, so typed rules never apply to them
+        assert_eq!(
+            matcher.decide(
+                Path::new("skills/demo"),
+                true,
+                SyncDirection::ToLocal,
+                || None
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_patterns_match_parent_directories() {
+        let matcher = RuleMatcher::new(&[rule("skills/demo/assets/", None, false)]).unwrap();
+
+        assert_eq!(
+            matcher.decide(
+                Path::new("skills/demo/assets/logo.png"),
+                false,
+                SyncDirection::ToLocal,
+                text
+            ),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_invalid_rule_pattern() {
+        assert!(RuleMatcher::new(&[rule("agents/{a,b", None, false)]).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 //! Configuration types and structures
 
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
 use serde::{Deserialize, Serialize};
 
 use crate::comparison::ConflictStrategy;
@@ -14,6 +17,17 @@ pub enum SyncDirection {
     ToGlobal,
 }
 
+impl SyncDirection {
+    /// The direction syncing the other way
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::ToLocal => Self::ToGlobal,
+            Self::ToGlobal => Self::ToLocal,
+        }
+    }
+}
+
 /// File type for type-specific rules
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -28,8 +42,20 @@ pub enum FileType {
     Any,
 }
 
+/// How the summary at the end of a sync is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable summary
+    #[default]
+    Text,
+    /// JSON object, for dashboards and scripts
+    Json,
+}
+
 /// Sync rule for direction and type-specific configuration
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SyncRule {
     /// File patterns this rule applies to
     #[serde(default)]
@@ -48,7 +74,12 @@ pub struct SyncRule {
 }
 
 /// Main configuration structure
+///
+/// Unknown keys are rejected so a typo such as `ignroe` fails loudly instead
+/// of being silently dropped. Fields set only from the command line are
+/// unknown keys too.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     /// Patterns to ignore (exclude from sync)
@@ -67,6 +98,14 @@ pub struct Config {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub preserve_symlinks: Option<bool>,
 
+    /// Hardlink files whose content already exists elsewhere in the destination
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardlink_identical: Option<bool>,
+
+    /// Give synced files the owner and group of their source (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preserve_ownership: Option<bool>,
+
     /// Dry run mode (don't actually sync)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dry_run: Option<bool>,
@@ -75,13 +114,81 @@ pub struct Config {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub non_interactive: Option<bool>,
 
+    /// Sync hidden entries whose name starts with `.` (default: true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_hidden: Option<bool>,
+
+    /// Skip files larger than this many bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+
+    /// Times to retry a copy that fails with a transient error (default: 2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_retries: Option<u32>,
+
+    /// Record the last-synced state so one-sided edits are not treated as conflicts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_baseline: Option<bool>,
+
+    /// File name that marks a skills/ subdirectory as a skill (default: `SKILL.md`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill_marker: Option<String>,
+
+    /// Extensions of files synced from commands/ and hooks/ (default: `["md"]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_extensions: Option<Vec<String>>,
+
+    /// Treat files whose YAML frontmatter differs only in key order as identical
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic_frontmatter: Option<bool>,
+
+    /// Directory types scanned concurrently (default: number of CPUs; 1 is serial)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+
+    /// Shell command run before scanning (e.g. "git pull"); nonzero exit fails the sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_sync: Option<String>,
+
+    /// Shell command run after a successful sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_sync: Option<String>,
+
+    /// Format of the final summary (default: text)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<OutputFormat>,
+
     /// Conflict resolution strategy
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub conflict_strategy: Option<ConflictStrategy>,
 
+    /// Only sync source entries modified at or after this time (set by `--since`)
+    #[serde(skip)]
+    pub modified_since: Option<SystemTime>,
+
+    /// Only sync source entries whose relative path contains one of these (set by `--match`)
+    #[serde(skip)]
+    pub path_match: Vec<String>,
+
+    /// Patterns excluded even when an include pattern matches them (set by `--exclude`)
+    #[serde(skip)]
+    pub exclude: Vec<String>,
+
+    /// Log a diff for each conflict during a dry run (set by `--show-diff`)
+    #[serde(skip)]
+    pub show_diff: Option<bool>,
+
+    /// Remove destination entries ccsync created whose source is gone (set by `--prune`)
+    #[serde(skip)]
+    pub prune: Option<bool>,
+
     /// Advanced sync rules (direction and type-specific)
     #[serde(default)]
     pub rules: Vec<SyncRule>,
+
+    /// Named profiles that overlay the base config when selected via `--profile`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Self>,
 }
 
 #[cfg(test)]
@@ -97,6 +204,13 @@ mod tests {
         assert!(config.preserve_symlinks != Some(true));
     }
 
+    #[test]
+    fn test_output_format_from_toml() {
+        let config: Config = toml::from_str(r#"output_format = "json""#).unwrap();
+        assert_eq!(config.output_format, Some(OutputFormat::Json));
+        assert!(toml::from_str::<Config>(r#"output_format = "yaml""#).is_err());
+    }
+
     #[test]
     fn test_sync_direction_serde() {
         let to_local = SyncDirection::ToLocal;
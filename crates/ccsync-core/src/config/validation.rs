@@ -1,5 +1,7 @@
 //! Configuration validation and error reporting
 
+use anyhow::Context;
+
 use super::types::Config;
 use crate::error::Result;
 
@@ -45,6 +47,35 @@ impl ConfigValidator {
             }
         }
 
+        // The skill marker is looked up directly inside each skill directory
+        if let Some(marker) = &config.skill_marker
+            && (marker.trim().is_empty() || marker.contains(['/', '\\']))
+        {
+            anyhow::bail!("skill_marker must be a plain file name, got '{marker}'");
+        }
+
+        if config.jobs == Some(0) {
+            anyhow::bail!("jobs must be at least 1");
+        }
+
+        for (key, hook) in [("pre_sync", &config.pre_sync), ("post_sync", &config.post_sync)] {
+            if hook.as_ref().is_some_and(|command| command.trim().is_empty()) {
+                anyhow::bail!("{key} cannot be an empty command");
+            }
+        }
+
+        if let Some(extensions) = &config.command_extensions {
+            if extensions.is_empty() {
+                anyhow::bail!("command_extensions cannot be empty");
+            }
+            for extension in extensions {
+                let name = extension.trim_start_matches('.');
+                if name.trim().is_empty() || name.contains(['/', '\\', '.']) {
+                    anyhow::bail!("command_extensions must be plain extensions, got '{extension}'");
+                }
+            }
+        }
+
         // Validate rules
         for (idx, rule) in config.rules.iter().enumerate() {
             if rule.patterns.is_empty() {
@@ -58,6 +89,14 @@ impl ConfigValidator {
             }
         }
 
+        // Validate profiles (nesting is not supported)
+        for (name, profile) in &config.profiles {
+            if !profile.profiles.is_empty() {
+                anyhow::bail!("Profile '{name}' cannot define nested profiles");
+            }
+            Self::validate(profile).with_context(|| format!("Invalid profile '{name}'"))?;
+        }
+
         Ok(())
     }
 }
@@ -122,6 +161,59 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("has no patterns"));
     }
 
+    #[test]
+    fn test_validate_command_extensions() {
+        for extensions in [vec![], vec![String::new()], vec!["md/x".to_string()]] {
+            let config = Config {
+                command_extensions: Some(extensions),
+                ..Default::default()
+            };
+            let result = ConfigValidator::validate(&config);
+            assert!(result.unwrap_err().to_string().contains("command_extensions"));
+        }
+
+        let config = Config {
+            command_extensions: Some(vec!["md".to_string(), ".mdx".to_string()]),
+            ..Default::default()
+        };
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_hooks_are_not_empty() {
+        let config = Config {
+            pre_sync: Some("  ".to_string()),
+            ..Default::default()
+        };
+        let result = ConfigValidator::validate(&config);
+        assert!(result.unwrap_err().to_string().contains("pre_sync"));
+
+        let config = Config {
+            pre_sync: Some("git pull".to_string()),
+            post_sync: Some("echo done".to_string()),
+            ..Default::default()
+        };
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_skill_marker_is_a_file_name() {
+        for marker in ["", "docs/index.md", "docs\\index.md"] {
+            let config = Config {
+                skill_marker: Some(marker.to_string()),
+                ..Default::default()
+            };
+            let result = ConfigValidator::validate(&config);
+            assert!(result.unwrap_err().to_string().contains("skill_marker"));
+        }
+
+        let config = Config {
+            skill_marker: Some("index.md".to_string()),
+            ..Default::default()
+        };
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
     #[test]
     fn test_validate_valid_config() {
         let mut config = Config::default();
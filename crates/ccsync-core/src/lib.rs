@@ -8,6 +8,16 @@
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
+#![cfg_attr(
+    test,
+    allow(
+        clippy::default_constructed_unit_structs,
+        clippy::field_reassign_with_default,
+        clippy::needless_raw_string_hashes,
+        clippy::similar_names,
+        clippy::uninlined_format_args
+    )
+)]
 
 /// Core error types for the ccsync library
 pub mod error {
@@ -19,6 +29,9 @@ pub mod error {
 #[cfg_attr(not(test), allow(dead_code))]
 pub(crate) mod scanner;
 
+/// ANSI color control for terminal output
+pub mod color;
+
 /// File comparison and conflict detection
 pub mod comparison;
 
@@ -27,3 +40,84 @@ pub mod config;
 
 /// Bidirectional synchronization engine
 pub mod sync;
+
+/// Filesystem watching for continuous sync
+pub mod watch;
+
+use std::path::Path;
+
+use comparison::TimestampComparator;
+
+pub use comparison::{
+    ComparisonResult, ConflictStrategy, DirectoryComparator, DirectoryComparison, FileComparator,
+};
+
+/// Compare two directory trees recursively
+///
+/// `a` is treated as the source and `b` as the destination: files only in `a`
+/// are `added`, files only in `b` are `removed`. All paths in the result are
+/// relative to the two roots. `b` may be missing, in which case every file in
+/// `a` is reported as added.
+///
+/// Files whose content differs are resolved with `strategy`, as a sync from
+/// `a` to `b` would: `Fail` returns an error, `Skip` leaves them out of
+/// `modified`, `Newer` keeps only those newer in `a`, and `Overwrite` and
+/// `KeepBoth` report them all.
+///
+/// ```
+/// # fn main() -> anyhow::Result<()> {
+/// use ccsync_core::{ConflictStrategy, compare_trees};
+///
+/// let a = tempfile::tempdir()?;
+/// let b = tempfile::tempdir()?;
+/// std::fs::write(a.path().join("new.md"), "new")?;
+/// std::fs::write(a.path().join("shared.md"), "v2")?;
+/// std::fs::write(b.path().join("shared.md"), "v1")?;
+///
+/// let comparison = compare_trees(a.path(), b.path(), ConflictStrategy::Overwrite)?;
+/// assert_eq!(comparison.added, [std::path::PathBuf::from("new.md")]);
+/// assert_eq!(comparison.modified, [std::path::PathBuf::from("shared.md")]);
+/// assert!(!comparison.is_identical());
+///
+/// let skipped = compare_trees(a.path(), b.path(), ConflictStrategy::Skip)?;
+/// assert!(skipped.modified.is_empty());
+/// assert!(compare_trees(a.path(), b.path(), ConflictStrategy::Fail).is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if either tree cannot be walked or a file cannot be read,
+/// or if a file differs and `strategy` is `Fail`.
+pub fn compare_trees(
+    a: &Path,
+    b: &Path,
+    strategy: ConflictStrategy,
+) -> error::Result<DirectoryComparison> {
+    let mut comparison = DirectoryComparator::compare(a, b)?;
+    match strategy {
+        ConflictStrategy::Fail => {
+            if let Some(path) = comparison.modified.first() {
+                anyhow::bail!(
+                    "Conflict: {} differs between {} and {}",
+                    path.display(),
+                    a.display(),
+                    b.display()
+                );
+            }
+        }
+        ConflictStrategy::Skip => comparison.modified.clear(),
+        ConflictStrategy::Newer => {
+            let mut newer = Vec::new();
+            for path in comparison.modified {
+                if TimestampComparator::is_newer(&a.join(&path), &b.join(&path))? {
+                    newer.push(path);
+                }
+            }
+            comparison.modified = newer;
+        }
+        ConflictStrategy::Overwrite | ConflictStrategy::KeepBoth => {}
+    }
+    Ok(comparison)
+}
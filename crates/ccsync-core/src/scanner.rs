@@ -2,36 +2,116 @@
 //!
 //! This module provides directory-specific scanning patterns for:
 //! - `agents/`: Flat directory scanning (*.md files only)
-//! - `skills/`: One level subdirectory scanning (skills/*/SKILL.md pattern)
-//! - `commands/`: Fully recursive scanning (commands/**/*.md)
+//! - `skills/`: One level subdirectory scanning (skills/*/SKILL.md pattern,
+//!   with a configurable marker file name)
+//! - `commands/`: Fully recursive scanning (commands/**/*.md, with a
+//!   configurable set of extensions)
+//! - `hooks/`: Fully recursive scanning, like `commands/` (hooks/**/*.md)
 
 mod agents;
 mod commands;
 mod filters;
+mod ignore_files;
 mod skills;
 mod symlinks;
 
 #[cfg(test)]
 mod integration_tests;
 
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
+use std::thread;
 
-pub use filters::FileFilter;
+pub use filters::{FileFilter, Pattern};
+use ignore_files::IgnoreFiles;
 use symlinks::SymlinkResolver;
+use tracing::{debug, debug_span, warn};
 
 use crate::error::Result;
 
 /// Type of directory scanning to perform
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ScanMode {
     /// Flat directory scan (agents/)
     Flat,
     /// One level of subdirectories (skills/)
     OneLevel,
-    /// Recursive directory scan (commands/)
+    /// Recursive directory scan (commands/, hooks/)
     Recursive,
 }
 
+/// File name that marks a skills/ subdirectory as a skill unless configured
+pub const DEFAULT_SKILL_MARKER: &str = "SKILL.md";
+
+/// Extensions of files scanned in commands/ and hooks/ unless configured
+pub const DEFAULT_COMMAND_EXTENSIONS: &[&str] = &["md"];
+
+/// Directories scanned under the base path, with the mode used for each
+pub const SCAN_TARGETS: [(&str, ScanMode); 4] = [
+    ("agents", ScanMode::Flat),
+    ("skills", ScanMode::OneLevel),
+    ("commands", ScanMode::Recursive),
+    ("hooks", ScanMode::Recursive),
+];
+
+/// Tag of the incoming copies kept next to conflicting destinations (keep-both)
+pub const INCOMING_TAG: &str = ".incoming";
+
+/// Name of the `copy`-th keep-both incoming copy of `name`, counting from 1
+///
+/// Files get the tag before their extension (`name.incoming.md`), directories
+/// at the end (`name.incoming`). Copies after the first are numbered
+/// (`name.incoming-2.md`).
+#[must_use]
+pub fn incoming_name(name: &OsStr, is_dir: bool, copy: usize) -> OsString {
+    let path = Path::new(name);
+    let (stem, extension) = match (is_dir, path.file_stem(), path.extension()) {
+        (false, Some(stem), Some(extension)) => (stem, Some(extension)),
+        _ => (name, None),
+    };
+
+    let mut incoming = stem.to_os_string();
+    incoming.push(INCOMING_TAG);
+    if copy > 1 {
+        incoming.push(format!("-{copy}"));
+    }
+    if let Some(extension) = extension {
+        incoming.push(".");
+        incoming.push(extension);
+    }
+    incoming
+}
+
+/// Name of the original that `name` would be an incoming copy of, if it is
+/// named like one by [`incoming_name`]
+fn incoming_original(name: &OsStr) -> Option<String> {
+    let name = name.to_str()?;
+    let index = name.rfind(INCOMING_TAG)?;
+    let (stem, rest) = (&name[..index], &name[index + INCOMING_TAG.len()..]);
+    let (number, extension) = rest.split_at(rest.find('.').unwrap_or(rest.len()));
+
+    let numbered = number.is_empty()
+        || number
+            .strip_prefix('-')
+            .and_then(|digits| digits.parse::<usize>().ok())
+            .is_some_and(|copy| copy > 1 && format!("-{copy}") == number);
+    let single_extension = !extension.get(1..).unwrap_or_default().contains('.');
+    (!stem.is_empty() && numbered && single_extension).then(|| format!("{stem}{extension}"))
+}
+
+/// Check whether `path` is a keep-both incoming copy
+///
+/// Only names produced by [`incoming_name`] count, and only while the original
+/// they were made for is still next to them, so a file of your own that
+/// happens to be called `notes.incoming.md` is synced as usual.
+#[must_use]
+pub fn is_incoming_copy(path: &Path) -> bool {
+    path.file_name()
+        .and_then(incoming_original)
+        .is_some_and(|original| path.with_file_name(original).exists())
+}
+
 /// A scanned file with metadata
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ScannedFile {
@@ -47,52 +127,108 @@ pub struct ScanResult {
     /// Successfully scanned files
     pub files: Vec<ScannedFile>,
     /// Non-fatal warnings encountered during scanning
-    #[allow(dead_code)]
     pub warnings: Vec<String>,
+    /// Number of scanned entries per directory in [`SCAN_TARGETS`]
+    pub counts: HashMap<&'static str, usize>,
+}
+
+impl ScanResult {
+    /// Number of entries found in a directory of [`SCAN_TARGETS`], such as `agents`
+    #[must_use]
+    pub fn count(&self, dir: &str) -> usize {
+        self.counts.get(dir).copied().unwrap_or(0)
+    }
 }
 
 /// Main scanner coordinator
 pub struct Scanner {
     filter: FileFilter,
     symlink_resolver: SymlinkResolver,
+    /// File name (matched case-insensitively) that makes a directory a skill
+    skill_marker: String,
+    /// Extensions (without the dot) of files scanned in commands/ and hooks/
+    command_extensions: Vec<String>,
+    /// Directory types scanned at the same time
+    jobs: usize,
 }
 
 impl Scanner {
     /// Create a new scanner with the given configuration
     #[must_use]
-    pub const fn new(filter: FileFilter, preserve_symlinks: bool) -> Self {
+    pub fn new(filter: FileFilter, preserve_symlinks: bool) -> Self {
         Self {
             filter,
             symlink_resolver: SymlinkResolver::new(preserve_symlinks),
+            skill_marker: DEFAULT_SKILL_MARKER.to_string(),
+            command_extensions: DEFAULT_COMMAND_EXTENSIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            jobs: thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
         }
     }
 
-    /// Scan a base directory for Claude Code configuration files
+    /// Require `skill_marker` instead of [`DEFAULT_SKILL_MARKER`] at the root
+    /// of a skills/ subdirectory for it to be scanned as a skill
     #[must_use]
-    pub fn scan(&self, base_path: &Path) -> ScanResult {
-        let mut files = Vec::new();
-        let mut warnings = Vec::new();
+    pub fn with_skill_marker(mut self, skill_marker: &str) -> Self {
+        self.skill_marker = skill_marker.to_string();
+        self
+    }
 
-        // Scan each directory type with appropriate mode
-        match Self::scan_directory(&base_path.join("agents"), ScanMode::Flat) {
-            Ok(agents) => files.extend(agents),
-            Err(e) => warnings.push(format!("Failed to scan agents directory: {e}")),
-        }
+    /// Scan at most `jobs` directory types at a time instead of one per CPU
+    ///
+    /// With 1 (or 0), directories are scanned one after another on the
+    /// calling thread.
+    #[must_use]
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
 
-        match Self::scan_directory(&base_path.join("skills"), ScanMode::OneLevel) {
-            Ok(skills) => files.extend(skills),
-            Err(e) => warnings.push(format!("Failed to scan skills directory: {e}")),
-        }
+    /// Scan commands/ and hooks/ for files with these extensions instead of
+    /// [`DEFAULT_COMMAND_EXTENSIONS`]
+    ///
+    /// A leading dot is ignored, so `mdx` and `.mdx` are the same.
+    #[must_use]
+    pub fn with_command_extensions(mut self, extensions: &[String]) -> Self {
+        self.command_extensions = extensions
+            .iter()
+            .map(|extension| extension.trim_start_matches('.').to_string())
+            .collect();
+        self
+    }
 
-        match Self::scan_directory(&base_path.join("commands"), ScanMode::Recursive) {
-            Ok(commands) => files.extend(commands),
-            Err(e) => warnings.push(format!("Failed to scan commands directory: {e}")),
-        }
+    /// Scan a base directory for Claude Code configuration files
+    ///
+    /// The agents, skills, commands, and hooks directories are scanned
+    /// concurrently; files are returned sorted by path. Entries matched by a
+    /// `.ccsyncignore` in their directory or any parent up to `base_path` are
+    /// left out.
+    #[must_use]
+    pub fn scan(&self, base_path: &Path) -> ScanResult {
+        let _span = debug_span!("scan", base = %base_path.display()).entered();
+        let (files, mut warnings) = self.collect(base_path);
+        let mut ignore_files = IgnoreFiles::new(base_path);
 
         // Apply filtering and symlink resolution
         let mut resolved_files = Vec::new();
         for file in files {
-            if self.filter.should_include(&file.path) {
+            if ignore_files.is_ignored(&file.path, file.path.is_dir()) {
+                debug!(path = %file.path.display(), "Ignored by .ccsyncignore");
+                continue;
+            }
+            // Filters see the path relative to the scanned root
+            let rel_path = file.path.strip_prefix(base_path).unwrap_or(&file.path);
+            // Incoming copies are reconciled by hand, never synced themselves
+            if is_incoming_copy(&file.path) {
+                warnings.push(format!(
+                    "{} is a keep-both incoming copy, skipping",
+                    rel_path.display()
+                ));
+                continue;
+            }
+            if self.filter.should_include(rel_path) {
                 // Verify symlink is valid (not broken) but keep original path
                 match self.symlink_resolver.resolve(&file.path) {
                     Ok(_resolved) => {
@@ -106,22 +242,111 @@ impl Scanner {
             }
         }
 
+        warnings.extend(ignore_files.into_warnings());
+        for warning in &warnings {
+            warn!("{warning}");
+        }
+        resolved_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut counts = HashMap::new();
+        for file in &resolved_files {
+            let rel_path = file.path.strip_prefix(base_path).unwrap_or(&file.path);
+            if let Some((dir, _)) = SCAN_TARGETS
+                .iter()
+                .find(|(dir, _)| rel_path.starts_with(dir))
+            {
+                *counts.entry(*dir).or_insert(0) += 1;
+            }
+        }
+        debug!(files = resolved_files.len(), ?counts, "Scan complete");
+
         ScanResult {
             files: resolved_files,
             warnings,
+            counts,
+        }
+    }
+
+    /// Scan every directory type and merge the results, sorted by path
+    ///
+    /// Directory types are scanned on up to `jobs` threads at once, in
+    /// batches. Returns the found files and any warnings.
+    fn collect(&self, base_path: &Path) -> (Vec<ScannedFile>, Vec<String>) {
+        let scan = |(name, mode): (&str, ScanMode)| self.scan_directory(&base_path.join(name), mode);
+
+        let results: Vec<Result<Vec<ScannedFile>>> = if self.jobs > 1 {
+            SCAN_TARGETS
+                .chunks(self.jobs)
+                .flat_map(|batch| {
+                    thread::scope(|scope| {
+                        // Spawn every scan in the batch before joining any of them
+                        #[allow(clippy::needless_collect)]
+                        let handles: Vec<_> = batch
+                            .iter()
+                            .map(|&target| scope.spawn(move || scan(target)))
+                            .collect();
+                        handles
+                            .into_iter()
+                            .map(|handle| {
+                                handle.join().unwrap_or_else(|_| {
+                                    Err(anyhow::anyhow!("scan thread panicked"))
+                                })
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect()
+        } else {
+            SCAN_TARGETS.into_iter().map(scan).collect()
+        };
+
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
+        for ((name, _), result) in SCAN_TARGETS.into_iter().zip(results) {
+            match result {
+                Ok(found) => files.extend(found),
+                Err(e) => warnings.push(format!("Failed to scan {name} directory: {e}")),
+            }
+        }
+        match self.skill_warnings(base_path) {
+            Ok(skill_warnings) => warnings.extend(skill_warnings),
+            Err(e) => warnings.push(format!("Failed to check skills directory: {e}")),
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        (files, warnings)
+    }
+
+    /// Diagnostics for skills/ subdirectories skipped for lacking the marker file
+    fn skill_warnings(&self, base_path: &Path) -> Result<Vec<String>> {
+        let skills = base_path.join("skills");
+        if !skills.is_dir() {
+            return Ok(Vec::new());
         }
+
+        Ok(skills::missing_skill_files(&skills, &self.skill_marker)?
+            .into_iter()
+            .map(|dir| {
+                let rel_path = dir.strip_prefix(base_path).unwrap_or(&dir);
+                format!(
+                    "{} has no {}, skipping",
+                    rel_path.display(),
+                    self.skill_marker
+                )
+            })
+            .collect())
     }
 
     /// Scan a directory with the specified mode
-    fn scan_directory(path: &Path, mode: ScanMode) -> Result<Vec<ScannedFile>> {
+    fn scan_directory(&self, path: &Path, mode: ScanMode) -> Result<Vec<ScannedFile>> {
         if !path.exists() {
             return Ok(Vec::new());
         }
 
         let paths = match mode {
             ScanMode::Flat => agents::scan(path)?,
-            ScanMode::OneLevel => skills::scan(path)?,
-            ScanMode::Recursive => commands::scan(path)?,
+            ScanMode::OneLevel => skills::scan(path, &self.skill_marker)?,
+            ScanMode::Recursive => commands::scan(path, &self.command_extensions)?,
         };
 
         Ok(paths
@@ -140,4 +365,22 @@ mod tests {
         assert_eq!(ScanMode::Flat, ScanMode::Flat);
         assert_ne!(ScanMode::Flat, ScanMode::OneLevel);
     }
+
+    #[test]
+    fn test_incoming_names_map_back_to_their_original() {
+        for (name, is_dir) in [("agent.md", false), ("my.skill", true), ("hook", false)] {
+            for copy in [1, 2, 10] {
+                let incoming = incoming_name(OsStr::new(name), is_dir, copy);
+                assert_eq!(incoming_original(&incoming).as_deref(), Some(name));
+            }
+        }
+        for name in [
+            "agent.incoming-1.md",
+            "agent.incoming-02.md",
+            ".incoming",
+            "a.incoming.b.md",
+        ] {
+            assert_eq!(incoming_original(OsStr::new(name)), None, "{name}");
+        }
+    }
 }
@@ -1,21 +1,22 @@
-//! Recursive directory scanning for commands/
+//! Recursive directory scanning for commands/ and hooks/
 //!
 //! Commands can use subdirectories for organization. Subdirectories are used
 //! for organization and appear in the command description, but they do not
-//! affect the command name itself.
+//! affect the command name itself. Hooks are laid out the same way.
 
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::error::Result;
 
-/// Scan the commands/ directory recursively for `.md` files
+/// Scan a commands/ or hooks/ directory recursively for files whose
+/// extension is one of `extensions` (e.g. `["md"]`)
 ///
 /// # Errors
 ///
 /// Returns an error if directory traversal fails due to permission issues
 /// or I/O errors.
-pub fn scan(base: &Path) -> Result<Vec<PathBuf>> {
+pub fn scan(base: &Path, extensions: &[String]) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
     for entry in WalkDir::new(base).follow_links(false) {
@@ -26,7 +27,9 @@ pub fn scan(base: &Path) -> Result<Vec<PathBuf>> {
         // Accept both regular files and symlinks (symlinks are resolved later by the scanner)
         let file_type = entry.file_type();
         if (file_type.is_file() || file_type.is_symlink())
-            && path.extension().is_some_and(|ext| ext == "md")
+            && path
+                .extension()
+                .is_some_and(|ext| extensions.iter().any(|allowed| ext == allowed.as_str()))
         {
             files.push(path.to_path_buf());
         }
@@ -41,6 +44,10 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    fn markdown() -> Vec<String> {
+        vec!["md".to_string()]
+    }
+
     #[test]
     fn test_commands_recursive_structure() {
         let tmp = TempDir::new().unwrap();
@@ -64,7 +71,7 @@ mod tests {
         // Non-md file (should be ignored)
         fs::write(commands_dir.join("ignore.txt"), "ignore").unwrap();
 
-        let files = scan(&commands_dir).unwrap();
+        let files = scan(&commands_dir, &markdown()).unwrap();
 
         assert_eq!(files.len(), 3);
         assert!(
@@ -76,13 +83,33 @@ mod tests {
         assert!(files.iter().any(|p| p.ends_with("backend/api/endpoint.md")));
     }
 
+    #[test]
+    fn test_commands_extra_extensions() {
+        let tmp = TempDir::new().unwrap();
+        let commands_dir = tmp.path().join("commands");
+        fs::create_dir_all(commands_dir.join("docs")).unwrap();
+        fs::write(commands_dir.join("plain.md"), "plain").unwrap();
+        fs::write(commands_dir.join("docs/rich.mdx"), "rich").unwrap();
+
+        let files = scan(&commands_dir, &markdown()).unwrap();
+        assert_eq!(files, [commands_dir.join("plain.md")]);
+
+        let extensions = vec!["md".to_string(), "mdx".to_string()];
+        let mut files = scan(&commands_dir, &extensions).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [commands_dir.join("docs/rich.mdx"), commands_dir.join("plain.md")]
+        );
+    }
+
     #[test]
     fn test_commands_empty_directory() {
         let tmp = TempDir::new().unwrap();
         let commands_dir = tmp.path().join("commands");
         fs::create_dir(&commands_dir).unwrap();
 
-        let files = scan(&commands_dir).unwrap();
+        let files = scan(&commands_dir, &markdown()).unwrap();
         assert_eq!(files.len(), 0);
     }
 
@@ -105,7 +132,7 @@ mod tests {
         fs::write(subdir.join("command2.md"), "cmd2").unwrap();
         fs::write(subdir.join("script.sh"), "#!/bin/bash").unwrap();
 
-        let files = scan(&commands_dir).unwrap();
+        let files = scan(&commands_dir, &markdown()).unwrap();
 
         assert_eq!(files.len(), 2);
         assert!(files.iter().all(|p| p.extension().unwrap() == "md"));
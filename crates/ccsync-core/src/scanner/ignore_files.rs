@@ -0,0 +1,106 @@
+//! Per-directory `.ccsyncignore` files
+//!
+//! Each `.ccsyncignore` holds gitignore-style patterns that apply to the
+//! subtree of the directory it lives in. When several files match a path, the
+//! one closest to the path wins, just like nested `.gitignore` files.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::PatternMatcher;
+
+/// Lazily loaded `.ccsyncignore` matchers below a scan root
+pub struct IgnoreFiles<'a> {
+    base: &'a Path,
+    matchers: HashMap<PathBuf, Option<PatternMatcher>>,
+    warnings: Vec<String>,
+}
+
+impl<'a> IgnoreFiles<'a> {
+    /// Create an empty cache for the scan root `base`
+    pub fn new(base: &'a Path) -> Self {
+        Self {
+            base,
+            matchers: HashMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Whether a scanned path is ignored by a `.ccsyncignore` at or above it
+    pub fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        let Some(parent) = path.parent() else {
+            return false;
+        };
+        let mut dirs: Vec<&Path> = parent
+            .ancestors()
+            .take_while(|dir| dir.starts_with(self.base))
+            .collect();
+        // Deeper files override shallower ones, so apply them last
+        dirs.reverse();
+
+        let mut ignored = false;
+        for dir in dirs {
+            if let Some(include) = self
+                .matcher(dir)
+                .and_then(|matcher| matcher.decide_in_subtree(path, is_dir))
+            {
+                ignored = !include;
+            }
+        }
+        ignored
+    }
+
+    /// Problems reading ignore files, one per unreadable file
+    pub fn into_warnings(self) -> Vec<String> {
+        self.warnings
+    }
+
+    fn matcher(&mut self, dir: &Path) -> Option<&PatternMatcher> {
+        if !self.matchers.contains_key(dir) {
+            let matcher = PatternMatcher::from_ignore_file(dir).unwrap_or_else(|e| {
+                self.warnings.push(format!("{e:#}"));
+                None
+            });
+            self.matchers.insert(dir.to_path_buf(), matcher);
+        }
+        self.matchers.get(dir).and_then(Option::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IGNORE_FILE;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nested_ignore_file_overrides_parent() {
+        let tmp = TempDir::new().unwrap();
+        let agents = tmp.path().join("agents");
+        fs::create_dir(&agents).unwrap();
+        fs::write(tmp.path().join(IGNORE_FILE), "*.draft.md\n").unwrap();
+        fs::write(agents.join(IGNORE_FILE), "!keep.draft.md\n").unwrap();
+
+        let mut ignore_files = IgnoreFiles::new(tmp.path());
+
+        assert!(ignore_files.is_ignored(&agents.join("wip.draft.md"), false));
+        assert!(!ignore_files.is_ignored(&agents.join("keep.draft.md"), false));
+        assert!(!ignore_files.is_ignored(&agents.join("agent.md"), false));
+    }
+
+    #[test]
+    fn test_ignore_file_only_applies_to_its_subtree() {
+        let tmp = TempDir::new().unwrap();
+        let agents = tmp.path().join("agents");
+        let commands = tmp.path().join("commands");
+        fs::create_dir(&agents).unwrap();
+        fs::create_dir(&commands).unwrap();
+        fs::write(agents.join(IGNORE_FILE), "*.md\n").unwrap();
+
+        let mut ignore_files = IgnoreFiles::new(tmp.path());
+
+        assert!(ignore_files.is_ignored(&agents.join("agent.md"), false));
+        assert!(!ignore_files.is_ignored(&commands.join("command.md"), false));
+    }
+}
@@ -1,9 +1,10 @@
 //! Integration tests for the scanner module
 
 use std::fs;
+use std::path::Path;
 use tempfile::TempDir;
 
-use super::{FileFilter, Scanner};
+use super::{FileFilter, SCAN_TARGETS, Scanner};
 
 #[test]
 fn test_full_scan_all_directory_types() {
@@ -45,6 +46,9 @@ fn test_full_scan_all_directory_types() {
 
     // Should find: 2 agents, 2 skill directories, 2 commands = 6 total
     assert_eq!(result.files.len(), 6);
+    assert_eq!(result.count("agents"), 2);
+    assert_eq!(result.count("skills"), 2);
+    assert_eq!(result.count("commands"), 2);
 
     // Verify each type is found
     assert!(result.files.iter().any(|f| f.path.ends_with("agent1.md")));
@@ -66,6 +70,39 @@ fn test_full_scan_all_directory_types() {
     );
 }
 
+#[test]
+fn test_parallel_scan_matches_single_job() {
+    let tmp = TempDir::new().unwrap();
+
+    fs::create_dir_all(tmp.path().join("agents")).unwrap();
+    fs::write(tmp.path().join("agents/b.md"), "b").unwrap();
+    fs::write(tmp.path().join("agents/a.md"), "a").unwrap();
+    fs::create_dir_all(tmp.path().join("skills/demo")).unwrap();
+    fs::write(tmp.path().join("skills/demo/SKILL.md"), "skill").unwrap();
+    fs::create_dir_all(tmp.path().join("skills/no-marker")).unwrap();
+    fs::create_dir_all(tmp.path().join("commands/nested")).unwrap();
+    fs::write(tmp.path().join("commands/root.md"), "root").unwrap();
+    fs::write(tmp.path().join("commands/nested/deep.md"), "deep").unwrap();
+
+    let with_jobs = |jobs| Scanner::new(FileFilter::new(), false).with_jobs(jobs);
+    let scanner = with_jobs(SCAN_TARGETS.len());
+    let parallel = scanner.collect(tmp.path());
+    let sequential = with_jobs(1).collect(tmp.path());
+
+    assert_eq!(parallel, sequential);
+    // Batches smaller than the number of directory types keep the same order
+    assert_eq!(with_jobs(3).collect(tmp.path()), sequential);
+    assert_eq!(parallel.0.len(), 5);
+    assert_eq!(parallel.1.len(), 1);
+
+    // The public scan returns files sorted by path
+    let result = scanner.scan(tmp.path());
+    let paths: Vec<_> = result.files.iter().map(|f| f.path.clone()).collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+}
+
 #[test]
 fn test_scan_missing_directories() {
     let tmp = TempDir::new().unwrap();
@@ -84,6 +121,9 @@ fn test_scan_missing_directories() {
     // Should only find the one agent file
     assert_eq!(result.files.len(), 1);
     assert!(result.files[0].path.ends_with("agent.md"));
+    assert_eq!(result.count("agents"), 1);
+    assert_eq!(result.count("skills"), 0);
+    assert_eq!(result.count("commands"), 0);
 }
 
 #[test]
@@ -102,6 +142,31 @@ fn test_scan_empty_directories() {
     assert_eq!(result.files.len(), 0);
 }
 
+#[test]
+fn test_scan_warns_on_skill_without_skill_md() {
+    let tmp = TempDir::new().unwrap();
+
+    let skills = tmp.path().join("skills");
+    fs::create_dir_all(skills.join("good")).unwrap();
+    fs::write(skills.join("good/SKILL.md"), "skill").unwrap();
+    fs::create_dir_all(skills.join("foo")).unwrap();
+    fs::write(skills.join("foo/README.md"), "readme").unwrap();
+
+    let scanner = Scanner::new(FileFilter::new(), false);
+    let result = scanner.scan(tmp.path());
+
+    // The directory is still not synced, but the user is told why
+    assert_eq!(result.files.len(), 1);
+    assert!(result.files[0].path.ends_with("good"));
+    assert_eq!(
+        result.warnings,
+        vec![format!(
+            "{} has no SKILL.md, skipping",
+            Path::new("skills").join("foo").display()
+        )]
+    );
+}
+
 #[cfg(unix)]
 #[test]
 fn test_scan_with_symlinks() {
@@ -155,6 +220,51 @@ fn test_scan_with_broken_symlink() {
     // Note: Warnings are collected for broken symlinks during resolution
 }
 
+#[cfg(unix)]
+#[test]
+fn test_scan_broken_symlink_emits_warn_event() {
+    use std::io;
+    use std::os::unix::fs as unix_fs;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer that appends formatted log output to a shared buffer
+    #[derive(Clone, Default)]
+    struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let tmp = TempDir::new().unwrap();
+    let agents = tmp.path().join("agents");
+    fs::create_dir(&agents).unwrap();
+    unix_fs::symlink("/nonexistent/file.md", agents.join("broken.md")).unwrap();
+
+    let writer = CaptureWriter::default();
+    let make_writer = writer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || make_writer.clone())
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .finish();
+
+    let result = tracing::subscriber::with_default(subscriber, || {
+        Scanner::new(FileFilter::new(), false).scan(tmp.path())
+    });
+
+    assert!(result.files.is_empty());
+    let output = String::from_utf8(writer.0.lock().unwrap().clone()).unwrap();
+    assert!(output.contains("WARN"), "no warn event captured: {output}");
+    assert!(output.contains("Symlink resolution failed"));
+}
+
 #[cfg(unix)]
 #[test]
 fn test_scan_preserve_symlinks() {
@@ -199,3 +309,120 @@ fn test_scan_cross_platform_paths() {
 
     assert_eq!(result.files.len(), 2);
 }
+
+#[test]
+fn test_scan_honors_ccsyncignore() {
+    let tmp = TempDir::new().unwrap();
+    let agents = tmp.path().join("agents");
+    fs::create_dir(&agents).unwrap();
+    fs::write(agents.join("reviewer.md"), "reviewer").unwrap();
+    fs::write(agents.join("planner.draft.md"), "draft").unwrap();
+    fs::write(
+        tmp.path().join(".ccsyncignore"),
+        "# work in progress\n*.draft.md\n",
+    )
+    .unwrap();
+
+    let scanner = Scanner::new(FileFilter::new(), false);
+    let result = scanner.scan(tmp.path());
+
+    assert_eq!(result.files.len(), 1);
+    assert!(result.files[0].path.ends_with("reviewer.md"));
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn test_scan_ccsyncignore_can_exclude_skill_directories() {
+    let tmp = TempDir::new().unwrap();
+    let skills = tmp.path().join("skills");
+    for name in ["kept", "private"] {
+        fs::create_dir_all(skills.join(name)).unwrap();
+        fs::write(skills.join(name).join("SKILL.md"), name).unwrap();
+    }
+    fs::write(skills.join(".ccsyncignore"), "private/\n").unwrap();
+
+    let scanner = Scanner::new(FileFilter::new(), false);
+    let result = scanner.scan(tmp.path());
+
+    assert_eq!(result.files.len(), 1);
+    assert!(result.files[0].path.ends_with("kept"));
+}
+
+#[test]
+fn test_scan_hooks_recursively() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("hooks/git")).unwrap();
+    fs::write(tmp.path().join("hooks/pre-commit.md"), "pre-commit").unwrap();
+    fs::write(tmp.path().join("hooks/git/post-merge.md"), "post-merge").unwrap();
+    fs::write(tmp.path().join("hooks/notes.txt"), "not a hook").unwrap();
+
+    let scanner = Scanner::new(FileFilter::new(), false);
+    let result = scanner.scan(tmp.path());
+
+    assert_eq!(result.files.len(), 2);
+    assert_eq!(result.count("hooks"), 2);
+    assert!(
+        result
+            .files
+            .iter()
+            .any(|f| f.path.ends_with("hooks/pre-commit.md"))
+    );
+    assert!(
+        result
+            .files
+            .iter()
+            .any(|f| f.path.ends_with("hooks/git/post-merge.md"))
+    );
+}
+
+#[test]
+fn test_scan_counts_commands_and_hooks_separately() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("commands")).unwrap();
+    fs::create_dir_all(tmp.path().join("hooks")).unwrap();
+    fs::write(tmp.path().join("commands/build.md"), "build").unwrap();
+    fs::write(tmp.path().join("hooks/pre-commit.md"), "pre-commit").unwrap();
+    fs::write(tmp.path().join("hooks/post-merge.md"), "post-merge").unwrap();
+
+    let scanner = Scanner::new(FileFilter::new(), false);
+    let result = scanner.scan(tmp.path());
+
+    assert_eq!(result.count("commands"), 1);
+    assert_eq!(result.count("hooks"), 2);
+}
+
+#[test]
+fn test_scan_skips_incoming_copies() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("agents")).unwrap();
+    fs::write(tmp.path().join("agents/agent.md"), "agent").unwrap();
+    fs::write(tmp.path().join("agents/agent.incoming.md"), "incoming").unwrap();
+    fs::write(tmp.path().join("agents/agent.incoming-2.md"), "incoming").unwrap();
+    // Named like a copy, but there is no original next to it
+    fs::write(tmp.path().join("agents/notes.incoming.md"), "notes").unwrap();
+    fs::create_dir_all(tmp.path().join("skills/x")).unwrap();
+    fs::create_dir_all(tmp.path().join("skills/x.incoming")).unwrap();
+    fs::write(tmp.path().join("skills/x/SKILL.md"), "skill").unwrap();
+    fs::write(tmp.path().join("skills/x.incoming/SKILL.md"), "incoming").unwrap();
+
+    let scanner = Scanner::new(FileFilter::new(), false);
+    let result = scanner.scan(tmp.path());
+
+    let paths: Vec<_> = result.files.iter().map(|f| f.path.clone()).collect();
+    assert_eq!(
+        paths,
+        [
+            tmp.path().join("agents/agent.md"),
+            tmp.path().join("agents/notes.incoming.md"),
+            tmp.path().join("skills/x"),
+        ]
+    );
+    assert_eq!(
+        result.warnings,
+        [
+            "agents/agent.incoming-2.md is a keep-both incoming copy, skipping",
+            "agents/agent.incoming.md is a keep-both incoming copy, skipping",
+            "skills/x.incoming is a keep-both incoming copy, skipping",
+        ]
+    );
+}
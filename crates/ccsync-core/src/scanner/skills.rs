@@ -1,8 +1,10 @@
 //! One-level subdirectory scanning for skills/
 //!
 //! Skills are organized as `skills/skill-name/SKILL.md` where each skill
-//! has its own subdirectory containing a required `SKILL.md` file plus
-//! optional supporting files.
+//! has its own subdirectory containing a required marker file plus optional
+//! supporting files. The marker is `SKILL.md` unless `skill_marker` is
+//! configured, and is matched case-insensitively, so `Skill.md` and
+//! `skill.md` are accepted too.
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -11,14 +13,15 @@ use crate::error::Result;
 
 /// Scan the skills/ directory for skill directories (one level deep)
 ///
-/// Returns paths to skill directories that contain a `SKILL.md` file.
+/// Returns paths to skill directories that contain the `marker` file
+/// (in any letter case).
 /// The entire directory will be synced, including all supporting files.
 ///
 /// # Errors
 ///
 /// Returns an error if the directory cannot be read or if there are
 /// permission issues.
-pub fn scan(base: &Path) -> Result<Vec<PathBuf>> {
+pub fn scan(base: &Path, marker: &str) -> Result<Vec<PathBuf>> {
     let mut directories = Vec::new();
 
     for entry in fs::read_dir(base)? {
@@ -26,18 +29,58 @@ pub fn scan(base: &Path) -> Result<Vec<PathBuf>> {
         let path = entry.path();
 
         // Only process directories
-        if path.is_dir() {
-            let skill_md = path.join("SKILL.md");
-            if skill_md.exists() && skill_md.is_file() {
-                // Return the directory path, not the SKILL.md file
-                directories.push(path);
-            }
+        if path.is_dir() && has_skill_file(&path, marker)? {
+            // Return the directory path, not the marker file
+            directories.push(path);
         }
     }
 
     Ok(directories)
 }
 
+/// Find subdirectories of skills/ that are not skills because they lack the `marker` file
+///
+/// Hidden directories (such as `.git`) are not reported.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read.
+pub fn missing_skill_files(base: &Path, marker: &str) -> Result<Vec<PathBuf>> {
+    let mut missing = Vec::new();
+
+    for entry in fs::read_dir(base)? {
+        let entry = entry?;
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+
+        if path.is_dir() && !hidden && !has_skill_file(&path, marker)? {
+            missing.push(path);
+        }
+    }
+
+    missing.sort();
+    Ok(missing)
+}
+
+/// Whether a directory contains the `marker` file, ignoring case
+///
+/// Only the directory root is checked; a marker in a subdirectory does not
+/// make a skill.
+fn has_skill_file(dir: &Path, marker: &str) -> Result<bool> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.eq_ignore_ascii_case(marker))
+            && entry.path().is_file()
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,20 +111,88 @@ mod tests {
         // File directly in skills/ (should be ignored)
         fs::write(skills_dir.join("direct.md"), "ignore").unwrap();
 
-        let directories = scan(&skills_dir).unwrap();
+        let directories = scan(&skills_dir, "SKILL.md").unwrap();
 
         assert_eq!(directories.len(), 2);
         assert!(directories.iter().any(|p| p.ends_with("skill-1")));
         assert!(directories.iter().any(|p| p.ends_with("skill-2")));
     }
 
+    #[test]
+    fn test_skill_file_name_is_case_insensitive() {
+        let tmp = TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+
+        let mixed = skills_dir.join("mixed-case");
+        fs::create_dir_all(&mixed).unwrap();
+        fs::write(mixed.join("Skill.md"), "skill").unwrap();
+
+        let lower = skills_dir.join("lower-case");
+        fs::create_dir_all(&lower).unwrap();
+        fs::write(lower.join("skill.md"), "skill").unwrap();
+
+        // A marker nested below the skill root does not count
+        let nested = skills_dir.join("nested");
+        fs::create_dir_all(nested.join("docs")).unwrap();
+        fs::write(nested.join("docs/SKILL.md"), "skill").unwrap();
+
+        let directories = scan(&skills_dir, "SKILL.md").unwrap();
+
+        assert_eq!(directories.len(), 2);
+        assert!(directories.iter().any(|p| p.ends_with("mixed-case")));
+        assert!(directories.iter().any(|p| p.ends_with("lower-case")));
+    }
+
+    #[test]
+    fn test_custom_skill_marker() {
+        let tmp = TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+
+        let indexed = skills_dir.join("indexed");
+        fs::create_dir_all(&indexed).unwrap();
+        fs::write(indexed.join("index.md"), "skill").unwrap();
+
+        // SKILL.md no longer marks a skill once the marker is changed
+        let classic = skills_dir.join("classic");
+        fs::create_dir_all(&classic).unwrap();
+        fs::write(classic.join("SKILL.md"), "skill").unwrap();
+
+        let directories = scan(&skills_dir, "index.md").unwrap();
+
+        assert_eq!(directories, vec![indexed]);
+        assert_eq!(
+            missing_skill_files(&skills_dir, "index.md").unwrap(),
+            vec![classic]
+        );
+    }
+
+    #[test]
+    fn test_missing_skill_files() {
+        let tmp = TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+
+        let skill = skills_dir.join("skill");
+        fs::create_dir_all(&skill).unwrap();
+        fs::write(skill.join("SKILL.md"), "skill").unwrap();
+
+        let readme_only = skills_dir.join("readme-only");
+        fs::create_dir_all(&readme_only).unwrap();
+        fs::write(readme_only.join("README.md"), "readme").unwrap();
+
+        fs::create_dir_all(skills_dir.join(".git")).unwrap();
+
+        let missing = missing_skill_files(&skills_dir, "SKILL.md").unwrap();
+
+        assert_eq!(missing, vec![readme_only]);
+    }
+
     #[test]
     fn test_skills_empty_directory() {
         let tmp = TempDir::new().unwrap();
         let skills_dir = tmp.path().join("skills");
         fs::create_dir(&skills_dir).unwrap();
 
-        let directories = scan(&skills_dir).unwrap();
+        let directories = scan(&skills_dir, "SKILL.md").unwrap();
         assert_eq!(directories.len(), 0);
     }
 }
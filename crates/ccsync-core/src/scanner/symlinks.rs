@@ -20,14 +20,6 @@ pub enum ResolvedPath {
 }
 
 impl ResolvedPath {
-    /// Get the inner path regardless of variant
-    #[must_use]
-    pub fn into_path(self) -> PathBuf {
-        match self {
-            Self::Regular(p) | Self::Symlink(p) | Self::Resolved(p) => p,
-        }
-    }
-
     /// Get a reference to the inner path
     #[must_use]
     #[allow(dead_code)]
@@ -53,10 +45,13 @@ impl SymlinkResolver {
 
     /// Resolve a path, handling symlinks appropriately
     ///
+    /// When preserving symlinks, a link is returned as-is without checking
+    /// its target, so dangling links are kept too.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - A symlink is broken (target doesn't exist)
+    /// - A symlink is broken (target doesn't exist) and is not preserved
     /// - A symlink loop is detected
     /// - Path canonicalization fails
     pub fn resolve(&self, path: &Path) -> Result<ResolvedPath> {
@@ -185,6 +180,18 @@ mod tests {
         assert_eq!(resolved, ResolvedPath::Symlink(link));
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_preserve_keeps_broken_symlink() {
+        let tmp = TempDir::new().unwrap();
+        let link = tmp.path().join("broken.txt");
+        unix_fs::symlink("/nonexistent/target.txt", &link).unwrap();
+
+        let resolver = SymlinkResolver::new(true);
+
+        assert_eq!(resolver.resolve(&link).unwrap(), ResolvedPath::Symlink(link));
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_broken_symlink() {
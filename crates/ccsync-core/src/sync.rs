@@ -1,17 +1,29 @@
 //! Bidirectional synchronization engine
 //!
-//! This module implements the core sync logic for to-local and to-global operations.
+//! This module implements the core sync logic for to-local, to-global, and two-way
+//! (bidirectional) operations.
 //! Interactive prompts are NOT implemented here - they will be added in Task 4.
 //! The sync engine uses ConflictStrategy from config/CLI flags directly.
 
 mod actions;
+mod baseline;
+mod dedup;
 mod executor;
+mod hooks;
+mod journal;
+mod manifest;
 mod orchestrator;
+mod plan;
 mod reporting;
 
 // Public exports for CLI integration
 pub use actions::SyncAction;
-pub use orchestrator::{ApprovalCallback, SyncEngine};
+pub use baseline::{BASELINE_FILE, Baseline, BaselineChange};
+pub use hooks::Hook;
+pub use journal::{JournalEntry, SyncJournal, UndoReport};
+pub use manifest::{MANIFEST_FILE, Manifest};
+pub use orchestrator::{ApprovalCallback, ComparatorCallback, DecisionCallback, SyncEngine};
+pub use plan::{PlanFile, PlanSize};
 pub use reporting::SyncReporter;
 
 /// Synchronization result with statistics
@@ -23,14 +35,32 @@ pub struct SyncResult {
     pub updated: usize,
     /// Files deleted
     pub deleted: usize,
+    /// Created, updated, and deleted entries per configuration type, keyed
+    /// by top-level directory (e.g. "agents")
+    pub by_type: std::collections::BTreeMap<String, usize>,
     /// Files skipped
     pub skipped: usize,
     /// Skip reasons with counts
     pub skip_reasons: std::collections::HashMap<String, usize>,
     /// Conflicts encountered
     pub conflicts: usize,
+    /// Conflicts that would have failed but were approved for overwrite
+    pub conflicts_resolved: usize,
+    /// Actions left for a later sync because the operation limit was reached
+    pub deferred: usize,
+    /// Dry-run conflicts that would abort a real run under `ConflictStrategy::Fail`
+    pub would_fail: usize,
+    /// Incoming copies written next to conflicting destinations (keep-both)
+    pub kept_both: Vec<std::path::PathBuf>,
+    /// Problems that did not fail the sync, such as a source that changed
+    /// while it was being synced
+    pub warnings: Vec<String>,
     /// Errors encountered
     pub errors: Vec<String>,
+    /// Total bytes written to the destination
+    pub bytes_copied: u64,
+    /// Wall time the sync took, once it finished
+    pub elapsed: Option<std::time::Duration>,
 }
 
 impl SyncResult {
@@ -40,10 +70,24 @@ impl SyncResult {
         self.created + self.updated + self.deleted
     }
 
-    /// Whether sync was successful (no errors)
+    /// Whether sync was successful (no errors, and a dry run found no
+    /// conflicts that would fail the real run)
     #[must_use]
     pub const fn is_success(&self) -> bool {
-        self.errors.is_empty()
+        self.errors.is_empty() && self.would_fail == 0
+    }
+
+    /// Count `operations` done on `rel_path` toward its configuration type
+    fn record_type(&mut self, rel_path: &std::path::Path, operations: usize) {
+        if operations == 0 {
+            return;
+        }
+        if let Some(std::path::Component::Normal(kind)) = rel_path.components().next() {
+            *self
+                .by_type
+                .entry(kind.to_string_lossy().into_owned())
+                .or_insert(0) += operations;
+        }
     }
 }
 
@@ -51,12 +95,13 @@ impl SyncResult {
 mod integration_tests {
     use std::fs;
     use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
 
     use tempfile::TempDir;
 
     use super::*;
     use crate::comparison::ConflictStrategy;
-    use crate::config::{Config, SyncDirection};
+    use crate::config::{Config, FileType, SyncDirection, SyncRule};
 
     fn setup_test_dirs() -> (TempDir, TempDir) {
         let source = TempDir::new().unwrap();
@@ -72,6 +117,16 @@ mod integration_tests {
         fs::write(path, content).unwrap();
     }
 
+    fn set_mtime(dir: &Path, rel_path: &str, secs_since_epoch: u64) {
+        let time = UNIX_EPOCH + Duration::from_secs(secs_since_epoch);
+        fs::File::options()
+            .write(true)
+            .open(dir.join(rel_path))
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
     #[test]
     fn test_sync_create_new_files() {
         let (source_dir, dest_dir) = setup_test_dirs();
@@ -95,6 +150,76 @@ mod integration_tests {
         assert!(dest_dir.path().join("skills/skill1/SKILL.md").exists());
     }
 
+    #[test]
+    fn test_sync_tracks_bytes_copied() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/a.md", "12345");
+        create_test_file(source_dir.path(), "commands/nested/b.md", "1234567890");
+        create_test_file(source_dir.path(), "skills/skill1/SKILL.md", "abc");
+        create_test_file(source_dir.path(), "skills/skill1/extra.txt", "defg");
+
+        let config = Config::default();
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.created, 3);
+        assert_eq!(result.bytes_copied, 5 + 10 + 3 + 4);
+    }
+
+    #[test]
+    fn test_sync_bytes_copied_counts_overwrites_not_skips() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/changed.md", "new content");
+        create_test_file(dest_dir.path(), "agents/changed.md", "old");
+        create_test_file(source_dir.path(), "agents/same.md", "identical");
+        create_test_file(dest_dir.path(), "agents/same.md", "identical");
+
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.bytes_copied, "new content".len() as u64);
+    }
+
+    #[test]
+    fn test_sync_directory_update_moves_renamed_files() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/skill1/SKILL.md", "updated skill");
+        create_test_file(source_dir.path(), "skills/skill1/docs/new-name.md", "large reference");
+        create_test_file(dest_dir.path(), "skills/skill1/SKILL.md", "old skill");
+        create_test_file(dest_dir.path(), "skills/skill1/refs/old-name.md", "large reference");
+
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.updated, 1);
+        // Only SKILL.md is copied; the renamed file is moved in place
+        assert_eq!(result.bytes_copied, "updated skill".len() as u64);
+
+        let skill = dest_dir.path().join("skills/skill1");
+        assert_eq!(
+            fs::read_to_string(skill.join("docs/new-name.md")).unwrap(),
+            "large reference"
+        );
+        assert!(!skill.join("refs").exists());
+        assert_eq!(fs::read_to_string(skill.join("SKILL.md")).unwrap(), "updated skill");
+    }
+
     #[test]
     fn test_sync_skip_identical_files() {
         let (source_dir, dest_dir) = setup_test_dirs();
@@ -155,6 +280,676 @@ mod integration_tests {
         assert!(err_msg.contains("Conflict"));
     }
 
+    #[test]
+    fn test_plan_returns_actions_without_side_effects() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/new.md", "new");
+        create_test_file(source_dir.path(), "agents/same.md", "same");
+        create_test_file(dest_dir.path(), "agents/same.md", "same");
+        create_test_file(source_dir.path(), "agents/changed.md", "source version");
+        create_test_file(dest_dir.path(), "agents/changed.md", "dest version");
+        create_test_file(source_dir.path(), "skills/skill1/SKILL.md", "skill");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let actions = engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(actions.len(), 4);
+        assert!(actions.contains(&SyncAction::Create {
+            source: source_dir.path().join("agents/new.md"),
+            dest: dest_dir.path().join("agents/new.md"),
+        }));
+        assert!(actions.contains(&SyncAction::CreateDirectory {
+            source: source_dir.path().join("skills/skill1"),
+            dest: dest_dir.path().join("skills/skill1"),
+        }));
+        assert!(actions.contains(&SyncAction::Skip {
+            path: source_dir.path().join("agents/same.md"),
+            reason: "identical content".to_string(),
+        }));
+        assert!(actions.iter().any(|action| matches!(
+            action,
+            SyncAction::Conflict { dest, strategy: ConflictStrategy::Fail, .. }
+                if dest == &dest_dir.path().join("agents/changed.md")
+        )));
+
+        // Planning must not touch the destination
+        assert!(!dest_dir.path().join("agents/new.md").exists());
+        assert!(!dest_dir.path().join("skills/skill1").exists());
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("agents/changed.md")).unwrap(),
+            "dest version"
+        );
+    }
+
+    #[test]
+    fn test_single_job_plans_same_actions_in_same_order() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/new.md", "new");
+        create_test_file(source_dir.path(), "agents/same.md", "same");
+        create_test_file(dest_dir.path(), "agents/same.md", "same");
+        create_test_file(source_dir.path(), "commands/run.md", "run");
+        create_test_file(source_dir.path(), "hooks/pre.md", "pre");
+        create_test_file(source_dir.path(), "skills/skill1/SKILL.md", "skill");
+
+        let plan_with = |jobs| {
+            let config = Config {
+                jobs,
+                ..Config::default()
+            };
+            SyncEngine::new(config, SyncDirection::ToLocal)
+                .unwrap()
+                .plan(source_dir.path(), dest_dir.path())
+                .unwrap()
+        };
+
+        let serial = plan_with(Some(1));
+        assert_eq!(serial.len(), 5);
+        assert_eq!(serial, plan_with(Some(4)));
+        assert_eq!(serial, plan_with(None));
+    }
+
+    #[test]
+    fn test_custom_comparator_decides_identical_content() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "hooks/config.json", r#"{"a": 1, "b": 2}"#);
+        create_test_file(dest_dir.path(), "hooks/config.json", "{\n  \"b\": 2,\n  \"a\": 1\n}\n");
+
+        let config = Config {
+            command_extensions: Some(vec!["json".to_string()]),
+            ..Config::default()
+        };
+
+        // Hashing sees differently formatted files as a conflict
+        let engine = SyncEngine::new(config.clone(), SyncDirection::ToLocal).unwrap();
+        let plan = engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+        assert!(matches!(plan[..], [SyncAction::Conflict { .. }]));
+
+        let same_json = |a: &Path, b: &Path| -> crate::error::Result<bool> {
+            let parse = |path: &Path| -> crate::error::Result<serde_json::Value> {
+                Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+            };
+            Ok(parse(a)? == parse(b)?)
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal)
+            .unwrap()
+            .with_comparator(Box::new(same_json));
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.conflicts, 0);
+        assert_eq!(result.skip_reasons.get("identical content"), Some(&1));
+        assert!(
+            fs::read_to_string(dest_dir.path().join("hooks/config.json"))
+                .unwrap()
+                .starts_with("{\n")
+        );
+    }
+
+    #[test]
+    fn test_paths_differing_only_in_case_are_reported() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/Foo.md", "upper");
+        create_test_file(source_dir.path(), "agents/foo.md", "lower");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert_eq!(result.skip_reasons.get("case collision"), Some(&1));
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("agents/Foo.md")).unwrap(),
+            "upper"
+        );
+        assert!(!dest_dir.path().join("agents/foo.md").exists());
+    }
+
+    #[test]
+    fn test_file_and_directory_mismatch_is_reported() {
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+
+        // Source skill directory, destination file of the same name
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "skills/foo/SKILL.md", "skill");
+        create_test_file(dest_dir.path(), "skills/foo", "not a skill");
+
+        let err = engine.sync(source_dir.path(), dest_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("is a directory but"));
+        assert!(err.to_string().contains("skills/foo is a file"));
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("skills/foo")).unwrap(),
+            "not a skill"
+        );
+
+        // Source file, destination directory of the same name
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "agent");
+        create_test_file(dest_dir.path(), "agents/a.md/notes.txt", "notes");
+
+        let err = engine.plan(source_dir.path(), dest_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("is a file but"));
+        assert!(err.to_string().contains("agents/a.md is a directory"));
+    }
+
+    #[test]
+    fn test_decision_callback_explains_each_entry() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/changed.md", "source version");
+        create_test_file(dest_dir.path(), "agents/changed.md", "dest version");
+        create_test_file(source_dir.path(), "agents/new.md", "new");
+        create_test_file(source_dir.path(), "agents/same.md", "same");
+        create_test_file(dest_dir.path(), "agents/same.md", "same");
+
+        let lines = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = std::rc::Rc::clone(&lines);
+        let source_root = source_dir.path().to_path_buf();
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal)
+            .unwrap()
+            .with_decision_callback(Box::new(move |action| {
+                let path = action.source_path().strip_prefix(&source_root).unwrap();
+                sink.borrow_mut()
+                    .push(format!("{}: {}", path.display(), action.explain()));
+            }));
+        engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+
+        let lines = lines.borrow();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("agents/changed.md: Conflict: hashes differ, "));
+        assert_eq!(lines[1], "agents/new.md: Create: missing at destination");
+        assert_eq!(lines[2], "agents/same.md: Skip: identical content");
+    }
+
+    #[test]
+    fn test_failed_file_does_not_stop_the_others() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "conflicting");
+        create_test_file(dest_dir.path(), "agents/a.md", "local edit");
+        create_test_file(source_dir.path(), "agents/b.md", "b");
+        create_test_file(source_dir.path(), "agents/c.md", "c");
+
+        // The default conflict strategy fails on a.md
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let err = engine.sync(source_dir.path(), dest_dir.path()).unwrap_err();
+
+        assert!(err.to_string().contains("1 error(s)"));
+        assert!(dest_dir.path().join("agents/b.md").exists());
+        assert!(dest_dir.path().join("agents/c.md").exists());
+    }
+
+    #[test]
+    fn test_fail_fast_stops_at_first_failed_file() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "conflicting");
+        create_test_file(dest_dir.path(), "agents/a.md", "local edit");
+        create_test_file(source_dir.path(), "agents/b.md", "b");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal)
+            .unwrap()
+            .with_fail_fast(true);
+        let err = engine.sync(source_dir.path(), dest_dir.path()).unwrap_err();
+
+        assert!(err.to_string().contains("Conflict"));
+        assert!(!dest_dir.path().join("agents/b.md").exists());
+    }
+
+    #[test]
+    fn test_limit_defers_actions_past_the_limit() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        for name in ["a", "b", "c", "d", "e"] {
+            create_test_file(source_dir.path(), &format!("agents/{name}.md"), name);
+        }
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal)
+            .unwrap()
+            .with_limit(Some(2));
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.created, 2);
+        assert_eq!(result.deferred, 3);
+        let created = fs::read_dir(dest_dir.path().join("agents")).unwrap().count();
+        assert_eq!(created, 2);
+    }
+
+    #[test]
+    fn test_saved_plan_loads_back_unchanged() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        let plan_dir = TempDir::new().unwrap();
+        let plan_path = plan_dir.path().join("plan.json");
+
+        create_test_file(source_dir.path(), "agents/new.md", "new");
+        create_test_file(source_dir.path(), "agents/changed.md", "source version");
+        create_test_file(dest_dir.path(), "agents/changed.md", "dest version");
+        create_test_file(source_dir.path(), "skills/skill1/SKILL.md", "skill");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let actions = engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+        let plan =
+            PlanFile::new(engine.direction(), source_dir.path(), dest_dir.path(), actions).unwrap();
+        plan.save(&plan_path).unwrap();
+
+        assert_eq!(PlanFile::load(&plan_path).unwrap(), plan);
+    }
+
+    #[test]
+    fn test_apply_fresh_plan() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/new.md", "new");
+        create_test_file(source_dir.path(), "skills/skill1/SKILL.md", "skill");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let actions = engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+        let plan =
+            PlanFile::new(engine.direction(), source_dir.path(), dest_dir.path(), actions).unwrap();
+
+        let result = engine.apply(&plan).unwrap();
+
+        assert_eq!(result.created, 2);
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("agents/new.md")).unwrap(),
+            "new"
+        );
+        assert!(dest_dir.path().join("skills/skill1/SKILL.md").exists());
+    }
+
+    #[test]
+    fn test_apply_rejects_stale_plan() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/new.md", "new");
+        create_test_file(source_dir.path(), "agents/other.md", "other");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let actions = engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+        let plan =
+            PlanFile::new(engine.direction(), source_dir.path(), dest_dir.path(), actions).unwrap();
+        create_test_file(source_dir.path(), "agents/new.md", "edited after planning");
+
+        let err = engine.apply(&plan).unwrap_err();
+
+        assert!(err.to_string().contains("Stale plan"));
+        assert!(!dest_dir.path().join("agents/other.md").exists());
+    }
+
+    #[test]
+    fn test_plan_marks_pattern_exclusions_as_skips() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/keep.md", "keep");
+        create_test_file(source_dir.path(), "agents/ignore.md", "ignore");
+
+        let config = Config {
+            ignore: vec!["**/ignore.md".to_string()],
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let actions = engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(actions.len(), 2);
+        assert!(actions.contains(&SyncAction::Skip {
+            path: source_dir.path().join("agents/ignore.md"),
+            reason: "excluded by pattern".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_sync_direction_rule_excludes_only_in_its_direction() {
+        let config = Config {
+            rules: vec![SyncRule {
+                patterns: vec!["*.secret.md".to_string()],
+                direction: Some(SyncDirection::ToGlobal),
+                file_type: None,
+                include: false,
+            }],
+            ..Default::default()
+        };
+
+        for (direction, secret_synced) in [
+            (SyncDirection::ToGlobal, false),
+            (SyncDirection::ToLocal, true),
+        ] {
+            let (source_dir, dest_dir) = setup_test_dirs();
+            create_test_file(source_dir.path(), "agents/keys.secret.md", "secret");
+            create_test_file(source_dir.path(), "agents/plain.md", "plain");
+
+            let engine = SyncEngine::new(config.clone(), direction).unwrap();
+            let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+            assert!(dest_dir.path().join("agents/plain.md").exists());
+            assert_eq!(
+                dest_dir.path().join("agents/keys.secret.md").exists(),
+                secret_synced,
+                "{direction:?}"
+            );
+            if !secret_synced {
+                assert_eq!(result.skip_reasons.get("excluded by rule"), Some(&1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sync_rule_include_overrides_ignore_pattern() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/draft.md", "draft");
+        create_test_file(source_dir.path(), "agents/other.md", "other");
+
+        let config = Config {
+            ignore: vec!["agents/*".to_string()],
+            rules: vec![SyncRule {
+                patterns: vec!["agents/draft.md".to_string()],
+                direction: Some(SyncDirection::ToLocal),
+                file_type: None,
+                include: true,
+            }],
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert!(dest_dir.path().join("agents/draft.md").exists());
+        assert!(!dest_dir.path().join("agents/other.md").exists());
+    }
+
+    fn exclude_binary_assets_rule() -> SyncRule {
+        SyncRule {
+            patterns: vec!["skills/*/assets/**".to_string()],
+            direction: None,
+            file_type: Some(FileType::Binary),
+            include: false,
+        }
+    }
+
+    #[test]
+    fn test_sync_file_type_rule_excludes_binary_assets() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/demo/SKILL.md", "skill");
+        create_test_file(source_dir.path(), "skills/demo/assets/notes.txt", "text asset");
+        let logo = source_dir.path().join("skills/demo/assets/logo.png");
+        fs::write(&logo, [0x89, b'P', b'N', b'G', 0x00, 0x1a]).unwrap();
+
+        let config = Config {
+            rules: vec![exclude_binary_assets_rule()],
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        let skill = dest_dir.path().join("skills/demo");
+        assert_eq!(result.created, 1);
+        assert!(skill.join("SKILL.md").exists());
+        assert!(skill.join("assets/notes.txt").exists());
+        assert!(!skill.join("assets/logo.png").exists());
+
+        // The excluded asset must not make the skill look out of date
+        let actions = engine.plan(source_dir.path(), dest_dir.path()).unwrap();
+        assert!(matches!(
+            actions.as_slice(),
+            [SyncAction::Skip { reason, .. }] if reason == "identical content"
+        ));
+    }
+
+    #[test]
+    fn test_sync_file_type_rule_keeps_excluded_destination_files() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/demo/SKILL.md", "new skill");
+        create_test_file(dest_dir.path(), "skills/demo/SKILL.md", "old skill");
+        let dest_logo = dest_dir.path().join("skills/demo/assets/logo.png");
+        fs::create_dir_all(dest_logo.parent().unwrap()).unwrap();
+        fs::write(&dest_logo, [0x00, 0x01, 0x02]).unwrap();
+
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            rules: vec![exclude_binary_assets_rule()],
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.updated, 1);
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("skills/demo/SKILL.md")).unwrap(),
+            "new skill"
+        );
+        assert!(dest_logo.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_file_type_rule_excludes_symlinked_entries() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/real.md", "real");
+        std::os::unix::fs::symlink(
+            source_dir.path().join("agents/real.md"),
+            source_dir.path().join("agents/linked.md"),
+        )
+        .unwrap();
+
+        let config = Config {
+            rules: vec![SyncRule {
+                patterns: vec!["agents/**".to_string()],
+                direction: None,
+                file_type: Some(FileType::Symlink),
+                include: false,
+            }],
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert_eq!(result.skip_reasons.get("excluded by rule"), Some(&1));
+        assert!(!dest_dir.path().join("agents/linked.md").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_preserves_internal_relative_symlink() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/real.md", "real");
+        std::os::unix::fs::symlink("real.md", source_dir.path().join("agents/linked.md")).unwrap();
+
+        let config = Config {
+            preserve_symlinks: Some(true),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        let link = dest_dir.path().join("agents/linked.md");
+        assert_eq!(result.created, 2);
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new("real.md"));
+        assert_eq!(fs::read_to_string(&link).unwrap(), "real");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_preserves_dangling_symlink() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        fs::create_dir_all(source_dir.path().join("agents")).unwrap();
+        std::os::unix::fs::symlink("missing.md", source_dir.path().join("agents/dangling.md"))
+            .unwrap();
+
+        let config = Config {
+            preserve_symlinks: Some(true),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        let link = dest_dir.path().join("agents/dangling.md");
+        assert_eq!(result.created, 1);
+        assert!(result.errors.is_empty());
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new("missing.md"));
+
+        // The same dangling link is recognized as already synced
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+        assert_eq!(result.created, 0);
+        assert_eq!(result.skip_reasons.get("identical content"), Some(&1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_dereferences_symlinked_agents() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "shared/new.md", "new body");
+        create_test_file(source_dir.path(), "shared/updated.md", "updated body");
+        fs::create_dir_all(source_dir.path().join("agents")).unwrap();
+        fs::create_dir_all(dest_dir.path().join("agents")).unwrap();
+        std::os::unix::fs::symlink("../shared/new.md", source_dir.path().join("agents/new.md"))
+            .unwrap();
+        std::os::unix::fs::symlink(
+            source_dir.path().join("shared/updated.md"),
+            source_dir.path().join("agents/updated.md"),
+        )
+        .unwrap();
+        // The destination's own link must be replaced, not written through
+        create_test_file(dest_dir.path(), "elsewhere.md", "elsewhere");
+        std::os::unix::fs::symlink(
+            dest_dir.path().join("elsewhere.md"),
+            dest_dir.path().join("agents/updated.md"),
+        )
+        .unwrap();
+
+        let config = Config {
+            follow_symlinks: Some(true),
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!((result.created, result.updated), (1, 1));
+        for (name, content) in [("new.md", "new body"), ("updated.md", "updated body")] {
+            let dest = dest_dir.path().join("agents").join(name);
+            assert!(fs::symlink_metadata(&dest).unwrap().is_file());
+            assert_eq!(fs::read_to_string(&dest).unwrap(), content);
+        }
+        assert!(!dest_dir.path().join("shared").exists());
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("elsewhere.md")).unwrap(),
+            "elsewhere"
+        );
+    }
+
+    #[test]
+    fn test_ignore_pattern_excludes_skill_marker_but_syncs_the_rest() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/demo/SKILL.md", "upstream skill");
+        create_test_file(source_dir.path(), "skills/demo/assets/guide.md", "new guide");
+        create_test_file(source_dir.path(), "skills/demo/helper.py", "new helper");
+        create_test_file(dest_dir.path(), "skills/demo/SKILL.md", "customized skill");
+        create_test_file(dest_dir.path(), "skills/demo/helper.py", "old helper");
+
+        let config = Config {
+            ignore: vec!["skills/*/SKILL.md".to_string()],
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        let skill = dest_dir.path().join("skills/demo");
+        assert_eq!(result.created, 1);
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.skip_reasons.get("excluded by pattern"), Some(&1));
+        assert_eq!(
+            fs::read_to_string(skill.join("SKILL.md")).unwrap(),
+            "customized skill"
+        );
+        assert_eq!(fs::read_to_string(skill.join("helper.py")).unwrap(), "new helper");
+        assert_eq!(
+            fs::read_to_string(skill.join("assets/guide.md")).unwrap(),
+            "new guide"
+        );
+    }
+
+    #[test]
+    fn test_directory_patterns_exclude_skills_and_their_contents() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/experimental/SKILL.md", "wip");
+        create_test_file(source_dir.path(), "skills/stable/SKILL.md", "stable");
+        create_test_file(source_dir.path(), "skills/stable/drafts/idea.md", "draft");
+        create_test_file(source_dir.path(), "skills/stable/helper.py", "helper");
+
+        let config = Config {
+            ignore: vec![
+                "skills/experimental/".to_string(),
+                "skills/stable/drafts/".to_string(),
+            ],
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        // The experimental skill is skipped whole, the drafts file by file
+        assert_eq!(result.skip_reasons.get("excluded by pattern"), Some(&2));
+        assert!(!dest_dir.path().join("skills/experimental").exists());
+        assert!(!dest_dir.path().join("skills/stable/drafts").exists());
+        assert!(dest_dir.path().join("skills/stable/SKILL.md").exists());
+        assert!(dest_dir.path().join("skills/stable/helper.py").exists());
+    }
+
+    #[test]
+    fn test_sync_modified_since_skips_old_files() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/recent.md", "recent");
+        create_test_file(source_dir.path(), "agents/old.md", "old");
+        set_mtime(source_dir.path(), "agents/old.md", 1_000_000);
+
+        let config = Config {
+            modified_since: Some(UNIX_EPOCH + Duration::from_secs(2_000_000)),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.skip_reasons.get("older than --since"), Some(&1));
+        assert!(dest_dir.path().join("agents/recent.md").exists());
+        assert!(!dest_dir.path().join("agents/old.md").exists());
+    }
+
+    #[test]
+    fn test_sync_modified_since_dates_directories_by_newest_file() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/fresh/SKILL.md", "fresh");
+        create_test_file(source_dir.path(), "skills/fresh/notes.md", "notes");
+        set_mtime(source_dir.path(), "skills/fresh/SKILL.md", 1_000_000);
+        create_test_file(source_dir.path(), "skills/stale/SKILL.md", "stale");
+        set_mtime(source_dir.path(), "skills/stale/SKILL.md", 1_000_000);
+
+        let config = Config {
+            modified_since: Some(UNIX_EPOCH + Duration::from_secs(2_000_000)),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert!(dest_dir.path().join("skills/fresh/SKILL.md").exists());
+        assert!(!dest_dir.path().join("skills/stale").exists());
+    }
+
     #[test]
     fn test_sync_dry_run() {
         let (source_dir, dest_dir) = setup_test_dirs();
@@ -169,6 +964,7 @@ mod integration_tests {
 
         // Should report as created
         assert_eq!(result.created, 1);
+        assert_eq!(result.bytes_copied, 0);
         assert!(result.is_success());
 
         // But file should NOT actually exist (dry run)
@@ -442,4 +1238,677 @@ mod integration_tests {
             .unwrap();
         assert_eq!(content, "new content");
     }
+
+    #[test]
+    fn test_approved_conflicts_are_counted_as_resolved() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "new agent");
+        create_test_file(dest_dir.path(), "agents/a.md", "old agent");
+        create_test_file(source_dir.path(), "skills/s/SKILL.md", "new skill");
+        create_test_file(dest_dir.path(), "skills/s/SKILL.md", "old skill");
+        create_test_file(source_dir.path(), "agents/b.md", "created");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let approver = Box::new(|_action: &SyncAction| Ok(true));
+        let result = engine
+            .sync_with_approver(source_dir.path(), dest_dir.path(), Some(approver))
+            .unwrap();
+
+        assert_eq!(result.conflicts_resolved, 2);
+        assert_eq!(result.updated, 2);
+        assert_eq!(result.created, 1);
+        assert!(result.is_success());
+
+        // Conflicts a strategy already resolves don't need the approver
+        create_test_file(source_dir.path(), "agents/a.md", "newer agent");
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let approver = Box::new(|_action: &SyncAction| Ok(true));
+        let result = engine
+            .sync_with_approver(source_dir.path(), dest_dir.path(), Some(approver))
+            .unwrap();
+
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.conflicts_resolved, 0);
+    }
+
+    #[test]
+    fn test_sync_bidirectional_newest_wins() {
+        let (global_dir, local_dir) = setup_test_dirs();
+
+        // A first sync records both files as in sync
+        for dir in [global_dir.path(), local_dir.path()] {
+            create_test_file(dir, "agents/a.md", "A old");
+            create_test_file(dir, "agents/b.md", "B old");
+        }
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap();
+
+        // A is edited globally, B locally
+        create_test_file(global_dir.path(), "agents/a.md", "A new");
+        create_test_file(local_dir.path(), "agents/b.md", "B new");
+
+        // One-sided files travel in both directions too
+        create_test_file(global_dir.path(), "commands/global-only.md", "g");
+        create_test_file(local_dir.path(), "commands/local-only.md", "l");
+
+        let result = engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap();
+
+        assert_eq!(result.updated, 2);
+        assert_eq!(result.created, 2);
+        assert_eq!(result.conflicts, 0);
+        assert!(result.is_success());
+
+        for dir in [global_dir.path(), local_dir.path()] {
+            assert_eq!(fs::read_to_string(dir.join("agents/a.md")).unwrap(), "A new");
+            assert_eq!(fs::read_to_string(dir.join("agents/b.md")).unwrap(), "B new");
+            assert!(dir.join("commands/global-only.md").exists());
+            assert!(dir.join("commands/local-only.md").exists());
+        }
+    }
+
+    #[test]
+    fn test_sync_bidirectional_applies_rules_cutoff_and_case_collisions() {
+        let (global_dir, local_dir) = setup_test_dirs();
+
+        create_test_file(global_dir.path(), "agents/keys.secret.md", "secret");
+        create_test_file(global_dir.path(), "agents/old.md", "old");
+        set_mtime(global_dir.path(), "agents/old.md", 1_000_000);
+        create_test_file(global_dir.path(), "agents/Dup.md", "upper");
+        create_test_file(local_dir.path(), "agents/dup.md", "lower");
+        create_test_file(local_dir.path(), "agents/plain.md", "plain");
+        create_test_file(global_dir.path(), "skills/tool/SKILL.md", "skill");
+        create_test_file(global_dir.path(), "skills/tool/keys.secret.md", "secret");
+
+        let config = Config {
+            rules: vec![SyncRule {
+                patterns: vec!["*.secret.md".to_string()],
+                direction: None,
+                file_type: None,
+                include: false,
+            }],
+            modified_since: Some(UNIX_EPOCH + Duration::from_secs(2_000_000)),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap();
+
+        assert_eq!(result.skip_reasons.get("excluded by rule"), Some(&1));
+        assert_eq!(result.skip_reasons.get("older than --since"), Some(&1));
+        assert_eq!(result.skip_reasons.get("case collision"), Some(&1));
+        assert!(global_dir.path().join("agents/plain.md").exists());
+        assert!(!local_dir.path().join("agents/keys.secret.md").exists());
+        assert!(!local_dir.path().join("agents/old.md").exists());
+        assert!(!global_dir.path().join("agents/dup.md").exists());
+        assert!(local_dir.path().join("skills/tool/SKILL.md").exists());
+        assert!(!local_dir.path().join("skills/tool/keys.secret.md").exists());
+    }
+
+    #[test]
+    fn test_sync_bidirectional_two_sided_edit_is_conflict() {
+        let (global_dir, local_dir) = setup_test_dirs();
+
+        for dir in [global_dir.path(), local_dir.path()] {
+            create_test_file(dir, "agents/a.md", "synced");
+        }
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap();
+
+        // Both sides are edited; the global edit is newer
+        create_test_file(global_dir.path(), "agents/a.md", "global edit");
+        create_test_file(local_dir.path(), "agents/a.md", "local edit");
+        set_mtime(global_dir.path(), "agents/a.md", 2_000);
+        set_mtime(local_dir.path(), "agents/a.md", 1_000);
+
+        // The newer side does not silently win under the default strategy
+        let err = engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("Conflict"));
+        assert_eq!(
+            fs::read_to_string(local_dir.path().join("agents/a.md")).unwrap(),
+            "local edit"
+        );
+
+        // With the newer strategy it does
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Newer),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap();
+        assert_eq!(result.updated, 1);
+        assert_eq!(
+            fs::read_to_string(local_dir.path().join("agents/a.md")).unwrap(),
+            "global edit"
+        );
+    }
+
+    #[test]
+    fn test_sync_bidirectional_unordered_edit_is_conflict() {
+        let (global_dir, local_dir) = setup_test_dirs();
+
+        create_test_file(global_dir.path(), "agents/a.md", "global edit");
+        create_test_file(local_dir.path(), "agents/a.md", "local edit");
+        set_mtime(global_dir.path(), "agents/a.md", 1_000);
+        set_mtime(local_dir.path(), "agents/a.md", 1_000);
+
+        // Default strategy (Fail) surfaces the conflict as an error
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let err = engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("Conflict"));
+
+        // Skip strategy records it and leaves both sides untouched
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Skip),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine
+            .sync_bidirectional(local_dir.path(), global_dir.path(), None)
+            .unwrap();
+
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.updated, 0);
+        assert_eq!(
+            fs::read_to_string(global_dir.path().join("agents/a.md")).unwrap(),
+            "global edit"
+        );
+        assert_eq!(
+            fs::read_to_string(local_dir.path().join("agents/a.md")).unwrap(),
+            "local edit"
+        );
+    }
+
+    #[test]
+    fn test_undo_reverts_overwrite_and_create() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        let journal_dir = TempDir::new().unwrap();
+
+        create_test_file(source_dir.path(), "agents/a.md", "new a");
+        create_test_file(source_dir.path(), "skills/demo/SKILL.md", "# Demo");
+        create_test_file(dest_dir.path(), "agents/a.md", "original a");
+
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal)
+            .unwrap()
+            .with_journal(journal_dir.path().join("undo"));
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("agents/a.md")).unwrap(),
+            "new a"
+        );
+
+        let report = SyncJournal::undo(&journal_dir.path().join("undo"), false).unwrap();
+
+        assert_eq!(report.restored, 1);
+        assert_eq!(report.removed, 1);
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("agents/a.md")).unwrap(),
+            "original a"
+        );
+        assert!(!dest_dir.path().join("skills/demo").exists());
+    }
+
+    #[test]
+    fn test_dry_run_writes_no_journal() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        let journal_dir = TempDir::new().unwrap();
+
+        create_test_file(source_dir.path(), "agents/a.md", "new a");
+
+        let config = Config {
+            dry_run: Some(true),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal)
+            .unwrap()
+            .with_journal(journal_dir.path().join("undo"));
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert!(!journal_dir.path().join("undo").exists());
+    }
+
+    #[test]
+    fn test_hidden_files_synced_by_default() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/demo/SKILL.md", "skill");
+        create_test_file(source_dir.path(), "skills/demo/.env.example", "KEY=");
+        create_test_file(source_dir.path(), "commands/.draft.md", "draft");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert!(dest_dir.path().join("skills/demo/.env.example").exists());
+        assert!(dest_dir.path().join("commands/.draft.md").exists());
+    }
+
+    #[test]
+    fn test_include_hidden_false_skips_dotfiles() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/demo/SKILL.md", "skill");
+        create_test_file(source_dir.path(), "skills/demo/.env.example", "KEY=");
+        create_test_file(source_dir.path(), "commands/.draft.md", "draft");
+        create_test_file(source_dir.path(), "commands/.wip/cmd.md", "wip");
+        create_test_file(source_dir.path(), "commands/real.md", "real");
+        // A hidden file already at the destination is left alone
+        create_test_file(dest_dir.path(), "skills/demo/.prettierrc", "{}");
+        create_test_file(dest_dir.path(), "skills/demo/SKILL.md", "old skill");
+
+        let config = Config {
+            include_hidden: Some(false),
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        let skill = dest_dir.path().join("skills/demo");
+        assert_eq!(fs::read_to_string(skill.join("SKILL.md")).unwrap(), "skill");
+        assert!(!skill.join(".env.example").exists());
+        assert!(skill.join(".prettierrc").exists());
+        assert!(dest_dir.path().join("commands/real.md").exists());
+        assert!(!dest_dir.path().join("commands/.draft.md").exists());
+        assert!(!dest_dir.path().join("commands/.wip").exists());
+        assert_eq!(result.skip_reasons.get("hidden file"), Some(&2));
+    }
+
+    #[test]
+    fn test_custom_skill_marker_identifies_skills() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "skills/indexed/index.md", "skill");
+        create_test_file(source_dir.path(), "skills/indexed/helper.py", "helper");
+        create_test_file(source_dir.path(), "skills/classic/SKILL.md", "skill");
+
+        let config = Config {
+            skill_marker: Some("index.md".to_string()),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert!(dest_dir.path().join("skills/indexed/helper.py").exists());
+        assert!(!dest_dir.path().join("skills/classic").exists());
+    }
+
+    #[test]
+    fn test_command_extensions_add_mdx_commands() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "commands/plain.md", "plain");
+        create_test_file(source_dir.path(), "commands/web/rich.mdx", "rich");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+        assert!(dest_dir.path().join("commands/plain.md").exists());
+        assert!(!dest_dir.path().join("commands/web/rich.mdx").exists());
+
+        let config = Config {
+            command_extensions: Some(vec!["md".to_string(), ".mdx".to_string()]),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+        assert_eq!(result.created, 1);
+        assert!(dest_dir.path().join("commands/web/rich.mdx").exists());
+    }
+
+    #[test]
+    fn test_prune_removes_only_files_ccsync_created() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/kept.md", "kept");
+        create_test_file(source_dir.path(), "agents/removed.md", "removed");
+        create_test_file(source_dir.path(), "skills/old/SKILL.md", "skill");
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+        assert!(dest_dir.path().join(MANIFEST_FILE).exists());
+
+        create_test_file(dest_dir.path(), "agents/by-hand.md", "mine");
+        fs::remove_file(source_dir.path().join("agents/removed.md")).unwrap();
+        fs::remove_dir_all(source_dir.path().join("skills/old")).unwrap();
+
+        // Without --prune, nothing is removed
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+        assert!(dest_dir.path().join("agents/removed.md").exists());
+
+        let config = Config {
+            prune: Some(true),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.deleted, 2);
+        assert!(!dest_dir.path().join("agents/removed.md").exists());
+        assert!(!dest_dir.path().join("skills/old").exists());
+        assert!(dest_dir.path().join("agents/kept.md").exists());
+        assert!(dest_dir.path().join("agents/by-hand.md").exists());
+    }
+
+    #[test]
+    fn test_semantic_frontmatter_ignores_key_order() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/a.md", "---\nname: a\nmodel: x\n---\nBody\n");
+        create_test_file(dest_dir.path(), "agents/a.md", "---\nmodel: x\nname: a\n---\nBody\n");
+        create_test_file(source_dir.path(), "agents/b.md", "---\nname: b\nmodel: x\n---\nBody\n");
+        create_test_file(dest_dir.path(), "agents/b.md", "---\nmodel: y\nname: b\n---\nBody\n");
+
+        let config = Config {
+            semantic_frontmatter: Some(true),
+            conflict_strategy: Some(ConflictStrategy::Skip),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.skip_reasons.get("identical content"), Some(&1));
+        assert_eq!(result.conflicts, 1);
+    }
+
+    #[test]
+    fn test_max_file_size_skips_large_files() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+
+        create_test_file(source_dir.path(), "agents/small.md", "small");
+        create_test_file(source_dir.path(), "agents/large.md", &"x".repeat(4096));
+        create_test_file(source_dir.path(), "skills/demo/SKILL.md", "skill");
+        create_test_file(source_dir.path(), "skills/demo/assets/blob.bin", &"x".repeat(4096));
+
+        let config = Config {
+            max_file_size: Some(1024),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert!(dest_dir.path().join("agents/small.md").exists());
+        assert!(!dest_dir.path().join("agents/large.md").exists());
+        assert!(dest_dir.path().join("skills/demo/SKILL.md").exists());
+        assert!(!dest_dir.path().join("skills/demo/assets/blob.bin").exists());
+        assert_eq!(result.skip_reasons.get("exceeds max-file-size"), Some(&2));
+    }
+
+    #[test]
+    fn test_sync_many_merges_source_roots() {
+        let personal = TempDir::new().unwrap();
+        let team = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        create_test_file(personal.path(), "agents/mine.md", "mine");
+        create_test_file(team.path(), "agents/team.md", "team");
+        create_test_file(personal.path(), "agents/shared.md", "personal version");
+        create_test_file(team.path(), "agents/shared.md", "team version");
+
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Config::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let roots = [personal.path().to_path_buf(), team.path().to_path_buf()];
+
+        let result = engine.sync_many(&roots, dest.path()).unwrap();
+
+        // The later root wins the collision, which is reported as a conflict
+        assert_eq!(result.created, 3);
+        assert_eq!(result.conflicts, 1);
+        let read = |rel: &str| fs::read_to_string(dest.path().join(rel)).unwrap();
+        assert_eq!(read("agents/mine.md"), "mine");
+        assert_eq!(read("agents/team.md"), "team");
+        assert_eq!(read("agents/shared.md"), "team version");
+    }
+
+    #[test]
+    fn test_sync_many_processes_entries_in_path_order() {
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        create_test_file(first.path(), "commands/c.md", "c");
+        create_test_file(first.path(), "agents/z.md", "z");
+        create_test_file(second.path(), "agents/a.md", "a");
+
+        // The limit keeps the first two entries by relative path, whichever root has them
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal)
+            .unwrap()
+            .with_limit(Some(2));
+        let roots = [first.path().to_path_buf(), second.path().to_path_buf()];
+        let result = engine.sync_many(&roots, dest.path()).unwrap();
+
+        assert_eq!(result.created, 2);
+        assert_eq!(result.deferred, 1);
+        assert!(dest.path().join("agents/a.md").exists());
+        assert!(dest.path().join("agents/z.md").exists());
+        assert!(!dest.path().join("commands/c.md").exists());
+    }
+
+    #[test]
+    fn test_result_counts_operations_per_type() {
+        let (source, dest) = setup_test_dirs();
+        create_test_file(source.path(), "agents/new.md", "new");
+        create_test_file(source.path(), "agents/changed.md", "changed");
+        create_test_file(source.path(), "skills/review/SKILL.md", "skill");
+        create_test_file(source.path(), "commands/build.md", "build");
+        create_test_file(source.path(), "commands/same.md", "same");
+        create_test_file(dest.path(), "agents/changed.md", "old");
+        create_test_file(dest.path(), "commands/same.md", "same");
+
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync(source.path(), dest.path()).unwrap();
+
+        assert_eq!((result.created, result.updated, result.skipped), (3, 1, 1));
+        let by_type: Vec<_> = result.by_type.iter().map(|(k, &v)| (k.as_str(), v)).collect();
+        assert_eq!(by_type, [("agents", 2), ("commands", 1), ("skills", 1)]);
+    }
+
+    #[test]
+    fn test_verify_replans_source_changed_before_copy() {
+        let (source, dest) = setup_test_dirs();
+        create_test_file(source.path(), "agents/a.md", "new");
+        create_test_file(dest.path(), "agents/a.md", "old");
+        let changed = source.path().join("agents/a.md");
+
+        let config = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Default::default()
+        };
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal)
+            .unwrap()
+            .with_verify(true);
+
+        // The source now matches the destination, so the planned overwrite is moot
+        let mut approvals = 0;
+        let approver: ApprovalCallback = Box::new(move |_| {
+            approvals += 1;
+            if approvals == 1 {
+                fs::write(&changed, "old").unwrap();
+            }
+            Ok(true)
+        });
+        let result = engine
+            .sync_with_approver(source.path(), dest.path(), Some(approver))
+            .unwrap();
+
+        assert_eq!(result.updated, 0);
+        assert_eq!(result.skip_reasons.get("identical content"), Some(&1));
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("changed after it was planned"));
+    }
+
+    #[test]
+    fn test_verify_compares_against_planning_time_digest() {
+        let (source, dest) = setup_test_dirs();
+        create_test_file(source.path(), "agents/a.md", "planned");
+        let changed = source.path().join("agents/a.md");
+
+        // The source changes once planning is done, before anything executes
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal)
+            .unwrap()
+            .with_verify(true)
+            .with_decision_callback(Box::new(move |_| {
+                fs::write(&changed, "edited after planning").unwrap();
+            }));
+        let result = engine.sync(source.path(), dest.path()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("changed after it was planned"));
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/a.md")).unwrap(),
+            "edited after planning"
+        );
+    }
+
+    #[test]
+    fn test_sync_many_identical_overlap_is_not_a_conflict() {
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        create_test_file(first.path(), "agents/shared.md", "same");
+        create_test_file(second.path(), "agents/shared.md", "same");
+
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+        let roots = [first.path().to_path_buf(), second.path().to_path_buf()];
+
+        let result = engine.sync_many(&roots, dest.path()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert_eq!(result.conflicts, 0);
+    }
+
+    #[test]
+    fn test_sync_many_overlap_resolves_against_dest_per_strategy() {
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        create_test_file(first.path(), "agents/shared.md", "first");
+        create_test_file(second.path(), "agents/shared.md", "second");
+        create_test_file(dest.path(), "agents/shared.md", "local edit");
+        let roots = [first.path().to_path_buf(), second.path().to_path_buf()];
+
+        let skip = Config {
+            conflict_strategy: Some(ConflictStrategy::Skip),
+            ..Config::default()
+        };
+        let engine = SyncEngine::new(skip, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync_many(&roots, dest.path()).unwrap();
+
+        // One conflict between the roots, one with the destination
+        assert_eq!(result.conflicts, 2);
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/shared.md")).unwrap(),
+            "local edit"
+        );
+
+        let overwrite = Config {
+            conflict_strategy: Some(ConflictStrategy::Overwrite),
+            ..Config::default()
+        };
+        let engine = SyncEngine::new(overwrite, SyncDirection::ToLocal).unwrap();
+        let result = engine.sync_many(&roots, dest.path()).unwrap();
+
+        assert_eq!(result.updated, 1);
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/shared.md")).unwrap(),
+            "second"
+        );
+    }
+
+    fn baseline_engine(strategy: ConflictStrategy) -> SyncEngine {
+        let config = Config {
+            conflict_strategy: Some(strategy),
+            track_baseline: Some(true),
+            ..Default::default()
+        };
+        SyncEngine::new(config, SyncDirection::ToLocal).unwrap()
+    }
+
+    #[test]
+    fn test_baseline_applies_source_only_edit() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "v1");
+        create_test_file(source_dir.path(), "skills/s/SKILL.md", "v1");
+        let engine = baseline_engine(ConflictStrategy::Fail);
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+        assert!(dest_dir.path().join(BASELINE_FILE).is_file());
+
+        create_test_file(source_dir.path(), "agents/a.md", "v2");
+        create_test_file(source_dir.path(), "skills/s/SKILL.md", "v2");
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.updated, 2);
+        assert_eq!(result.conflicts, 0);
+        let agent = fs::read_to_string(dest_dir.path().join("agents/a.md")).unwrap();
+        assert_eq!(agent, "v2");
+    }
+
+    #[test]
+    fn test_baseline_keeps_destination_only_edit() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "v1");
+        let engine = baseline_engine(ConflictStrategy::Overwrite);
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        create_test_file(dest_dir.path(), "agents/a.md", "local edit");
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.updated, 0);
+        assert_eq!(
+            result.skip_reasons.get("destination changed since last sync"),
+            Some(&1)
+        );
+        let agent = fs::read_to_string(dest_dir.path().join("agents/a.md")).unwrap();
+        assert_eq!(agent, "local edit");
+    }
+
+    #[test]
+    fn test_baseline_reports_two_sided_edit_as_conflict() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "v1");
+        let engine = baseline_engine(ConflictStrategy::Skip);
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        create_test_file(source_dir.path(), "agents/a.md", "v2");
+        create_test_file(dest_dir.path(), "agents/a.md", "local edit");
+        let result = engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.updated, 0);
+    }
+
+    #[test]
+    fn test_baseline_not_written_without_opt_in() {
+        let (source_dir, dest_dir) = setup_test_dirs();
+        create_test_file(source_dir.path(), "agents/a.md", "v1");
+        let engine = SyncEngine::new(Config::default(), SyncDirection::ToLocal).unwrap();
+
+        engine.sync(source_dir.path(), dest_dir.path()).unwrap();
+
+        assert!(!dest_dir.path().join(BASELINE_FILE).exists());
+    }
 }
@@ -1,11 +1,17 @@
 //! Sync action determination logic
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
 
 use crate::comparison::{ComparisonResult, ConflictStrategy};
 
 /// Sync action to perform
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Serialized with an `action` tag (e.g. `"action": "create"`), paths as
+/// strings, and the strategy in kebab-case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
 pub enum SyncAction {
     /// Create new file at destination
     Create {
@@ -52,6 +58,57 @@ pub enum SyncAction {
     },
 }
 
+impl SyncAction {
+    /// Path of the source entry this action was planned for
+    ///
+    /// For a skip this is the skipped path.
+    #[must_use]
+    pub fn source_path(&self) -> &Path {
+        match self {
+            Self::Create { source, .. }
+            | Self::CreateDirectory { source, .. }
+            | Self::Conflict { source, .. }
+            | Self::DirectoryConflict { source, .. } => source,
+            Self::Skip { path, .. } => path,
+        }
+    }
+
+    /// One-line account of the decision, e.g. "Conflict: hashes differ, source newer"
+    #[must_use]
+    pub fn explain(&self) -> String {
+        let newer = |source_newer: bool| {
+            if source_newer {
+                "source newer"
+            } else {
+                "destination newer"
+            }
+        };
+        match self {
+            Self::Create { .. } => "Create: missing at destination".to_string(),
+            Self::CreateDirectory { .. } => {
+                "CreateDirectory: directory missing at destination".to_string()
+            }
+            Self::Skip { reason, .. } => format!("Skip: {reason}"),
+            Self::Conflict {
+                strategy,
+                source_newer,
+                ..
+            } => format!(
+                "Conflict: hashes differ, {} (strategy: {strategy:?})",
+                newer(*source_newer)
+            ),
+            Self::DirectoryConflict {
+                strategy,
+                source_newer,
+                ..
+            } => format!(
+                "DirectoryConflict: contents differ, {} (strategy: {strategy:?})",
+                newer(*source_newer)
+            ),
+        }
+    }
+}
+
 /// Resolves comparison results into sync actions
 pub struct SyncActionResolver;
 
@@ -69,6 +126,10 @@ impl SyncActionResolver {
                 path: dest,
                 reason: "source doesn't exist".to_string(),
             },
+            ComparisonResult::NeitherExists => SyncAction::Skip {
+                path: source,
+                reason: "both absent".to_string(),
+            },
             ComparisonResult::Conflict {
                 source_newer,
                 strategy,
@@ -81,3 +142,25 @@ impl SyncActionResolver {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_both_absent_resolves_to_skip() {
+        let action = SyncActionResolver::resolve(
+            "src/gone.md".into(),
+            "dst/gone.md".into(),
+            &ComparisonResult::NeitherExists,
+        );
+
+        assert_eq!(
+            action,
+            SyncAction::Skip {
+                path: "src/gone.md".into(),
+                reason: "both absent".to_string(),
+            }
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Last-synced state of a destination
+//!
+//! With `track_baseline` enabled, every sync records the content hash of each
+//! entry that ended up identical on both sides in `.ccsync-state.json` at the
+//! destination root. When the two sides of a path differ on a later sync, the
+//! record tells a one-sided edit (safe to apply, or nothing to do) from a
+//! genuine two-sided conflict.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use super::journal::{SyncJournal, to_hex};
+use crate::comparison::{DirectoryComparator, FileHasher};
+use crate::error::Result;
+
+/// State file name inside the destination root
+pub const BASELINE_FILE: &str = ".ccsync-state.json";
+
+/// How the two differing sides of a path changed since the last sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaselineChange {
+    /// Only the source changed, so the destination can be updated
+    SourceChanged,
+    /// Only the destination changed, so the source has nothing new
+    DestinationChanged,
+    /// Both sides changed, or the path has no recorded baseline
+    BothChanged,
+}
+
+/// Content hashes of entries as of the last sync, keyed by relative path
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    entries: BTreeMap<PathBuf, String>,
+}
+
+impl Baseline {
+    /// Load the baseline stored in `dest_root`
+    ///
+    /// A destination without a state file has an empty baseline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file exists but cannot be read or parsed.
+    pub fn load(dest_root: &Path) -> Result<Self> {
+        let path = dest_root.join(BASELINE_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read sync state: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid sync state: {}", path.display()))
+    }
+
+    /// Write the baseline to `dest_root`, if it changed
+    ///
+    /// When the sync already journaled changes, the previous state file is
+    /// journaled too, so `undo` restores the baseline along with the files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file cannot be written.
+    pub(crate) fn save(&self, dest_root: &Path, journal: Option<&SyncJournal>) -> Result<()> {
+        let path = dest_root.join(BASELINE_FILE);
+        if *self == Self::load(dest_root)? {
+            return Ok(());
+        }
+
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize sync state")?;
+        let journal = journal.filter(|journal| !journal.is_empty());
+        let backup = match journal {
+            Some(journal) => journal.backup(&path)?,
+            None => None,
+        };
+        fs::create_dir_all(dest_root)
+            .with_context(|| format!("Failed to create directory: {}", dest_root.display()))?;
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write sync state: {}", path.display()))?;
+        if let Some(journal) = journal {
+            journal.record_write(&path, backup)?;
+        }
+        Ok(())
+    }
+
+    /// Classify a path whose source and destination differ
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either side cannot be hashed.
+    pub fn classify(&self, rel_path: &Path, source: &Path, dest: &Path) -> Result<BaselineChange> {
+        let Some(recorded) = self.entries.get(rel_path) else {
+            return Ok(BaselineChange::BothChanged);
+        };
+
+        if digest(source)? == *recorded {
+            Ok(BaselineChange::DestinationChanged)
+        } else if digest(dest)? == *recorded {
+            Ok(BaselineChange::SourceChanged)
+        } else {
+            Ok(BaselineChange::BothChanged)
+        }
+    }
+
+    /// Whether both sides of a directory still match its recorded tree hash
+    ///
+    /// An unchanged directory is identical on both sides without comparing
+    /// it file by file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either side cannot be hashed.
+    pub fn unchanged(&self, rel_path: &Path, source: &Path, dest: &Path) -> Result<bool> {
+        let Some(recorded) = self.entries.get(rel_path) else {
+            return Ok(false);
+        };
+        if !source.is_dir() || !dest.is_dir() {
+            return Ok(false);
+        }
+
+        Ok(digest(source)? == *recorded && digest(dest)? == *recorded)
+    }
+
+    /// Record a path as synced if both sides now have the same content
+    ///
+    /// Paths that still differ keep their previous record.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either side cannot be hashed.
+    pub fn record(&mut self, rel_path: &Path, source: &Path, dest: &Path) -> Result<()> {
+        if !source.exists() || !dest.exists() {
+            return Ok(());
+        }
+
+        let source_digest = digest(source)?;
+        if digest(dest)? == source_digest {
+            self.entries.insert(rel_path.to_path_buf(), source_digest);
+        }
+        Ok(())
+    }
+}
+
+/// Hash of a file, or the tree hash of a directory
+pub(super) fn digest(path: &Path) -> Result<String> {
+    if path.is_dir() {
+        return Ok(to_hex(&DirectoryComparator::tree_hash(path)?));
+    }
+    Ok(to_hex(&FileHasher::hash(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// A source and destination file that were synced with `content`
+    fn synced(content: &str) -> (TempDir, PathBuf, PathBuf, Baseline) {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.md");
+        let dest = tmp.path().join("dest.md");
+        fs::write(&source, content).unwrap();
+        fs::write(&dest, content).unwrap();
+
+        let mut baseline = Baseline::default();
+        baseline
+            .record(Path::new("agent.md"), &source, &dest)
+            .unwrap();
+        (tmp, source, dest, baseline)
+    }
+
+    #[test]
+    fn test_classify_source_change() {
+        let (_tmp, source, dest, baseline) = synced("v1");
+        fs::write(&source, "v2").unwrap();
+
+        let change = baseline.classify(Path::new("agent.md"), &source, &dest);
+        assert_eq!(change.unwrap(), BaselineChange::SourceChanged);
+    }
+
+    #[test]
+    fn test_classify_destination_change() {
+        let (_tmp, source, dest, baseline) = synced("v1");
+        fs::write(&dest, "local edit").unwrap();
+
+        let change = baseline.classify(Path::new("agent.md"), &source, &dest);
+        assert_eq!(change.unwrap(), BaselineChange::DestinationChanged);
+    }
+
+    #[test]
+    fn test_classify_both_changed_or_unknown() {
+        let (_tmp, source, dest, baseline) = synced("v1");
+        fs::write(&source, "v2").unwrap();
+        fs::write(&dest, "local edit").unwrap();
+
+        let change = baseline.classify(Path::new("agent.md"), &source, &dest);
+        assert_eq!(change.unwrap(), BaselineChange::BothChanged);
+        let unknown = baseline.classify(Path::new("other.md"), &source, &dest);
+        assert_eq!(unknown.unwrap(), BaselineChange::BothChanged);
+    }
+
+    #[test]
+    fn test_unchanged_directory_matches_recorded_tree_hash() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source/demo");
+        let dest = tmp.path().join("dest/demo");
+        for dir in [&source, &dest] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("SKILL.md"), "skill").unwrap();
+        }
+        let rel_path = Path::new("skills/demo");
+        let mut baseline = Baseline::default();
+        baseline.record(rel_path, &source, &dest).unwrap();
+
+        assert!(baseline.unchanged(rel_path, &source, &dest).unwrap());
+
+        fs::write(dest.join("SKILL.md"), "local edit").unwrap();
+        assert!(!baseline.unchanged(rel_path, &source, &dest).unwrap());
+    }
+
+    #[test]
+    fn test_record_keeps_previous_entry_while_sides_differ() {
+        let (_tmp, source, dest, mut baseline) = synced("v1");
+        fs::write(&dest, "local edit").unwrap();
+        baseline
+            .record(Path::new("agent.md"), &source, &dest)
+            .unwrap();
+
+        let change = baseline.classify(Path::new("agent.md"), &source, &dest);
+        assert_eq!(change.unwrap(), BaselineChange::DestinationChanged);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let (tmp, _source, _dest, baseline) = synced("v1");
+        let root = tmp.path().join("dest-root");
+
+        baseline.save(&root, None).unwrap();
+
+        assert!(root.join(BASELINE_FILE).is_file());
+        assert_eq!(Baseline::load(&root).unwrap(), baseline);
+        assert_eq!(Baseline::load(tmp.path()).unwrap(), Baseline::default());
+    }
+}
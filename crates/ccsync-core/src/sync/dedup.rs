@@ -0,0 +1,109 @@
+//! Content index for hardlinking identical files
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use walkdir::WalkDir;
+
+use crate::comparison::FileHasher;
+use crate::error::Result;
+use crate::scanner::SCAN_TARGETS;
+
+/// Files under the destination roots, grouped by size
+///
+/// Built on first lookup by walking the roots; files written during the sync
+/// are added as they land, so later copies can link to them too.
+pub struct ContentIndex {
+    roots: Vec<PathBuf>,
+    by_size: RefCell<Option<HashMap<u64, Vec<PathBuf>>>>,
+}
+
+impl ContentIndex {
+    /// Index regular files under `roots`
+    #[must_use]
+    pub const fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            by_size: RefCell::new(None),
+        }
+    }
+
+    /// Index the configuration directories (`agents/`, `skills/`, ...) under
+    /// each of `roots`
+    ///
+    /// Nothing else under the roots, such as `projects/` or ccsync's own
+    /// `.ccsync` state and backups, is hashed or linked to.
+    #[must_use]
+    pub fn for_config_dirs(roots: &[&Path]) -> Self {
+        Self::new(
+            roots
+                .iter()
+                .flat_map(|root| SCAN_TARGETS.iter().map(|(name, _)| root.join(name)))
+                .collect(),
+        )
+    }
+
+    /// An indexed file in the same root as `dest` with the content of `source`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `source` cannot be read.
+    pub fn find(&self, source: &Path, dest: &Path) -> Result<Option<PathBuf>> {
+        let Some(root) = self.roots.iter().find(|root| dest.starts_with(root)) else {
+            return Ok(None);
+        };
+        let size = fs::metadata(source)
+            .with_context(|| format!("Failed to read metadata: {}", source.display()))?
+            .len();
+        let mut by_size = self.by_size.borrow_mut();
+        let by_size = by_size.get_or_insert_with(|| self.scan());
+        let Some(candidates) = by_size.get(&size) else {
+            return Ok(None);
+        };
+
+        let hash = FileHasher::hash(source)?;
+        Ok(candidates
+            .iter()
+            .filter(|candidate| candidate.starts_with(root) && candidate.as_path() != dest)
+            // Candidates replaced or removed since they were indexed fail here
+            .find(|candidate| {
+                fs::symlink_metadata(candidate).is_ok_and(|metadata| metadata.is_file())
+                    && FileHasher::hash(candidate).is_ok_and(|candidate| candidate == hash)
+            })
+            .cloned())
+    }
+
+    /// Add a file written during the sync
+    pub fn insert(&self, path: &Path) {
+        if let Some(by_size) = self.by_size.borrow_mut().as_mut()
+            && let Ok(metadata) = fs::symlink_metadata(path)
+            && metadata.is_file()
+        {
+            by_size
+                .entry(metadata.len())
+                .or_default()
+                .push(path.to_path_buf());
+        }
+    }
+
+    /// Walk the roots, skipping symlinks and unreadable entries
+    fn scan(&self) -> HashMap<u64, Vec<PathBuf>> {
+        let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for root in &self.roots {
+            for entry in WalkDir::new(root).into_iter().filter_map(std::result::Result::ok) {
+                if let Ok(metadata) = entry.metadata()
+                    && metadata.is_file()
+                {
+                    by_size
+                        .entry(metadata.len())
+                        .or_default()
+                        .push(entry.into_path());
+                }
+            }
+        }
+        by_size
+    }
+}
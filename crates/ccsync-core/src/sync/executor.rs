@@ -1,25 +1,222 @@
 //! Atomic file operations executor
 
-use std::fs;
-use std::path::Path;
+use std::borrow::Cow;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io, thread};
 
 use anyhow::Context;
+use tracing::{debug, debug_span, info, warn};
 
 use super::SyncResult;
 use super::actions::SyncAction;
-use crate::comparison::ConflictStrategy;
+use super::dedup::ContentIndex;
+use super::journal::SyncJournal;
+use crate::comparison::{
+    ConflictStrategy, DiffGenerator, DirectoryComparator, DirectoryComparison, FileHasher,
+};
 use crate::error::Result;
+use crate::scanner::incoming_name;
+
+/// Skip reason for destinations whose name Windows can't create
+const UNSUPPORTED_NAME_REASON: &str = "name not allowed on Windows";
+
+/// Skip reason for conflicts left alone because the destination is newer
+const DEST_NEWER_REASON: &str = "destination newer";
+
+/// Predicate deciding whether a file inside a synced directory is included
+pub type EntryFilter<'a> = &'a dyn Fn(&Path) -> bool;
+
+/// Low-level file copy, returning the number of bytes copied
+type CopyFn = fn(&Path, &Path) -> io::Result<u64>;
+
+/// Low-level owner change, setting a file's uid and gid
+type ChownFn = fn(&Path, u32, u32) -> io::Result<()>;
+
+/// How failed copies are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first failed attempt
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub initial_delay: Duration,
+    /// Error kinds considered transient and worth retrying
+    pub transient: &'static [io::ErrorKind],
+}
+
+impl RetryPolicy {
+    /// Error kinds retried by default (EAGAIN, EINTR, ETXTBSY, EBUSY, timeouts)
+    pub const TRANSIENT_ERRORS: &'static [io::ErrorKind] = &[
+        io::ErrorKind::WouldBlock,
+        io::ErrorKind::Interrupted,
+        io::ErrorKind::ExecutableFileBusy,
+        io::ErrorKind::ResourceBusy,
+        io::ErrorKind::TimedOut,
+    ];
+
+    /// Default number of retries
+    pub const DEFAULT_RETRIES: u32 = 2;
+
+    /// Retry transient errors `retries` times, starting with a 100ms delay
+    #[must_use]
+    pub const fn new(retries: u32) -> Self {
+        Self {
+            retries,
+            initial_delay: Duration::from_millis(100),
+            transient: Self::TRANSIENT_ERRORS,
+        }
+    }
+
+    /// Whether an error should be retried
+    fn is_transient(&self, error: &io::Error) -> bool {
+        self.transient.contains(&error.kind())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_RETRIES)
+    }
+}
 
 /// Executes file operations atomically
-pub struct FileOperationExecutor {
+#[allow(clippy::struct_excessive_bools)]
+pub struct FileOperationExecutor<'a> {
     dry_run: bool,
+    show_diff: bool,
+    /// Replace destination symlinks instead of writing through them
+    dereference: bool,
+    filter: Option<EntryFilter<'a>>,
+    journal: Option<&'a SyncJournal>,
+    /// Source and destination root pairs, set when symlinks are preserved
+    symlink_roots: Option<&'a [(PathBuf, PathBuf)]>,
+    /// Destination files to hardlink to instead of copying identical content
+    hardlinks: Option<&'a ContentIndex>,
+    /// Give copied files the owner and group of their source
+    preserve_ownership: bool,
+    retry: RetryPolicy,
+    copy: CopyFn,
+    chown: ChownFn,
 }
 
-impl FileOperationExecutor {
+impl<'a> FileOperationExecutor<'a> {
     /// Create a new executor
     #[must_use]
     pub const fn new(dry_run: bool) -> Self {
-        Self { dry_run }
+        Self {
+            dry_run,
+            show_diff: false,
+            dereference: false,
+            filter: None,
+            journal: None,
+            symlink_roots: None,
+            hardlinks: None,
+            preserve_ownership: false,
+            retry: RetryPolicy::new(RetryPolicy::DEFAULT_RETRIES),
+            copy: |source, dest| fs::copy(source, dest),
+            chown: set_owner,
+        }
+    }
+
+    /// In dry-run mode, log the diff of each conflict next to its plan line
+    #[must_use]
+    pub const fn with_show_diff(mut self, show_diff: bool) -> Self {
+        self.show_diff = show_diff;
+        self
+    }
+
+    /// Keep the destination self-contained when copying through symlinks
+    ///
+    /// A symlink already at a file's destination is replaced by a regular
+    /// file instead of having the content written to whatever it points at.
+    #[must_use]
+    pub const fn with_dereference(mut self, enabled: bool) -> Self {
+        self.dereference = enabled;
+        self
+    }
+
+    /// Retry copies that fail with transient errors according to `retry`
+    #[must_use]
+    pub const fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Restrict directory copies and updates to files accepted by `filter`
+    ///
+    /// The filter receives the path of each file on disk (source side, or
+    /// destination side for files only present there). Rejected files are
+    /// neither copied nor removed.
+    #[must_use]
+    pub const fn with_filter(mut self, filter: EntryFilter<'a>) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Replace the low-level copy, e.g. to inject faults in tests
+    #[cfg(test)]
+    const fn with_copy_fn(mut self, copy: CopyFn) -> Self {
+        self.copy = copy;
+        self
+    }
+
+    /// Replace the low-level owner change, e.g. to observe it in tests
+    #[cfg(test)]
+    const fn with_chown_fn(mut self, chown: ChownFn) -> Self {
+        self.chown = chown;
+        self
+    }
+
+    /// Record every change to the destination in `journal` so it can be undone
+    #[must_use]
+    pub const fn with_journal(mut self, journal: &'a SyncJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Recreate symlinks at the destination instead of copying their targets
+    ///
+    /// Each pair maps a source root to the destination root it syncs into.
+    /// Relative targets inside a source root are rewritten to reach the same
+    /// entry under its destination root; targets that escape the tree are
+    /// kept verbatim with a warning.
+    #[must_use]
+    pub const fn with_preserved_symlinks(mut self, roots: &'a [(PathBuf, PathBuf)]) -> Self {
+        self.symlink_roots = Some(roots);
+        self
+    }
+
+    /// Hardlink to an identical file in `index` instead of copying
+    ///
+    /// Existing destination files are replaced rather than written through,
+    /// so a file sharing its inode with another never changes both. Where
+    /// hardlinks are unsupported or fail (e.g. across filesystems), the file
+    /// is copied as usual.
+    #[must_use]
+    pub const fn with_hardlinks(mut self, index: &'a ContentIndex) -> Self {
+        self.hardlinks = Some(index);
+        self
+    }
+
+    /// Give each copied file the uid/gid of its source (Unix only)
+    ///
+    /// Changing the owner usually needs root; when it is not permitted the
+    /// file is still copied and a warning is logged.
+    #[must_use]
+    pub const fn with_preserved_ownership(mut self, enabled: bool) -> Self {
+        self.preserve_ownership = enabled;
+        self
+    }
+
+    /// Whether `path` is a symlink this executor recreates rather than follows
+    fn preserves(&self, path: &Path) -> bool {
+        self.symlink_roots.is_some()
+            && fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink())
+    }
+
+    /// Whether a file inside a directory passes the filter
+    fn includes(&self, path: &Path) -> bool {
+        self.filter.is_none_or(|filter| filter(path))
     }
 
     /// Execute a sync action
@@ -28,26 +225,41 @@ impl FileOperationExecutor {
     ///
     /// Returns an error if file operations fail.
     pub fn execute(&self, action: &SyncAction, result: &mut SyncResult) -> Result<()> {
+        let _span = debug_span!("execute", dry_run = self.dry_run).entered();
         match action {
             SyncAction::Create { source, dest } => {
+                if let Some(problem) = unsupported_name(dest) {
+                    Self::skip_unsupported_name(dest, &problem, result);
+                    return Ok(());
+                }
                 if self.dry_run {
-                    eprintln!("[DRY RUN] Would create: {}", dest.display());
+                    info!("[DRY RUN] Would create: {}", dest.display());
                 } else {
-                    Self::copy_file(source, dest)?;
+                    result.bytes_copied += self.write_file(source, dest)?;
                 }
                 result.created += 1;
             }
             SyncAction::CreateDirectory { source, dest } => {
+                if let Some(problem) = unsupported_name(dest) {
+                    Self::skip_unsupported_name(dest, &problem, result);
+                    return Ok(());
+                }
+                for (path, problem) in unsupported_entries(source) {
+                    Self::skip_unsupported_name(&path, &problem, result);
+                }
                 if self.dry_run {
-                    eprintln!("[DRY RUN] Would create directory: {}", dest.display());
+                    info!("[DRY RUN] Would create directory: {}", dest.display());
                 } else {
-                    Self::copy_directory(source, dest)?;
+                    if let Some(journal) = self.journal {
+                        journal.record_directory(dest)?;
+                    }
+                    result.bytes_copied += self.copy_directory(source, dest)?;
                 }
                 result.created += 1;
             }
             SyncAction::Skip { path, reason } => {
                 if self.dry_run {
-                    eprintln!("[DRY RUN] Would skip: {} ({})", path.display(), reason);
+                    info!("[DRY RUN] Would skip: {} ({})", path.display(), reason);
                 }
                 result.skipped += 1;
                 *result.skip_reasons.entry(reason.clone()).or_insert(0) += 1;
@@ -58,6 +270,9 @@ impl FileOperationExecutor {
                 strategy,
                 source_newer,
             } => {
+                if self.dry_run && self.show_diff {
+                    Self::preview_diff(source, dest);
+                }
                 self.handle_conflict(source, dest, *strategy, *source_newer, result)?;
             }
             SyncAction::DirectoryConflict {
@@ -66,12 +281,57 @@ impl FileOperationExecutor {
                 strategy,
                 source_newer,
             } => {
+                if self.dry_run && self.show_diff {
+                    self.preview_directory_diff(source, dest);
+                }
                 self.handle_directory_conflict(source, dest, *strategy, *source_newer, result)?;
             }
         }
         Ok(())
     }
 
+    /// Count a conflict whose destination is newer as skipped
+    fn skip_dest_newer(result: &mut SyncResult) {
+        result.skipped += 1;
+        *result
+            .skip_reasons
+            .entry(DEST_NEWER_REASON.to_string())
+            .or_insert(0) += 1;
+    }
+
+    /// Count an entry Windows can't create as skipped, with the reason why
+    fn skip_unsupported_name(path: &Path, problem: &str, result: &mut SyncResult) {
+        warn!("Skipping {}: {problem}", path.display());
+        result.skipped += 1;
+        *result
+            .skip_reasons
+            .entry(UNSUPPORTED_NAME_REASON.to_string())
+            .or_insert(0) += 1;
+    }
+
+    /// Log the diff a conflicting file would apply to its destination
+    fn preview_diff(source: &Path, dest: &Path) {
+        match DiffGenerator::generate(source, dest) {
+            Ok(diff) => info!("[DRY RUN] Diff for {}:\n{diff}", dest.display()),
+            Err(e) => warn!("[DRY RUN] Cannot diff {}: {e:#}", dest.display()),
+        }
+    }
+
+    /// Log a per-file summary of how a conflicting directory differs
+    fn preview_directory_diff(&self, source: &Path, dest: &Path) {
+        let name = dest.file_name().unwrap_or_default().to_string_lossy();
+        let summary = DirectoryComparator::compare_filtered(source, dest, &|path| {
+            self.includes(path)
+        })
+        .and_then(|comparison| {
+            DiffGenerator::generate_directory_summary(&comparison, source, dest, &name)
+        });
+        match summary {
+            Ok(diff) => info!("[DRY RUN] Diff for {}:\n{diff}", dest.display()),
+            Err(e) => warn!("[DRY RUN] Cannot diff {}: {e:#}", dest.display()),
+        }
+    }
+
     /// Handle a conflict according to strategy
     fn handle_conflict(
         &self,
@@ -82,6 +342,12 @@ impl FileOperationExecutor {
         result: &mut SyncResult,
     ) -> Result<()> {
         match strategy {
+            // A dry run records the failure and goes on, to list every conflict
+            ConflictStrategy::Fail if self.dry_run => {
+                info!("[DRY RUN] Would fail on conflict: {}", dest.display());
+                result.conflicts += 1;
+                result.would_fail += 1;
+            }
             ConflictStrategy::Fail => {
                 anyhow::bail!(
                     "Conflict: {} <-> {} (use --conflict to resolve)",
@@ -91,45 +357,120 @@ impl FileOperationExecutor {
             }
             ConflictStrategy::Overwrite => {
                 if self.dry_run {
-                    eprintln!("[DRY RUN] Would overwrite: {}", dest.display());
+                    info!("[DRY RUN] Would overwrite: {}", dest.display());
                 } else {
-                    Self::copy_file(source, dest)?;
+                    result.bytes_copied += self.write_file(source, dest)?;
                 }
                 result.updated += 1;
             }
             ConflictStrategy::Skip => {
                 if self.dry_run {
-                    eprintln!("[DRY RUN] Would skip conflict: {}", dest.display());
+                    info!("[DRY RUN] Would skip conflict: {}", dest.display());
+                }
+                result.conflicts += 1;
+            }
+            ConflictStrategy::KeepBoth => {
+                let (incoming, up_to_date) = self.incoming_target(source, dest, false)?;
+                if self.dry_run {
+                    info!("[DRY RUN] Would keep both: {}", incoming.display());
+                } else if up_to_date {
+                    debug!("Incoming copy is up to date: {}", incoming.display());
+                } else {
+                    result.bytes_copied += self.write_file(source, &incoming)?;
                 }
                 result.conflicts += 1;
+                result.kept_both.push(incoming);
             }
             ConflictStrategy::Newer => {
                 if source_newer {
                     if self.dry_run {
-                        eprintln!("[DRY RUN] Would update (source newer): {}", dest.display());
+                        info!("[DRY RUN] Would update (source newer): {}", dest.display());
                     } else {
-                        Self::copy_file(source, dest)?;
+                        result.bytes_copied += self.write_file(source, dest)?;
                     }
                     result.updated += 1;
                 } else {
                     if self.dry_run {
-                        eprintln!("[DRY RUN] Would skip (dest newer): {}", dest.display());
+                        info!("[DRY RUN] Would skip (dest newer): {}", dest.display());
                     }
-                    result.skipped += 1;
+                    Self::skip_dest_newer(result);
                 }
             }
         }
         Ok(())
     }
 
-    /// Copy file atomically
-    fn copy_file(source: &Path, dest: &Path) -> Result<()> {
+    /// Where to keep the incoming copy of `source` next to `dest`
+    ///
+    /// Returns the first of `name.incoming`, `name.incoming-2`, ... that is
+    /// free or already holds the same content, and whether it does. An
+    /// existing copy with other content is never overwritten.
+    fn incoming_target(&self, source: &Path, dest: &Path, is_dir: bool) -> Result<(PathBuf, bool)> {
+        let mut copy = 1;
+        loop {
+            let incoming = incoming_path(dest, is_dir, copy);
+            if fs::symlink_metadata(&incoming).is_err() {
+                return Ok((incoming, false));
+            }
+            let up_to_date = if is_dir {
+                incoming.is_dir()
+                    && DirectoryComparator::compare_filtered(source, &incoming, &|path| {
+                        self.includes(path)
+                    })?
+                    .is_identical()
+            } else {
+                incoming.is_file() && FileHasher::same_content(source, &incoming)?
+            };
+            if up_to_date {
+                return Ok((incoming, true));
+            }
+            copy += 1;
+        }
+    }
+
+    /// Copy a file, recording the change in the journal if one is set
+    fn write_file(&self, source: &Path, dest: &Path) -> Result<u64> {
+        let Some(journal) = self.journal else {
+            return self.copy_file(source, dest);
+        };
+
+        let backup = journal.backup(dest)?;
+        let bytes = self.copy_file(source, dest)?;
+        // A symlink whose target is not synced yet cannot be hashed
+        if dest.exists() {
+            journal.record_write(dest, backup)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Copy file atomically, returning the number of bytes copied
+    ///
+    /// Transient failures are retried with exponential backoff.
+    fn copy_file(&self, source: &Path, dest: &Path) -> Result<u64> {
+        debug!("Copying {} to {}", source.display(), dest.display());
+        if let Some(problem) = unsupported_name(dest) {
+            warn!("Skipping {}: {problem}", dest.display());
+            return Ok(0);
+        }
+
         // Create parent directory if needed
         if let Some(parent) = dest.parent() {
-            fs::create_dir_all(parent)
+            fs::create_dir_all(extended_length(parent))
                 .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
         }
 
+        if let Some(roots) = self.symlink_roots
+            && self.preserves(source)
+        {
+            Self::copy_symlink(source, dest, roots)?;
+            return Ok(0);
+        }
+
+        if self.dereference && fs::symlink_metadata(dest).is_ok_and(|m| m.is_symlink()) {
+            fs::remove_file(dest)
+                .with_context(|| format!("Failed to replace symlink {}", dest.display()))?;
+        }
+
         // If destination exists and is read-only, make it writable first
         if dest.exists() {
             let metadata = fs::metadata(dest)
@@ -156,12 +497,71 @@ impl FileOperationExecutor {
             }
         }
 
-        // Copy file
-        fs::copy(source, dest).with_context(|| {
-            format!("Failed to copy {} to {}", source.display(), dest.display())
-        })?;
+        let Some(index) = self.hardlinks else {
+            return self.copy_contents(source, dest);
+        };
+        let identical = index.find(source, dest)?;
+        if fs::symlink_metadata(dest).is_ok() {
+            fs::remove_file(dest)
+                .with_context(|| format!("Failed to replace {}", dest.display()))?;
+        }
+        let bytes = match identical {
+            Some(existing) if hard_link(&existing, dest) => 0,
+            _ => self.copy_contents(source, dest)?,
+        };
+        index.insert(dest);
+        Ok(bytes)
+    }
 
-        Ok(())
+    /// Copy file contents, retrying transient failures
+    ///
+    /// Ownership is copied too when preserved.
+    fn copy_contents(&self, source: &Path, dest: &Path) -> Result<u64> {
+        let bytes = self
+            .retry_transient(|| (self.copy)(&extended_length(source), &extended_length(dest)))
+            .with_context(|| {
+                format!("Failed to copy {} to {}", source.display(), dest.display())
+            })?;
+        if self.preserve_ownership {
+            copy_ownership(source, dest, self.chown);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Recreate the symlink at `source` at `dest`, rewriting its target
+    fn copy_symlink(source: &Path, dest: &Path, roots: &[(PathBuf, PathBuf)]) -> Result<()> {
+        let target = fs::read_link(source)
+            .with_context(|| format!("Failed to read symlink: {}", source.display()))?;
+        let target = rewrite_link_target(source, dest, &target, roots);
+
+        if fs::symlink_metadata(dest).is_ok() {
+            fs::remove_file(dest)
+                .with_context(|| format!("Failed to replace {}", dest.display()))?;
+        }
+        debug!("Linking {} to {}", dest.display(), target.display());
+        create_symlink(&target, dest)
+            .with_context(|| format!("Failed to create symlink: {}", dest.display()))
+    }
+
+    /// Run an I/O operation, retrying transient errors with exponential backoff
+    fn retry_transient<T>(&self, mut operation: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+        let mut delay = self.retry.initial_delay;
+        let mut attempt = 0;
+        loop {
+            match operation() {
+                Err(e) if attempt < self.retry.retries && self.retry.is_transient(&e) => {
+                    attempt += 1;
+                    warn!(
+                        "Transient error: {e}; retrying ({attempt}/{})",
+                        self.retry.retries
+                    );
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Handle a directory conflict according to strategy
@@ -174,6 +574,14 @@ impl FileOperationExecutor {
         result: &mut SyncResult,
     ) -> Result<()> {
         match strategy {
+            ConflictStrategy::Fail if self.dry_run => {
+                info!(
+                    "[DRY RUN] Would fail on directory conflict: {}",
+                    dest.display()
+                );
+                result.conflicts += 1;
+                result.would_fail += 1;
+            }
             ConflictStrategy::Fail => {
                 anyhow::bail!(
                     "Directory conflict: {} <-> {} (use --conflict to resolve)",
@@ -183,66 +591,223 @@ impl FileOperationExecutor {
             }
             ConflictStrategy::Overwrite => {
                 if self.dry_run {
-                    eprintln!("[DRY RUN] Would overwrite directory: {}", dest.display());
+                    info!("[DRY RUN] Would overwrite directory: {}", dest.display());
                 } else {
-                    // Remove destination and copy source
-                    if dest.exists() {
-                        fs::remove_dir_all(dest)?;
-                    }
-                    Self::copy_directory(source, dest)?;
+                    result.bytes_copied += self.update_directory(source, dest)?;
                 }
                 result.updated += 1;
             }
             ConflictStrategy::Skip => {
                 if self.dry_run {
-                    eprintln!("[DRY RUN] Would skip directory conflict: {}", dest.display());
+                    info!("[DRY RUN] Would skip directory conflict: {}", dest.display());
+                }
+                result.conflicts += 1;
+            }
+            ConflictStrategy::KeepBoth => {
+                let (incoming, up_to_date) = self.incoming_target(source, dest, true)?;
+                if self.dry_run {
+                    info!(
+                        "[DRY RUN] Would keep both directories: {}",
+                        incoming.display()
+                    );
+                } else if up_to_date {
+                    debug!("Incoming copy is up to date: {}", incoming.display());
+                } else {
+                    if let Some(journal) = self.journal {
+                        journal.record_directory(&incoming)?;
+                    }
+                    result.bytes_copied += self.copy_directory(source, &incoming)?;
                 }
                 result.conflicts += 1;
+                result.kept_both.push(incoming);
             }
             ConflictStrategy::Newer => {
                 if source_newer {
                     if self.dry_run {
-                        eprintln!(
+                        info!(
                             "[DRY RUN] Would update directory (source newer): {}",
                             dest.display()
                         );
                     } else {
-                        // Remove destination and copy source
-                        if dest.exists() {
-                            fs::remove_dir_all(dest)?;
-                        }
-                        Self::copy_directory(source, dest)?;
+                        result.bytes_copied += self.update_directory(source, dest)?;
                     }
                     result.updated += 1;
-                } else if self.dry_run {
-                    eprintln!("[DRY RUN] Would skip directory (dest newer): {}", dest.display());
-                    result.skipped += 1;
                 } else {
-                    result.skipped += 1;
+                    if self.dry_run {
+                        info!("[DRY RUN] Would skip directory (dest newer): {}", dest.display());
+                    }
+                    Self::skip_dest_newer(result);
                 }
             }
         }
         Ok(())
     }
 
-    /// Copy directory recursively
+    /// Bring an existing destination directory in line with source
+    ///
+    /// Renamed files are moved, removed files are deleted, and only added or
+    /// modified files are copied. The changes are made to a staged copy that
+    /// then replaces `dest`, so an interrupted update never leaves it half
+    /// done. Returns the number of bytes copied.
+    fn update_directory(&self, source: &Path, dest: &Path) -> Result<u64> {
+        let comparison =
+            DirectoryComparator::compare_filtered(source, dest, &|path| self.includes(path))?;
+
+        let writes = self.journal_update(&comparison, dest)?;
+        let bytes = self.replace_directory(&comparison, source, dest)?;
+        if let Some(journal) = self.journal {
+            for (path, backup) in writes {
+                // A symlink whose target is not synced yet cannot be hashed
+                if path.exists() {
+                    journal.record_write(&path, backup)?;
+                }
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Back up what a directory update removes or overwrites
+    ///
+    /// Returns the destination files the update writes, with their backups,
+    /// to record once they are in place.
+    fn journal_update(
+        &self,
+        comparison: &DirectoryComparison,
+        dest: &Path,
+    ) -> Result<Vec<(PathBuf, Option<PathBuf>)>> {
+        let Some(journal) = self.journal else {
+            return Ok(Vec::new());
+        };
+
+        let mut writes = Vec::new();
+        for rel_path in &comparison.removed {
+            journal.record_removal(&dest.join(rel_path))?;
+        }
+        for rename in &comparison.renamed {
+            journal.record_removal(&dest.join(&rename.from))?;
+            writes.push((dest.join(&rename.to), None));
+        }
+        for rel_path in comparison.added.iter().chain(&comparison.modified) {
+            let path = dest.join(rel_path);
+            let backup = journal.backup(&path)?;
+            writes.push((path, backup));
+        }
+        Ok(writes)
+    }
+
+    /// Update a staged copy of `dest` and swap it in by rename
+    ///
+    /// A failure while staging leaves `dest` untouched. If `dest` cannot be
+    /// moved aside (e.g. it is a mount point), it is updated in place.
+    fn replace_directory(
+        &self,
+        comparison: &DirectoryComparison,
+        source: &Path,
+        dest: &Path,
+    ) -> Result<u64> {
+        let staging = sibling_path(dest, "staging");
+        let old = sibling_path(dest, "old");
+        remove_leftover(&staging)?;
+        remove_leftover(&old)?;
+
+        if let Err(e) = copy_tree(dest, &staging) {
+            warn!("Cannot stage {} ({e}); updating it in place", dest.display());
+            discard_staging(&staging);
+            return self.apply_update(comparison, source, dest);
+        }
+        let bytes = match self.apply_update(comparison, source, &staging) {
+            Ok(bytes) => bytes,
+            Err(e) => return Err(discard_after(e, &staging)),
+        };
+
+        if let Err(e) = fs::rename(dest, &old) {
+            warn!("Cannot replace {} by rename ({e}); updating it in place", dest.display());
+            discard_staging(&staging);
+            return self.apply_update(comparison, source, dest);
+        }
+        if let Err(e) = fs::rename(&staging, dest) {
+            let error =
+                anyhow::Error::new(e).context(format!("Failed to replace {}", dest.display()));
+            let error = restore_original(error, &old, dest);
+            return Err(discard_after(error, &staging));
+        }
+        if let Err(e) = fs::remove_dir_all(&old) {
+            warn!("Failed to remove {}: {e}", old.display());
+        }
+        Ok(bytes)
+    }
+
+    /// Apply a directory comparison to `target`, without journaling
+    fn apply_update(
+        &self,
+        comparison: &DirectoryComparison,
+        source: &Path,
+        target: &Path,
+    ) -> Result<u64> {
+        for rel_path in &comparison.removed {
+            let path = target.join(rel_path);
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            Self::remove_empty_parents(&path, target)?;
+        }
+
+        for rename in &comparison.renamed {
+            let from = target.join(&rename.from);
+            let to = target.join(&rename.to);
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent).with_context(|| {
+                    format!("Failed to create parent directory for {}", to.display())
+                })?;
+            }
+            debug!("Renaming {} to {}", from.display(), to.display());
+            fs::rename(&from, &to).with_context(|| {
+                format!("Failed to rename {} to {}", from.display(), to.display())
+            })?;
+            Self::remove_empty_parents(&from, target)?;
+        }
+
+        let mut bytes = 0;
+        for rel_path in comparison.added.iter().chain(&comparison.modified) {
+            bytes += self.copy_file(&source.join(rel_path), &target.join(rel_path))?;
+        }
+
+        Ok(bytes)
+    }
+
+    /// Remove directories left empty between a deleted path and the root
+    fn remove_empty_parents(path: &Path, root: &Path) -> Result<()> {
+        let mut current = path.parent();
+        while let Some(dir) = current {
+            if dir == root || !dir.starts_with(root) || fs::read_dir(dir)?.next().is_some() {
+                break;
+            }
+            fs::remove_dir(dir)
+                .with_context(|| format!("Failed to remove directory {}", dir.display()))?;
+            current = dir.parent();
+        }
+        Ok(())
+    }
+
+    /// Copy directory recursively, returning the total number of bytes copied
+    ///
+    /// Files rejected by the filter are skipped.
     ///
     /// # Errors
     ///
     /// Returns an error if directory operations fail.
-    pub fn copy_directory(source: &Path, dest: &Path) -> Result<()> {
+    pub fn copy_directory(&self, source: &Path, dest: &Path) -> Result<u64> {
         // Create destination directory
-        fs::create_dir_all(dest)
+        fs::create_dir_all(extended_length(dest))
             .with_context(|| format!("Failed to create directory: {}", dest.display()))?;
 
         // Recursively copy contents
-        Self::copy_directory_contents(source, dest)?;
-
-        Ok(())
+        self.copy_directory_contents(source, dest)
     }
 
     /// Recursively copy directory contents
-    fn copy_directory_contents(source: &Path, dest: &Path) -> Result<()> {
+    fn copy_directory_contents(&self, source: &Path, dest: &Path) -> Result<u64> {
+        let mut bytes = 0;
+
         for entry in fs::read_dir(source)
             .with_context(|| format!("Failed to read directory: {}", source.display()))?
         {
@@ -251,18 +816,332 @@ impl FileOperationExecutor {
             let path = entry.path();
             let file_name = path.file_name().unwrap();
             let dest_path = dest.join(file_name);
+            // Already reported when the directory action started
+            if unsupported_name(Path::new(file_name)).is_some() {
+                continue;
+            }
 
-            if path.is_dir() {
-                Self::copy_directory(&path, &dest_path)?;
-            } else if path.is_file() {
-                Self::copy_file(&path, &dest_path)?;
+            if self.preserves(&path) {
+                if self.includes(&path) {
+                    bytes += self.write_file(&path, &dest_path)?;
+                }
+            } else if path.is_dir() {
+                bytes += self.copy_directory(&path, &dest_path)?;
+            } else if path.is_file() && self.includes(&path) {
+                bytes += self.write_file(&path, &dest_path)?;
             }
         }
 
-        Ok(())
+        Ok(bytes)
     }
 }
 
+/// Target for a copy at `dest` of the symlink at `source`
+///
+/// Absolute targets are kept. A relative target that resolves inside the
+/// source root holding the link is re-expressed relative to `dest`, pointing
+/// at the same entry under the matching destination root. One that escapes
+/// the tree is kept verbatim, with a warning since it may dangle.
+fn rewrite_link_target(
+    source: &Path,
+    dest: &Path,
+    target: &Path,
+    roots: &[(PathBuf, PathBuf)],
+) -> PathBuf {
+    if target.is_absolute() {
+        return target.to_path_buf();
+    }
+    let Some((source_root, dest_root)) = roots.iter().find(|(root, _)| source.starts_with(root))
+    else {
+        return target.to_path_buf();
+    };
+
+    let link_dir = source.parent().unwrap_or(source);
+    let resolved = normalize(&link_dir.join(target));
+    let Ok(rel_path) = resolved.strip_prefix(normalize(source_root)) else {
+        warn!(
+            "Symlink {} points outside {}; copying its target {} as-is",
+            source.display(),
+            source_root.display(),
+            target.display()
+        );
+        return target.to_path_buf();
+    };
+
+    let dest_dir = normalize(dest.parent().unwrap_or(dest));
+    relative_path(&normalize(&dest_root.join(rel_path)), &dest_dir)
+}
+
+/// Resolve `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Path of `target` relative to the directory `base`
+fn relative_path(target: &Path, base: &Path) -> PathBuf {
+    let target: Vec<_> = target.components().collect();
+    let base: Vec<_> = base.components().collect();
+    let common = target
+        .iter()
+        .zip(&base)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative: PathBuf = base[common..].iter().map(|_| Component::ParentDir).collect();
+    relative.extend(&target[common..]);
+    relative
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    let resolved = link.parent().map_or_else(|| target.to_path_buf(), |dir| dir.join(target));
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+/// Why Windows can't create `path`, if one of its names is reserved or invalid
+///
+/// Names such as `aux.md` or `a:b.md` are fine on macOS and Linux, but
+/// Windows refuses to create them.
+#[cfg(windows)]
+fn unsupported_name(path: &Path) -> Option<String> {
+    path.components().find_map(|component| match component {
+        Component::Normal(name) => windows_name_problem(&name.to_string_lossy()),
+        _ => None,
+    })
+}
+
+#[cfg(not(windows))]
+const fn unsupported_name(_path: &Path) -> Option<String> {
+    None
+}
+
+/// Entries inside `dir` whose names Windows can't create, with the reason
+/// for each
+///
+/// A directory with such a name is reported once, without its contents.
+#[cfg(windows)]
+fn unsupported_entries(dir: &Path) -> Vec<(PathBuf, String)> {
+    let mut found = Vec::new();
+    let mut entries = walkdir::WalkDir::new(dir).min_depth(1).into_iter();
+    while let Some(entry) = entries.next() {
+        let Ok(entry) = entry else { continue };
+        if let Some(problem) = windows_name_problem(&entry.file_name().to_string_lossy()) {
+            if entry.file_type().is_dir() {
+                entries.skip_current_dir();
+            }
+            found.push((entry.into_path(), problem));
+        }
+    }
+    found
+}
+
+#[cfg(not(windows))]
+const fn unsupported_entries(_dir: &Path) -> Vec<(PathBuf, String)> {
+    Vec::new()
+}
+
+/// Why Windows can't create a file or directory called `name`
+#[cfg(windows)]
+fn windows_name_problem(name: &str) -> Option<String> {
+    const RESERVED: [&str; 22] = [
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+        "COM8", "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+    const INVALID: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+
+    if let Some(c) = name.chars().find(|c| INVALID.contains(c) || c.is_control()) {
+        return Some(format!(
+            "'{name}' contains '{}', which Windows doesn't allow in file names",
+            c.escape_default()
+        ));
+    }
+    if name.ends_with(['.', ' ']) {
+        return Some(format!(
+            "'{name}' ends with a dot or space, which Windows strips"
+        ));
+    }
+    // Device names are reserved with any extension (`aux.md` is still AUX)
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    RESERVED
+        .iter()
+        .find(|reserved| reserved.eq_ignore_ascii_case(stem))
+        .map(|reserved| format!("'{name}' is the reserved Windows device name {reserved}"))
+}
+
+/// `path` with the `\\?\` prefix when it is too long for the legacy Windows API
+///
+/// Deep skill trees can exceed 260 characters; the extended-length form
+/// lifts that limit. It only applies to absolute paths, which are normalized
+/// first because Windows doesn't resolve `.`, `..` or `/` in that form.
+#[cfg(windows)]
+fn extended_length(path: &Path) -> Cow<'_, Path> {
+    const MAX_PATH: usize = 260;
+
+    let Some(text) = path.to_str() else {
+        return Cow::Borrowed(path);
+    };
+    if text.len() < MAX_PATH || !path.is_absolute() || text.starts_with(r"\\?\") {
+        return Cow::Borrowed(path);
+    }
+
+    let normalized = normalize(path);
+    let Some(text) = normalized.to_str() else {
+        return Cow::Borrowed(path);
+    };
+    let extended = text.strip_prefix(r"\\").map_or_else(
+        || format!(r"\\?\{text}"),
+        |unc| format!(r"\\?\UNC\{unc}"),
+    );
+    Cow::Owned(PathBuf::from(extended))
+}
+
+#[cfg(not(windows))]
+const fn extended_length(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+/// Hidden path next to `dest` used while replacing it (`.name.ccsync-tag`)
+fn sibling_path(dest: &Path, tag: &str) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(dest.file_name().unwrap_or_default());
+    name.push(format!(".ccsync-{tag}"));
+    dest.with_file_name(name)
+}
+
+/// Remove a staging directory left behind by an interrupted sync
+fn remove_leftover(path: &Path) -> Result<()> {
+    if fs::symlink_metadata(path).is_ok() {
+        fs::remove_dir_all(path)
+            .with_context(|| format!("Failed to remove leftover {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Remove a staging directory that is no longer needed, warning if it stays
+fn discard_staging(staging: &Path) {
+    if let Err(e) = fs::remove_dir_all(staging) {
+        warn!("Failed to remove {}: {e}", staging.display());
+    }
+}
+
+/// Remove a staging directory after `error`, adding to it if that fails too
+fn discard_after(error: anyhow::Error, staging: &Path) -> anyhow::Error {
+    match fs::remove_dir_all(staging) {
+        Ok(()) => error,
+        Err(e) => anyhow::anyhow!("{error:#}; {} was also left behind: {e}", staging.display()),
+    }
+}
+
+/// Move a directory set aside at `old` back to `dest` after a failed replace
+///
+/// If that fails too, the returned error says where the original contents are.
+fn restore_original(error: anyhow::Error, old: &Path, dest: &Path) -> anyhow::Error {
+    match fs::rename(old, dest) {
+        Ok(()) => error,
+        Err(e) => error.context(format!(
+            "Failed to restore {} ({e}); its previous contents are left at {}",
+            dest.display(),
+            old.display()
+        )),
+    }
+}
+
+/// Copy a directory tree as is, recreating symlinks rather than following them
+fn copy_tree(source: &Path, dest: &Path) -> io::Result<()> {
+    fs::create_dir(dest)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            create_symlink(&fs::read_link(entry.path())?, &target)?;
+        } else if file_type.is_dir() {
+            copy_tree(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Hardlink `dest` to `existing`, returning whether the link was made
+#[cfg(unix)]
+fn hard_link(existing: &Path, dest: &Path) -> bool {
+    match fs::hard_link(existing, dest) {
+        Ok(()) => {
+            debug!("Linked {} to identical {}", dest.display(), existing.display());
+            true
+        }
+        Err(e) => {
+            debug!("Cannot hardlink {}: {e}; copying instead", dest.display());
+            false
+        }
+    }
+}
+
+#[cfg(not(unix))]
+const fn hard_link(_existing: &Path, _dest: &Path) -> bool {
+    false
+}
+
+/// Give `dest` the owner and group of `source`, warning when not permitted
+#[cfg(unix)]
+fn copy_ownership(source: &Path, dest: &Path, chown: ChownFn) {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = match fs::metadata(source) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            warn!("Cannot read owner of {}: {e}", source.display());
+            return;
+        }
+    };
+    if let Err(e) = chown(dest, metadata.uid(), metadata.gid()) {
+        warn!("Cannot set owner of {}: {e}", dest.display());
+    }
+}
+
+#[cfg(not(unix))]
+const fn copy_ownership(_source: &Path, _dest: &Path, _chown: ChownFn) {}
+
+#[cfg(unix)]
+fn set_owner(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    std::os::unix::fs::chown(path, Some(uid), Some(gid))
+}
+
+#[cfg(not(unix))]
+const fn set_owner(_path: &Path, _uid: u32, _gid: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// The path next to `dest` for the `copy`-th kept-both incoming copy
+fn incoming_path(dest: &Path, is_dir: bool, copy: usize) -> PathBuf {
+    dest.with_file_name(incoming_name(
+        dest.file_name().unwrap_or_default(),
+        is_dir,
+        copy,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,8 +1158,11 @@ mod tests {
         fs::write(src.join("file1.txt"), "content1").unwrap();
         fs::write(src.join("file2.txt"), "content2").unwrap();
 
-        FileOperationExecutor::copy_directory(&src, &dst).unwrap();
+        let bytes = FileOperationExecutor::new(false)
+            .copy_directory(&src, &dst)
+            .unwrap();
 
+        assert_eq!(bytes, 16);
         assert!(dst.exists());
         assert!(dst.join("file1.txt").exists());
         assert!(dst.join("file2.txt").exists());
@@ -300,7 +1182,9 @@ mod tests {
         fs::write(src.join("root.txt"), "root").unwrap();
         fs::write(subdir.join("nested.txt"), "nested").unwrap();
 
-        FileOperationExecutor::copy_directory(&src, &dst).unwrap();
+        FileOperationExecutor::new(false)
+            .copy_directory(&src, &dst)
+            .unwrap();
 
         assert!(dst.exists());
         assert!(dst.join("root.txt").exists());
@@ -309,6 +1193,253 @@ mod tests {
         assert_eq!(fs::read_to_string(dst.join("subdir/nested.txt")).unwrap(), "nested");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_preserved_internal_symlink_is_recreated() {
+        let tmp = TempDir::new().unwrap();
+        let src_root = tmp.path().join("global");
+        let dst_root = tmp.path().join("local");
+        let skill = src_root.join("skills/demo");
+        fs::create_dir_all(&skill).unwrap();
+        fs::write(skill.join("SKILL.md"), "skill").unwrap();
+        std::os::unix::fs::symlink("SKILL.md", skill.join("README.md")).unwrap();
+
+        let dest = dst_root.join("skills/demo");
+        let roots = [(src_root, dst_root)];
+        let executor = FileOperationExecutor::new(false).with_preserved_symlinks(&roots);
+        executor.copy_directory(&skill, &dest).unwrap();
+
+        let link = dest.join("README.md");
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new("SKILL.md"));
+        assert_eq!(fs::read_to_string(&link).unwrap(), "skill");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_preserved_symlink_target_follows_destination_layout() {
+        let tmp = TempDir::new().unwrap();
+        let src_root = tmp.path().join("global");
+        let dst_root = tmp.path().join("project/local");
+        fs::create_dir_all(src_root.join("agents")).unwrap();
+        fs::write(src_root.join("shared.md"), "shared").unwrap();
+        std::os::unix::fs::symlink("../shared.md", src_root.join("agents/a.md")).unwrap();
+
+        let roots = [(src_root.clone(), dst_root.clone())];
+        let executor = FileOperationExecutor::new(false).with_preserved_symlinks(&roots);
+        let mut result = SyncResult::default();
+        // An incoming copy lands one level deeper than the original link
+        let action = SyncAction::Create {
+            source: src_root.join("agents/a.md"),
+            dest: dst_root.join("agents/incoming/a.md"),
+        };
+        executor.execute(&action, &mut result).unwrap();
+
+        assert_eq!(
+            fs::read_link(dst_root.join("agents/incoming/a.md")).unwrap(),
+            Path::new("../../shared.md")
+        );
+    }
+
+    #[test]
+    fn test_link_target_escaping_the_tree_is_kept() {
+        let roots = [(PathBuf::from("/home/u/.claude"), PathBuf::from("/p/.claude"))];
+
+        let target = rewrite_link_target(
+            Path::new("/home/u/.claude/agents/a.md"),
+            Path::new("/p/.claude/agents/a.md"),
+            Path::new("../../notes/a.md"),
+            &roots,
+        );
+
+        assert_eq!(target, Path::new("../../notes/a.md"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_identical_files_are_hardlinked() {
+        use std::os::unix::fs::MetadataExt;
+
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("global/skills/demo");
+        let dst_root = tmp.path().join("local");
+        fs::create_dir_all(src.join("assets")).unwrap();
+        fs::write(src.join("SKILL.md"), "skill").unwrap();
+        fs::write(src.join("assets/logo.png"), "logo").unwrap();
+        fs::write(src.join("logo.png"), "logo").unwrap();
+
+        let index = ContentIndex::new(vec![dst_root.clone()]);
+        let dest = dst_root.join("skills/demo");
+        let bytes = FileOperationExecutor::new(false)
+            .with_hardlinks(&index)
+            .copy_directory(&src, &dest)
+            .unwrap();
+
+        let first = fs::metadata(dest.join("assets/logo.png")).unwrap();
+        let second = fs::metadata(dest.join("logo.png")).unwrap();
+        assert_eq!((first.dev(), first.ino()), (second.dev(), second.ino()));
+        assert_eq!(first.nlink(), 2);
+        assert_eq!(fs::metadata(dest.join("SKILL.md")).unwrap().nlink(), 1);
+        // The linked copy transfers no bytes
+        assert_eq!(bytes, 9);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hardlinks_stay_within_config_directories() {
+        use std::os::unix::fs::MetadataExt;
+
+        let tmp = TempDir::new().unwrap();
+        let dst_root = tmp.path().join("global");
+        for unrelated in [".ccsync/undo/1/a.md", "projects/notes.md"] {
+            let path = dst_root.join(unrelated);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "shared").unwrap();
+        }
+        let src = tmp.path().join("a.md");
+        fs::write(&src, "shared").unwrap();
+
+        let index = ContentIndex::for_config_dirs(&[&dst_root]);
+        let dest = dst_root.join("agents/a.md");
+        fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        FileOperationExecutor::new(false)
+            .with_hardlinks(&index)
+            .copy_file(&src, &dest)
+            .unwrap();
+
+        assert_eq!(fs::metadata(&dest).unwrap().nlink(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_overwriting_a_hardlinked_file_leaves_its_twin() {
+        let tmp = TempDir::new().unwrap();
+        let dst_root = tmp.path().join("local");
+        fs::create_dir_all(&dst_root).unwrap();
+        fs::write(dst_root.join("a.md"), "shared").unwrap();
+        fs::hard_link(dst_root.join("a.md"), dst_root.join("b.md")).unwrap();
+        let src = tmp.path().join("b.md");
+        fs::write(&src, "changed").unwrap();
+
+        let index = ContentIndex::new(vec![dst_root.clone()]);
+        FileOperationExecutor::new(false)
+            .with_hardlinks(&index)
+            .copy_file(&src, &dst_root.join("b.md"))
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(dst_root.join("a.md")).unwrap(), "shared");
+        assert_eq!(fs::read_to_string(dst_root.join("b.md")).unwrap(), "changed");
+    }
+
+    #[cfg(unix)]
+    thread_local! {
+        static OWNERS_SET: std::cell::RefCell<Vec<(PathBuf, u32, u32)>> =
+            const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    /// Owner change that only records what it was asked to do
+    #[cfg(unix)]
+    #[allow(clippy::unnecessary_wraps)]
+    fn recording_chown(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+        OWNERS_SET.with_borrow_mut(|owners| owners.push((path.to_path_buf(), uid, gid)));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_preserved_ownership_passes_source_owner_to_chown() {
+        use std::os::unix::fs::MetadataExt;
+
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("source.md");
+        let dst = tmp.path().join("source-copy.md");
+        fs::write(&src, "owned").unwrap();
+        let source = fs::metadata(&src).unwrap();
+        OWNERS_SET.with_borrow_mut(Vec::clear);
+
+        FileOperationExecutor::new(false)
+            .with_chown_fn(recording_chown)
+            .copy_file(&src, &dst)
+            .unwrap();
+        assert!(OWNERS_SET.with_borrow(Vec::is_empty));
+
+        FileOperationExecutor::new(false)
+            .with_preserved_ownership(true)
+            .with_chown_fn(recording_chown)
+            .copy_file(&src, &dst)
+            .unwrap();
+        assert_eq!(
+            OWNERS_SET.with_borrow(Clone::clone),
+            [(dst, source.uid(), source.gid())]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[ignore = "needs root"]
+    fn test_preserved_ownership_is_applied() {
+        use std::os::unix::fs::{MetadataExt, chown};
+
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("source.md");
+        let dst = tmp.path().join("dest/source.md");
+        fs::write(&src, "owned").unwrap();
+        chown(&src, Some(4321), Some(4322)).unwrap();
+
+        FileOperationExecutor::new(false)
+            .with_preserved_ownership(true)
+            .copy_file(&src, &dst)
+            .unwrap();
+
+        let metadata = fs::metadata(&dst).unwrap();
+        assert_eq!((metadata.uid(), metadata.gid()), (4321, 4322));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_name_problems() {
+        let invalid = [
+            "aux", "AUX.md", "con.txt", "nul", "com1.md", "lpt9", "a:b.md", "what?.md", "draft.",
+        ];
+        for name in invalid {
+            assert!(windows_name_problem(name).is_some(), "{name}");
+        }
+        for name in ["auxiliary.md", "console.md", "com10.md", "SKILL.md", ".hidden"] {
+            assert!(windows_name_problem(name).is_none(), "{name}");
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_reserved_windows_name_is_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("source.md");
+        fs::write(&source, "agent").unwrap();
+        let dest = tmp.path().join("dest/agents/aux.md");
+
+        let mut result = SyncResult::default();
+        FileOperationExecutor::new(false)
+            .execute(&SyncAction::Create { source, dest: dest.clone() }, &mut result)
+            .unwrap();
+
+        assert_eq!(result.created, 0);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.skip_reasons.get(UNSUPPORTED_NAME_REASON), Some(&1));
+        assert!(!dest.exists());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_paths_get_extended_prefix() {
+        let short = Path::new(r"C:\Users\me\.claude\agents\a.md");
+        assert_eq!(extended_length(short), short);
+
+        let deep = format!(r"C:\Users\me\.claude\skills\{}SKILL.md", "nested\\".repeat(40));
+        let extended = extended_length(Path::new(&deep));
+        assert!(extended.to_str().unwrap().starts_with(r"\\?\C:\Users\me"));
+        assert!(extended.to_str().unwrap().ends_with(r"nested\SKILL.md"));
+    }
+
     #[test]
     fn test_copy_directory_empty() {
         let tmp = TempDir::new().unwrap();
@@ -317,7 +1448,9 @@ mod tests {
 
         fs::create_dir(&src).unwrap();
 
-        FileOperationExecutor::copy_directory(&src, &dst).unwrap();
+        FileOperationExecutor::new(false)
+            .copy_directory(&src, &dst)
+            .unwrap();
 
         assert!(dst.exists());
         assert!(dst.is_dir());
@@ -341,9 +1474,322 @@ mod tests {
         fs::set_permissions(&dst, perms).unwrap();
 
         // This should succeed even though destination is read-only
-        FileOperationExecutor::copy_file(&src, &dst).unwrap();
+        FileOperationExecutor::new(false).copy_file(&src, &dst).unwrap();
 
         // Verify the file was overwritten with new content
         assert_eq!(fs::read_to_string(&dst).unwrap(), "new content");
     }
+
+    thread_local! {
+        static COPY_ATTEMPTS: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+    }
+
+    /// Copy that fails with EAGAIN twice before succeeding
+    fn flaky_copy(source: &Path, dest: &Path) -> io::Result<u64> {
+        let attempt = COPY_ATTEMPTS.get() + 1;
+        COPY_ATTEMPTS.set(attempt);
+        if attempt <= 2 {
+            return Err(io::Error::from(io::ErrorKind::WouldBlock));
+        }
+        fs::copy(source, dest)
+    }
+
+    /// Copy that always fails with a non-transient error
+    fn denied_copy(_source: &Path, _dest: &Path) -> io::Result<u64> {
+        COPY_ATTEMPTS.set(COPY_ATTEMPTS.get() + 1);
+        Err(io::Error::from(io::ErrorKind::PermissionDenied))
+    }
+
+    fn no_delay(retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            initial_delay: Duration::ZERO,
+            ..RetryPolicy::new(retries)
+        }
+    }
+
+    #[test]
+    fn test_transient_copy_failures_are_retried() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("source.md");
+        let dst = tmp.path().join("dest.md");
+        fs::write(&src, "content").unwrap();
+        COPY_ATTEMPTS.set(0);
+
+        let executor = FileOperationExecutor::new(false)
+            .with_retry(no_delay(2))
+            .with_copy_fn(flaky_copy);
+        let mut result = SyncResult::default();
+        executor
+            .execute(
+                &SyncAction::Create {
+                    source: src,
+                    dest: dst.clone(),
+                },
+                &mut result,
+            )
+            .unwrap();
+
+        assert_eq!(COPY_ATTEMPTS.get(), 3);
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "content");
+        assert_eq!(result.created, 1);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_copy_gives_up_after_retries() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("source.md");
+        let dst = tmp.path().join("dest.md");
+        fs::write(&src, "content").unwrap();
+        COPY_ATTEMPTS.set(0);
+
+        let executor = FileOperationExecutor::new(false)
+            .with_retry(no_delay(1))
+            .with_copy_fn(flaky_copy);
+
+        assert!(executor.copy_file(&src, &dst).is_err());
+        assert_eq!(COPY_ATTEMPTS.get(), 2);
+        assert!(!dst.exists());
+    }
+
+    #[test]
+    fn test_permanent_copy_errors_are_not_retried() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("source.md");
+        fs::write(&src, "content").unwrap();
+        COPY_ATTEMPTS.set(0);
+
+        let executor = FileOperationExecutor::new(false)
+            .with_retry(no_delay(3))
+            .with_copy_fn(denied_copy);
+
+        assert!(
+            executor
+                .copy_file(&src, &tmp.path().join("dest.md"))
+                .is_err()
+        );
+        assert_eq!(COPY_ATTEMPTS.get(), 1);
+    }
+
+    #[test]
+    fn test_keep_both_writes_incoming_copy() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src/agent.md");
+        let dst = tmp.path().join("dst/agent.md");
+        fs::create_dir_all(src.parent().unwrap()).unwrap();
+        fs::create_dir_all(dst.parent().unwrap()).unwrap();
+        fs::write(&src, "incoming").unwrap();
+        fs::write(&dst, "existing").unwrap();
+        // An earlier incoming copy with other content is never overwritten
+        let earlier = tmp.path().join("dst/agent.incoming.md");
+        fs::write(&earlier, "older incoming").unwrap();
+
+        let mut result = SyncResult::default();
+        FileOperationExecutor::new(false)
+            .execute(
+                &SyncAction::Conflict {
+                    source: src,
+                    dest: dst.clone(),
+                    strategy: ConflictStrategy::KeepBoth,
+                    source_newer: true,
+                },
+                &mut result,
+            )
+            .unwrap();
+
+        let incoming = tmp.path().join("dst/agent.incoming-2.md");
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(&earlier).unwrap(), "older incoming");
+        assert_eq!(fs::read_to_string(&incoming).unwrap(), "incoming");
+        assert_eq!(fs::read_dir(tmp.path().join("dst")).unwrap().count(), 3);
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.kept_both, [incoming]);
+    }
+
+    #[test]
+    fn test_keep_both_leaves_identical_incoming_copy_alone() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src/my.skill");
+        let dst = tmp.path().join("dst/my.skill");
+        let incoming = tmp.path().join("dst/my.skill.incoming");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fs::create_dir_all(&incoming).unwrap();
+        fs::write(src.join("SKILL.md"), "incoming").unwrap();
+        fs::write(dst.join("SKILL.md"), "existing").unwrap();
+        fs::write(incoming.join("SKILL.md"), "incoming").unwrap();
+
+        let mut result = SyncResult::default();
+        FileOperationExecutor::new(false)
+            .execute(
+                &SyncAction::DirectoryConflict {
+                    source: src,
+                    dest: dst,
+                    strategy: ConflictStrategy::KeepBoth,
+                    source_newer: true,
+                },
+                &mut result,
+            )
+            .unwrap();
+
+        assert_eq!(result.bytes_copied, 0);
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.kept_both, [incoming]);
+        assert_eq!(fs::read_dir(tmp.path().join("dst")).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_dry_run_counts_conflicts_that_would_fail() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.md");
+        let dst = tmp.path().join("agent.md");
+        fs::write(&src, "incoming").unwrap();
+        fs::write(&dst, "existing").unwrap();
+        let action = SyncAction::Conflict {
+            source: src,
+            dest: dst.clone(),
+            strategy: ConflictStrategy::Fail,
+            source_newer: true,
+        };
+
+        let mut result = SyncResult::default();
+        FileOperationExecutor::new(true)
+            .execute(&action, &mut result)
+            .unwrap();
+
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.would_fail, 1);
+        assert!(result.errors.is_empty());
+        assert!(!result.is_success());
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "existing");
+
+        // The real run still aborts on the conflict
+        let mut result = SyncResult::default();
+        assert!(
+            FileOperationExecutor::new(false)
+                .execute(&action, &mut result)
+                .is_err()
+        );
+        assert_eq!(result.would_fail, 0);
+    }
+
+    #[test]
+    fn test_keep_both_dry_run_writes_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.md");
+        let dst = tmp.path().join("agent.md");
+        fs::write(&src, "incoming").unwrap();
+        fs::write(&dst, "existing").unwrap();
+
+        let mut result = SyncResult::default();
+        FileOperationExecutor::new(true)
+            .execute(
+                &SyncAction::Conflict {
+                    source: src,
+                    dest: dst,
+                    strategy: ConflictStrategy::KeepBoth,
+                    source_newer: false,
+                },
+                &mut result,
+            )
+            .unwrap();
+
+        let incoming = tmp.path().join("agent.incoming.md");
+        assert!(!incoming.exists());
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.kept_both, [incoming]);
+    }
+
+    #[test]
+    fn test_keep_both_directory_conflict() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src/my.skill");
+        let dst = tmp.path().join("dst/my.skill");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fs::write(src.join("SKILL.md"), "incoming").unwrap();
+        fs::write(dst.join("SKILL.md"), "existing").unwrap();
+
+        let mut result = SyncResult::default();
+        FileOperationExecutor::new(false)
+            .execute(
+                &SyncAction::DirectoryConflict {
+                    source: src,
+                    dest: dst.clone(),
+                    strategy: ConflictStrategy::KeepBoth,
+                    source_newer: true,
+                },
+                &mut result,
+            )
+            .unwrap();
+
+        let incoming = tmp.path().join("dst/my.skill.incoming");
+        assert_eq!(
+            fs::read_to_string(dst.join("SKILL.md")).unwrap(),
+            "existing"
+        );
+        assert_eq!(
+            fs::read_to_string(incoming.join("SKILL.md")).unwrap(),
+            "incoming"
+        );
+        assert_eq!(result.conflicts, 1);
+    }
+
+    #[test]
+    fn test_failed_directory_update_keeps_original() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src/skill");
+        let dst = tmp.path().join("dst/skill");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fs::write(src.join("SKILL.md"), "new").unwrap();
+        fs::write(dst.join("SKILL.md"), "old").unwrap();
+        fs::write(dst.join("stale.md"), "stale").unwrap();
+        let action = SyncAction::DirectoryConflict {
+            source: src,
+            dest: dst.clone(),
+            strategy: ConflictStrategy::Overwrite,
+            source_newer: true,
+        };
+        let entries = || -> Vec<_> {
+            fs::read_dir(tmp.path().join("dst"))
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect()
+        };
+
+        // The stale file is removed from the staged copy before the copy fails
+        let failing = FileOperationExecutor::new(false)
+            .with_retry(no_delay(0))
+            .with_copy_fn(denied_copy);
+        assert!(failing.execute(&action, &mut SyncResult::default()).is_err());
+
+        assert_eq!(fs::read_to_string(dst.join("SKILL.md")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(dst.join("stale.md")).unwrap(), "stale");
+        assert_eq!(entries(), vec!["skill"]);
+
+        let mut result = SyncResult::default();
+        FileOperationExecutor::new(false)
+            .execute(&action, &mut result)
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(dst.join("SKILL.md")).unwrap(), "new");
+        assert!(!dst.join("stale.md").exists());
+        assert_eq!(entries(), vec!["skill"]);
+        assert_eq!(result.updated, 1);
+    }
+
+    #[test]
+    fn test_failed_restore_names_the_set_aside_directory() {
+        let tmp = TempDir::new().unwrap();
+        let dest = tmp.path().join("skill");
+        let old = sibling_path(&dest, "old");
+
+        let error = restore_original(anyhow::anyhow!("rename failed"), &old, &dest);
+
+        let message = error.to_string();
+        assert!(message.contains("Failed to restore"));
+        assert!(message.contains(&old.display().to_string()));
+        assert!(format!("{error:#}").contains("rename failed"));
+    }
 }
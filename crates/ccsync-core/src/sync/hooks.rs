@@ -0,0 +1,89 @@
+//! Shell commands run around a sync
+//!
+//! `pre_sync` runs before anything is scanned, so it can update the source
+//! (e.g. `git pull`); `post_sync` runs once the sync has finished. A hook
+//! that exits nonzero fails the sync.
+
+use std::fmt;
+use std::process::Command;
+
+use anyhow::Context;
+
+use crate::error::Result;
+
+/// When a configured hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Before scanning the source
+    PreSync,
+    /// After the sync finished
+    PostSync,
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PreSync => "pre_sync",
+            Self::PostSync => "post_sync",
+        })
+    }
+}
+
+/// Run `command` through the shell in the current directory
+///
+/// Returns the hook's stdout followed by its stderr.
+///
+/// # Errors
+///
+/// Returns an error if the shell cannot be started or the command exits
+/// nonzero; the error includes the captured output.
+pub fn run(hook: Hook, command: &str) -> Result<String> {
+    let output = shell(command)
+        .output()
+        .with_context(|| format!("Failed to run {hook} hook '{command}'"))?;
+
+    let mut captured = String::from_utf8_lossy(&output.stdout).into_owned();
+    captured.push_str(&String::from_utf8_lossy(&output.stderr));
+
+    if !output.status.success() {
+        let captured = captured.trim_end();
+        if captured.is_empty() {
+            anyhow::bail!("{hook} hook '{command}' failed ({})", output.status);
+        }
+        anyhow::bail!("{hook} hook '{command}' failed ({}):\n{captured}", output.status);
+    }
+    Ok(captured)
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_output_is_captured() {
+        let output = run(Hook::PreSync, "echo out; echo err >&2").unwrap();
+        assert_eq!(output, "out\nerr\n");
+    }
+
+    #[test]
+    fn test_failing_hook_reports_output() {
+        let err = run(Hook::PostSync, "echo broken >&2; exit 3").unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("post_sync hook 'echo broken >&2; exit 3' failed"));
+        assert!(message.ends_with("\nbroken"));
+    }
+}
@@ -0,0 +1,492 @@
+//! Undo journal for the most recent sync
+//!
+//! While a sync runs, every write to the destination is recorded together
+//! with a backup of the content it replaced. `SyncJournal::undo` replays the
+//! entries in reverse: overwritten and removed files are restored from their
+//! backups and created files are deleted. Files changed again after the sync
+//! are detected by hash and left alone.
+
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::comparison::{FileHash, FileHasher};
+use crate::error::Result;
+
+/// Journal file name inside the journal directory
+const JOURNAL_FILE: &str = "journal.json";
+
+/// Directory holding backed-up content, relative to the journal directory
+const BACKUP_DIR: &str = "backups";
+
+/// A single recorded change to the destination
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum JournalEntry {
+    /// A file that did not exist before the sync
+    Created {
+        /// Destination path
+        path: PathBuf,
+        /// Hash of the content written by the sync
+        hash: String,
+    },
+    /// A file whose previous content was replaced
+    Overwritten {
+        /// Destination path
+        path: PathBuf,
+        /// Backup of the previous content, relative to the journal directory
+        backup: PathBuf,
+        /// Hash of the content written by the sync
+        hash: String,
+    },
+    /// A file deleted by the sync
+    Removed {
+        /// Destination path
+        path: PathBuf,
+        /// Backup of the deleted content, relative to the journal directory
+        backup: PathBuf,
+    },
+    /// A directory that did not exist before the sync
+    CreatedDirectory {
+        /// Destination path
+        path: PathBuf,
+    },
+}
+
+/// Outcome of undoing a sync
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UndoReport {
+    /// Files restored to their content before the sync
+    pub restored: usize,
+    /// Files created by the sync that were removed
+    pub removed: usize,
+    /// Files left in place, with the reason
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct JournalState {
+    entries: Vec<JournalEntry>,
+    backups: usize,
+    started: bool,
+}
+
+/// Records destination changes so the sync can be undone
+///
+/// The journal directory is only replaced once the first change is recorded,
+/// so a sync that changes nothing keeps the previous journal.
+#[derive(Debug)]
+pub struct SyncJournal {
+    dir: PathBuf,
+    state: RefCell<JournalState>,
+}
+
+impl SyncJournal {
+    /// Create a journal stored in `dir`
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            state: RefCell::default(),
+        }
+    }
+
+    /// Back up a file that is about to be overwritten
+    ///
+    /// Returns the backup location to pass to `record_write`, or `None` if
+    /// the file does not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup cannot be written.
+    pub(crate) fn backup(&self, path: &Path) -> Result<Option<PathBuf>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        self.start()?;
+        let mut state = self.state.borrow_mut();
+        let backup = Path::new(BACKUP_DIR).join(state.backups.to_string());
+        state.backups += 1;
+
+        fs::copy(path, self.dir.join(&backup))
+            .with_context(|| format!("Failed to back up {}", path.display()))?;
+        Ok(Some(backup))
+    }
+
+    /// Record a file written by the sync
+    ///
+    /// `backup` is the value returned by `backup` before the write.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the written file cannot be hashed.
+    pub(crate) fn record_write(&self, path: &Path, backup: Option<PathBuf>) -> Result<()> {
+        self.start()?;
+        let hash = to_hex(&FileHasher::hash(path)?);
+        let entry = match backup {
+            Some(backup) => JournalEntry::Overwritten {
+                path: path.to_path_buf(),
+                backup,
+                hash,
+            },
+            None => JournalEntry::Created {
+                path: path.to_path_buf(),
+                hash,
+            },
+        };
+        self.state.borrow_mut().entries.push(entry);
+        Ok(())
+    }
+
+    /// Back up and record a file that is about to be deleted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup cannot be written.
+    pub(crate) fn record_removal(&self, path: &Path) -> Result<()> {
+        if let Some(backup) = self.backup(path)? {
+            self.state.borrow_mut().entries.push(JournalEntry::Removed {
+                path: path.to_path_buf(),
+                backup,
+            });
+        }
+        Ok(())
+    }
+
+    /// Record a directory about to be created by the sync
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal directory cannot be prepared.
+    pub(crate) fn record_directory(&self, path: &Path) -> Result<()> {
+        if path.exists() {
+            return Ok(());
+        }
+        self.start()?;
+        self.state
+            .borrow_mut()
+            .entries
+            .push(JournalEntry::CreatedDirectory {
+                path: path.to_path_buf(),
+            });
+        Ok(())
+    }
+
+    /// Whether no changes have been recorded yet
+    pub(crate) fn is_empty(&self) -> bool {
+        self.state.borrow().entries.is_empty()
+    }
+
+    /// Write the recorded entries to disk
+    ///
+    /// Does nothing if no changes were recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal cannot be written.
+    pub fn save(&self) -> Result<()> {
+        let state = self.state.borrow();
+        if state.entries.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.dir.join(JOURNAL_FILE);
+        let json = serde_json::to_string_pretty(&state.entries)
+            .context("Failed to serialize sync journal")?;
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write sync journal: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Replace any previous journal the first time a change is recorded
+    fn start(&self) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        if state.started {
+            return Ok(());
+        }
+
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir).with_context(|| {
+                format!("Failed to clear previous journal: {}", self.dir.display())
+            })?;
+        }
+        let backups = self.dir.join(BACKUP_DIR);
+        fs::create_dir_all(&backups)
+            .with_context(|| format!("Failed to create directory: {}", backups.display()))?;
+        state.started = true;
+        Ok(())
+    }
+
+    /// Undo the sync recorded in `dir`
+    ///
+    /// Entries are replayed newest first. A file whose content no longer
+    /// matches what the sync wrote is skipped with a warning, as is a
+    /// removed file that has been recreated. Unless `dry_run` is set, the
+    /// journal is deleted afterwards so the same sync cannot be undone twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no journal or a file cannot be restored.
+    pub fn undo(dir: &Path, dry_run: bool) -> Result<UndoReport> {
+        let journal_path = dir.join(JOURNAL_FILE);
+        if !journal_path.is_file() {
+            anyhow::bail!(
+                "Nothing to undo: no sync journal at {}",
+                journal_path.display()
+            );
+        }
+
+        let content = fs::read_to_string(&journal_path)
+            .with_context(|| format!("Failed to read sync journal: {}", journal_path.display()))?;
+        let entries: Vec<JournalEntry> = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid sync journal: {}", journal_path.display()))?;
+
+        let mut report = UndoReport::default();
+        for entry in entries.iter().rev() {
+            match entry {
+                JournalEntry::Created { path, hash } => {
+                    if !path.exists() {
+                        continue;
+                    }
+                    if !matches_hash(path, hash)? {
+                        skip(&mut report, path, "modified after the sync, not removed");
+                        continue;
+                    }
+                    if !dry_run {
+                        fs::remove_file(path)
+                            .with_context(|| format!("Failed to remove {}", path.display()))?;
+                    }
+                    report.removed += 1;
+                }
+                JournalEntry::Overwritten { path, backup, hash } => {
+                    if !path.exists() || !matches_hash(path, hash)? {
+                        skip(&mut report, path, "modified after the sync, not restored");
+                        continue;
+                    }
+                    if !dry_run {
+                        restore(&dir.join(backup), path)?;
+                    }
+                    report.restored += 1;
+                }
+                JournalEntry::Removed { path, backup } => {
+                    if path.exists() {
+                        skip(&mut report, path, "recreated after the sync, not restored");
+                        continue;
+                    }
+                    if !dry_run {
+                        restore(&dir.join(backup), path)?;
+                    }
+                    report.restored += 1;
+                }
+                JournalEntry::CreatedDirectory { path } => {
+                    if !dry_run {
+                        remove_empty_dirs(path)?;
+                    }
+                }
+            }
+        }
+
+        if !dry_run {
+            fs::remove_dir_all(dir)
+                .with_context(|| format!("Failed to remove sync journal: {}", dir.display()))?;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Record a skipped entry and warn about it
+fn skip(report: &mut UndoReport, path: &Path, reason: &str) {
+    warn!("Skipping {}: {reason}", path.display());
+    report.skipped.push(format!("{}: {reason}", path.display()));
+}
+
+/// Whether a file still has the content the sync wrote
+fn matches_hash(path: &Path, hash: &str) -> Result<bool> {
+    Ok(path.is_file() && to_hex(&FileHasher::hash(path)?) == hash)
+}
+
+/// Copy a backup back into place
+fn restore(backup: &Path, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    // Replace rather than write through, in case the file is hardlinked
+    if fs::symlink_metadata(path).is_ok() {
+        fs::remove_file(path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    }
+    fs::copy(backup, path).with_context(|| format!("Failed to restore {}", path.display()))?;
+    Ok(())
+}
+
+/// Remove a directory tree if it contains no files
+///
+/// Directories that still hold files (e.g. skipped entries) are kept.
+fn remove_empty_dirs(path: &Path) -> Result<()> {
+    if !path.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory: {}", path.display()))?
+    {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_empty_dirs(&entry.path())?;
+        }
+    }
+    if fs::read_dir(path)?.next().is_none() {
+        fs::remove_dir(path)
+            .with_context(|| format!("Failed to remove directory {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Lowercase hex encoding of a hash
+pub(super) fn to_hex(hash: &FileHash) -> String {
+    let mut hex = String::with_capacity(hash.len() * 2);
+    for byte in hash {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Simulate an executor write: back up, write, record
+    fn write(journal: &SyncJournal, path: &Path, content: &str) {
+        let backup = journal.backup(path).unwrap();
+        fs::write(path, content).unwrap();
+        journal.record_write(path, backup).unwrap();
+    }
+
+    #[test]
+    fn test_undo_restores_overwritten_file() {
+        let tmp = TempDir::new().unwrap();
+        let journal_dir = tmp.path().join("journal");
+        let file = tmp.path().join("agent.md");
+        fs::write(&file, [0xde, 0xad, 0x00, 0xbe, 0xef]).unwrap();
+
+        let journal = SyncJournal::new(&journal_dir);
+        write(&journal, &file, "synced");
+        journal.save().unwrap();
+
+        let report = SyncJournal::undo(&journal_dir, false).unwrap();
+
+        assert_eq!(report.restored, 1);
+        assert_eq!(fs::read(&file).unwrap(), [0xde, 0xad, 0x00, 0xbe, 0xef]);
+        assert!(!journal_dir.exists());
+    }
+
+    #[test]
+    fn test_undo_removes_created_file_and_directory() {
+        let tmp = TempDir::new().unwrap();
+        let journal_dir = tmp.path().join("journal");
+        let skill = tmp.path().join("skills/demo");
+
+        let journal = SyncJournal::new(&journal_dir);
+        journal.record_directory(&skill).unwrap();
+        fs::create_dir_all(skill.join("assets")).unwrap();
+        write(&journal, &skill.join("SKILL.md"), "# Demo");
+        journal.save().unwrap();
+
+        let report = SyncJournal::undo(&journal_dir, false).unwrap();
+
+        assert_eq!(report.removed, 1);
+        assert!(!skill.exists());
+        assert!(tmp.path().join("skills").exists());
+    }
+
+    #[test]
+    fn test_undo_restores_removed_file() {
+        let tmp = TempDir::new().unwrap();
+        let journal_dir = tmp.path().join("journal");
+        let file = tmp.path().join("stale.md");
+        fs::write(&file, "old").unwrap();
+
+        let journal = SyncJournal::new(&journal_dir);
+        journal.record_removal(&file).unwrap();
+        fs::remove_file(&file).unwrap();
+        journal.save().unwrap();
+
+        let report = SyncJournal::undo(&journal_dir, false).unwrap();
+
+        assert_eq!(report.restored, 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_undo_skips_files_modified_after_sync() {
+        let tmp = TempDir::new().unwrap();
+        let journal_dir = tmp.path().join("journal");
+        let overwritten = tmp.path().join("overwritten.md");
+        let created = tmp.path().join("created.md");
+        fs::write(&overwritten, "original").unwrap();
+
+        let journal = SyncJournal::new(&journal_dir);
+        write(&journal, &overwritten, "synced");
+        write(&journal, &created, "synced");
+        journal.save().unwrap();
+
+        fs::write(&overwritten, "edited later").unwrap();
+        fs::write(&created, "edited later").unwrap();
+
+        let report = SyncJournal::undo(&journal_dir, false).unwrap();
+
+        assert_eq!(report.restored, 0);
+        assert_eq!(report.removed, 0);
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(fs::read_to_string(&overwritten).unwrap(), "edited later");
+        assert_eq!(fs::read_to_string(&created).unwrap(), "edited later");
+    }
+
+    #[test]
+    fn test_undo_dry_run_changes_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let journal_dir = tmp.path().join("journal");
+        let file = tmp.path().join("agent.md");
+        fs::write(&file, "original").unwrap();
+
+        let journal = SyncJournal::new(&journal_dir);
+        write(&journal, &file, "synced");
+        journal.save().unwrap();
+
+        let report = SyncJournal::undo(&journal_dir, true).unwrap();
+
+        assert_eq!(report.restored, 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "synced");
+        assert!(journal_dir.join(JOURNAL_FILE).exists());
+    }
+
+    #[test]
+    fn test_empty_journal_keeps_previous_one() {
+        let tmp = TempDir::new().unwrap();
+        let journal_dir = tmp.path().join("journal");
+        let file = tmp.path().join("agent.md");
+
+        let journal = SyncJournal::new(&journal_dir);
+        write(&journal, &file, "synced");
+        journal.save().unwrap();
+
+        SyncJournal::new(&journal_dir).save().unwrap();
+
+        assert!(journal_dir.join(JOURNAL_FILE).exists());
+    }
+
+    #[test]
+    fn test_undo_without_journal() {
+        let tmp = TempDir::new().unwrap();
+        let err = SyncJournal::undo(&tmp.path().join("missing"), false).unwrap_err();
+        assert!(err.to_string().contains("Nothing to undo"));
+    }
+}
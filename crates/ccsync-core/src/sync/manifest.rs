@@ -0,0 +1,200 @@
+//! Paths a destination received from ccsync
+//!
+//! Every sync records the entries it created in `.ccsync-manifest` at the
+//! destination root. With `prune`, a managed entry whose source is gone is
+//! removed from the destination; entries ccsync never created, such as files
+//! added by hand, are not in the manifest and are never touched.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use walkdir::WalkDir;
+
+use super::SyncResult;
+use super::journal::SyncJournal;
+use crate::error::Result;
+
+/// Manifest file name inside the destination root
+pub const MANIFEST_FILE: &str = ".ccsync-manifest";
+
+/// Relative paths of destination entries created by ccsync
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    paths: BTreeSet<PathBuf>,
+}
+
+impl Manifest {
+    /// Load the manifest stored in `dest_root`
+    ///
+    /// A destination without a manifest has no managed paths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest exists but cannot be read or parsed.
+    pub fn load(dest_root: &Path) -> Result<Self> {
+        let path = dest_root.join(MANIFEST_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read manifest: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid manifest: {}", path.display()))
+    }
+
+    /// Write the manifest to `dest_root`, if it changed
+    ///
+    /// The manifest is not journaled: an entry that `undo` removes is simply
+    /// forgotten the next time the manifest is pruned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest cannot be written.
+    pub(crate) fn save(&self, dest_root: &Path) -> Result<()> {
+        let path = dest_root.join(MANIFEST_FILE);
+        if *self == Self::load(dest_root)? {
+            return Ok(());
+        }
+
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize manifest")?;
+        fs::create_dir_all(dest_root)
+            .with_context(|| format!("Failed to create directory: {}", dest_root.display()))?;
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write manifest: {}", path.display()))
+    }
+
+    /// Record an entry created in the destination
+    pub fn record(&mut self, rel_path: &Path) {
+        self.paths.insert(rel_path.to_path_buf());
+    }
+
+    /// Whether ccsync created the entry at `rel_path`
+    #[must_use]
+    pub fn contains(&self, rel_path: &Path) -> bool {
+        self.paths.contains(rel_path)
+    }
+
+    /// Remove managed entries whose source no longer exists in any source root
+    ///
+    /// Removed entries are counted as deleted and recorded in `journal`.
+    /// Entries already gone from the destination are forgotten. In a dry run
+    /// nothing is removed and the manifest is left as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry cannot be removed.
+    pub(crate) fn prune(
+        &mut self,
+        source_roots: &[PathBuf],
+        dest_root: &Path,
+        dry_run: bool,
+        journal: Option<&SyncJournal>,
+        result: &mut SyncResult,
+    ) -> Result<()> {
+        let mut kept = BTreeSet::new();
+        for rel_path in std::mem::take(&mut self.paths) {
+            let dest = dest_root.join(&rel_path);
+            if fs::symlink_metadata(&dest).is_err() {
+                continue;
+            }
+            if source_roots
+                .iter()
+                .any(|root| fs::symlink_metadata(root.join(&rel_path)).is_ok())
+            {
+                kept.insert(rel_path);
+                continue;
+            }
+
+            if dry_run {
+                info!("[DRY RUN] Would prune: {}", dest.display());
+            } else {
+                info!("Pruning {}", dest.display());
+                remove(&dest, journal)?;
+            }
+            result.deleted += 1;
+            result.record_type(&rel_path, 1);
+            if dry_run {
+                kept.insert(rel_path);
+            }
+        }
+        self.paths = kept;
+        Ok(())
+    }
+}
+
+/// Remove a file or directory, journaling every removed file
+fn remove(path: &Path, journal: Option<&SyncJournal>) -> Result<()> {
+    let metadata = fs::symlink_metadata(path)
+        .with_context(|| format!("Failed to read metadata: {}", path.display()))?;
+    if !metadata.is_dir() {
+        if let Some(journal) = journal {
+            journal.record_removal(path)?;
+        }
+        return fs::remove_file(path)
+            .with_context(|| format!("Failed to remove {}", path.display()));
+    }
+
+    if let Some(journal) = journal {
+        for entry in WalkDir::new(path).into_iter().filter_map(std::result::Result::ok) {
+            if !entry.file_type().is_dir() {
+                journal.record_removal(entry.path())?;
+            }
+        }
+    }
+    fs::remove_dir_all(path).with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let mut manifest = Manifest::default();
+        manifest.record(Path::new("agents/a.md"));
+
+        manifest.save(tmp.path()).unwrap();
+
+        let loaded = Manifest::load(tmp.path()).unwrap();
+        assert!(loaded.contains(Path::new("agents/a.md")));
+        assert_eq!(loaded, manifest);
+    }
+
+    #[test]
+    fn test_prune_only_removes_managed_entries_missing_from_source() {
+        let tmp = TempDir::new().unwrap();
+        let source = tmp.path().join("global");
+        let dest = tmp.path().join("local");
+        fs::create_dir_all(source.join("agents")).unwrap();
+        fs::create_dir_all(dest.join("agents")).unwrap();
+        fs::write(source.join("agents/kept.md"), "kept").unwrap();
+        fs::write(dest.join("agents/kept.md"), "kept").unwrap();
+        fs::write(dest.join("agents/removed.md"), "removed").unwrap();
+        fs::write(dest.join("agents/by-hand.md"), "mine").unwrap();
+
+        let mut manifest = Manifest::default();
+        manifest.record(Path::new("agents/kept.md"));
+        manifest.record(Path::new("agents/removed.md"));
+        manifest.record(Path::new("agents/gone.md"));
+
+        let mut result = SyncResult::default();
+        manifest
+            .prune(&[source], &dest, false, None, &mut result)
+            .unwrap();
+
+        assert_eq!(result.deleted, 1);
+        assert!(!dest.join("agents/removed.md").exists());
+        assert!(dest.join("agents/kept.md").exists());
+        assert!(dest.join("agents/by-hand.md").exists());
+        assert!(manifest.contains(Path::new("agents/kept.md")));
+        assert!(!manifest.contains(Path::new("agents/removed.md")));
+        assert!(!manifest.contains(Path::new("agents/gone.md")));
+    }
+}
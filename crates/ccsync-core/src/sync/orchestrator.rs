@@ -1,27 +1,88 @@
 //! Sync orchestration - coordinates the sync workflow
 
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Instant, SystemTime};
 
 use anyhow::Context;
+use tracing::{debug, debug_span, error, info_span, warn};
+use walkdir::WalkDir;
 
 use super::SyncResult;
 use super::actions::{SyncAction, SyncActionResolver};
-use super::executor::FileOperationExecutor;
-use crate::comparison::{ConflictStrategy, DirectoryComparator, FileComparator};
-use crate::config::{Config, PatternMatcher, SyncDirection};
+use super::baseline::{Baseline, BaselineChange, digest};
+use super::dedup::ContentIndex;
+use super::executor::{EntryFilter, FileOperationExecutor, RetryPolicy};
+use super::hooks::{self, Hook};
+use super::journal::SyncJournal;
+use super::manifest::Manifest;
+use super::plan::PlanFile;
+use crate::comparison::{
+    BinaryDetector, ConflictStrategy, DirectoryComparator, FileComparator, FileHasher,
+    TimestampComparator,
+};
+use crate::config::{
+    Config, FileType, OutputFormat, PatternMatcher, RuleMatcher, SyncDirection,
+};
 use crate::error::Result;
-use crate::scanner::{FileFilter, Scanner};
+use crate::scanner::{FileFilter, Pattern, Scanner};
+
+/// Skip reason for files larger than `max_file_size`
+const OVERSIZED_REASON: &str = "exceeds max-file-size";
+
+/// Skip reason for entries with the same content on both sides
+const IDENTICAL_REASON: &str = "identical content";
+
+/// Skip reason for hidden entries excluded by `include_hidden = false`
+const HIDDEN_REASON: &str = "hidden file";
+
+/// Skip reason for entries rejected by the ignore/include patterns
+const PATTERN_REASON: &str = "excluded by pattern";
+
+/// Skip reason for entries whose path differs from another only in case
+const CASE_COLLISION_REASON: &str = "case collision";
+
+/// Skip reason for entries not modified since the `modified_since` cutoff
+const SINCE_REASON: &str = "older than --since";
+
+/// Skip reason for sources deleted between planning and copying (`verify`)
+const VANISHED_REASON: &str = "source removed during sync";
 
 /// Approval callback for interactive sync operations
 pub type ApprovalCallback = Box<dyn FnMut(&SyncAction) -> Result<bool>>;
 
+/// Callback receiving every per-entry decision as it is made, for tracing
+pub type DecisionCallback = Box<dyn Fn(&SyncAction)>;
+
+/// Content-equality check replacing hashing when comparing two files
+pub type ComparatorCallback = Box<dyn Fn(&Path, &Path) -> Result<bool>>;
+
 /// Main sync engine
 pub struct SyncEngine {
     config: Config,
-    /// Sync direction (currently unused, will be used for direction-specific rules and reporting)
-    #[allow(dead_code)]
+    /// Sync direction, used to select direction-specific rules
     direction: SyncDirection,
     pattern_matcher: Option<PatternMatcher>,
+    rule_matcher: RuleMatcher,
+    /// Where to record changes for `undo` (not used for dry runs)
+    journal_dir: Option<PathBuf>,
+    /// Maximum number of non-skip actions to execute per sync
+    limit: Option<usize>,
+    /// Abort at the first failed action instead of recording it and going on
+    fail_fast: bool,
+    /// Told about each planned action, when decisions are traced
+    on_decision: Option<DecisionCallback>,
+    /// Decides whether two files are identical, instead of hashing them
+    comparator: Option<ComparatorCallback>,
+    /// Re-hash each source just before copying it
+    verify: bool,
+}
+
+/// Digests of the sources a plan copies, taken when it was planned
+struct SourceCheck<'a> {
+    digests: BTreeMap<PathBuf, String>,
+    include_file: EntryFilter<'a>,
 }
 
 impl SyncEngine {
@@ -32,22 +93,148 @@ impl SyncEngine {
     /// Returns an error if pattern compilation fails.
     pub fn new(config: Config, direction: SyncDirection) -> Result<Self> {
         // Compile pattern matcher once during construction
-        let pattern_matcher = if !config.ignore.is_empty() || !config.include.is_empty() {
-            Some(PatternMatcher::with_patterns(
-                &config.ignore,
-                &config.include,
-            )?)
+        let pattern_matcher = if !config.ignore.is_empty()
+            || !config.include.is_empty()
+            || !config.exclude.is_empty()
+        {
+            Some(
+                PatternMatcher::with_patterns(&config.ignore, &config.include)?
+                    .with_excludes(&config.exclude)?,
+            )
         } else {
             None
         };
 
+        let rule_matcher = RuleMatcher::new(&config.rules)?;
+
         Ok(Self {
             config,
             direction,
             pattern_matcher,
+            rule_matcher,
+            journal_dir: None,
+            limit: None,
+            fail_fast: false,
+            on_decision: None,
+            comparator: None,
+            verify: false,
         })
     }
 
+    /// Record the changes of each sync in a journal stored in `dir`
+    ///
+    /// Each sync that changes something replaces the previous journal, so
+    /// `SyncJournal::undo` reverts the most recent one.
+    #[must_use]
+    pub fn with_journal(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.journal_dir = Some(dir.into());
+        self
+    }
+
+    /// Execute at most `limit` non-skip actions per sync, deferring the rest
+    ///
+    /// Deferred actions are counted in `SyncResult::deferred` and picked up
+    /// by the next sync.
+    #[must_use]
+    pub const fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Choose what happens when an action fails
+    ///
+    /// By default each failure is recorded in `SyncResult::errors` and the
+    /// remaining actions still run; the sync then fails once all are done.
+    /// With `fail_fast`, the first failure aborts the sync instead.
+    #[must_use]
+    pub const fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Hash each source when it is planned and again just before copying it
+    ///
+    /// A source whose content changed since it was planned (e.g. an editor
+    /// saved it mid-sync) is recorded in `SyncResult::warnings` and planned
+    /// again, so the copy matches the decision. Bidirectional syncs compare
+    /// each entry right before syncing it and are not affected.
+    #[must_use]
+    pub const fn with_verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Report every planned action, including skips, to `callback`
+    ///
+    /// Use [`SyncAction::explain`] to describe why each action was chosen.
+    #[must_use]
+    pub fn with_decision_callback(mut self, callback: DecisionCallback) -> Self {
+        self.on_decision = Some(callback);
+        self
+    }
+
+    /// Decide whether a source file and its destination are identical with
+    /// `comparator` instead of comparing their hashes
+    ///
+    /// Files the comparator declares equal are skipped as identical; others
+    /// are conflicts. It is only asked about files that exist on both sides,
+    /// and takes precedence over `semantic_frontmatter`. Files inside synced
+    /// directories are still compared by hash.
+    #[must_use]
+    pub fn with_comparator(mut self, comparator: ComparatorCallback) -> Self {
+        self.comparator = Some(comparator);
+        self
+    }
+
+    /// An engine with the same configuration that syncs the opposite way
+    ///
+    /// Only the configuration carries over; the journal, limit, and other
+    /// per-run settings do not.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if pattern compilation fails.
+    pub fn reversed(&self) -> Result<Self> {
+        Self::new(self.config.clone(), self.direction.opposite())
+    }
+
+    /// Whether this engine only previews changes (`dry_run` in the config)
+    #[must_use]
+    pub fn dry_run(&self) -> bool {
+        self.config.dry_run == Some(true)
+    }
+
+    /// Format of the final summary (`output_format` in the config)
+    #[must_use]
+    pub fn output_format(&self) -> OutputFormat {
+        self.config.output_format.unwrap_or_default()
+    }
+
+    /// Direction this engine syncs in
+    #[must_use]
+    pub const fn direction(&self) -> SyncDirection {
+        self.direction
+    }
+
+    /// Run the command configured for `hook` (`pre_sync` or `post_sync`)
+    ///
+    /// Dry runs skip hooks. Returns the hook's output, or `None` when no
+    /// command ran.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command cannot be started or exits nonzero.
+    pub fn run_hook(&self, hook: Hook) -> Result<Option<String>> {
+        let command = match hook {
+            Hook::PreSync => &self.config.pre_sync,
+            Hook::PostSync => &self.config.post_sync,
+        };
+        match command {
+            Some(command) if !self.dry_run() => hooks::run(hook, command).map(Some),
+            _ => Ok(None),
+        }
+    }
+
     /// Execute the sync operation
     ///
     /// # Errors
@@ -69,18 +256,376 @@ impl SyncEngine {
         &self,
         source_root: &Path,
         dest_root: &Path,
+        approver: Option<ApprovalCallback>,
+    ) -> Result<SyncResult> {
+        let started = Instant::now();
+        let _span = info_span!(
+            "sync",
+            source = %source_root.display(),
+            dest = %dest_root.display()
+        )
+        .entered();
+        let (actions, digests) = self.plan_with_digests(source_root, dest_root)?;
+
+        // Process each planned action, applying rules to files inside directories
+        let include_file = |path: &Path| self.includes_nested(path, source_root, dest_root);
+        let result = self.execute_plan(
+            &actions,
+            digests,
+            &include_file,
+            approver,
+            &[source_root.to_path_buf()],
+            dest_root,
+        )?;
+
+        Self::finish(result, started)
+    }
+
+    /// Apply a saved plan without re-scanning the source
+    ///
+    /// The plan is verified first: if any source it copies from changed or
+    /// disappeared since it was made, nothing is executed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plan is stale or any action fails.
+    pub fn apply(&self, plan: &PlanFile) -> Result<SyncResult> {
+        let started = Instant::now();
+        let (source_root, dest_root) = (&plan.source_root, &plan.dest_root);
+        let _span = info_span!(
+            "apply",
+            source = %source_root.display(),
+            dest = %dest_root.display()
+        )
+        .entered();
+        plan.verify()?;
+
+        let include_file = |path: &Path| self.includes_nested(path, source_root, dest_root);
+        let result = self.execute_plan(
+            &plan.actions,
+            plan.sources().clone(),
+            &include_file,
+            None,
+            std::slice::from_ref(source_root),
+            dest_root,
+        )?;
+
+        Self::finish(result, started)
+    }
+
+    /// Sync several source roots into one destination
+    ///
+    /// The roots are planned in order and merged by relative path: when two
+    /// roots provide the same path, the later root wins. If their contents
+    /// differ, the collision is logged and counted in `conflicts`. The winning
+    /// entry is then compared with the destination as usual, so the configured
+    /// conflict strategy still decides what happens there. The merged entries
+    /// are processed in relative path order, so `--limit` picks the same ones
+    /// on every run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a root cannot be planned or the sync fails.
+    pub fn sync_many(&self, source_roots: &[PathBuf], dest_root: &Path) -> Result<SyncResult> {
+        let started = Instant::now();
+        let _span = info_span!(
+            "sync_many",
+            roots = source_roots.len(),
+            dest = %dest_root.display()
+        )
+        .entered();
+
+        let mut actions: Vec<SyncAction> = Vec::new();
+        // Relative path -> (index in `actions`, root that planned it)
+        let mut planned: HashMap<PathBuf, (usize, &Path)> = HashMap::new();
+        let mut collisions = 0;
+        let mut digests = BTreeMap::new();
+        for root in source_roots {
+            let (root_actions, root_digests) = self.plan_with_digests(root, dest_root)?;
+            digests.extend(root_digests);
+            for action in root_actions {
+                let Ok(rel_path) = action.source_path().strip_prefix(root) else {
+                    actions.push(action);
+                    continue;
+                };
+                let rel_path = rel_path.to_path_buf();
+
+                if let Some(&(index, earlier_root)) = planned.get(&rel_path) {
+                    let earlier = earlier_root.join(&rel_path);
+                    if Self::contents_differ(&earlier, &root.join(&rel_path))? {
+                        warn!(
+                            "{} differs between {} and {}; using the latter",
+                            rel_path.display(),
+                            earlier_root.display(),
+                            root.display()
+                        );
+                        collisions += 1;
+                    }
+                    actions[index] = action;
+                    planned.insert(rel_path, (index, root));
+                } else {
+                    planned.insert(rel_path, (actions.len(), root));
+                    actions.push(action);
+                }
+            }
+        }
+        // Interleave later roots' entries with the earlier ones instead of appending them
+        actions.sort_by_cached_key(|action| {
+            let path = action.source_path();
+            source_roots
+                .iter()
+                .find_map(|root| path.strip_prefix(root).ok())
+                .unwrap_or(path)
+                .to_path_buf()
+        });
+
+        // Nested files are matched relative to whichever root they come from
+        let include_file = |path: &Path| {
+            let root = source_roots
+                .iter()
+                .find(|root| path.starts_with(root))
+                .map_or(dest_root, PathBuf::as_path);
+            self.includes_nested(path, root, dest_root)
+        };
+        let mut result =
+            self.execute_plan(&actions, digests, &include_file, None, source_roots, dest_root)?;
+        result.conflicts += collisions;
+
+        Self::finish(result, started)
+    }
+
+    /// Whether two source entries for the same path have different content
+    fn contents_differ(a: &Path, b: &Path) -> Result<bool> {
+        if a.is_dir() && b.is_dir() {
+            return Ok(!DirectoryComparator::compare(a, b)?.is_identical());
+        }
+        if a.is_file() && b.is_file() {
+            return Ok(!FileHasher::same_content(a, b)?);
+        }
+        Ok(true)
+    }
+
+    /// Run planned actions through approval and the executor
+    ///
+    /// With `verify`, each source is checked against its planning-time digest
+    /// in `digests` before it is copied. The journal is saved even if the user
+    /// aborts part-way through. The baseline, if tracked, is only updated when
+    /// the plan ran to the end, and leaves out actions deferred by the limit.
+    fn execute_plan(
+        &self,
+        actions: &[SyncAction],
+        digests: BTreeMap<PathBuf, String>,
+        include_file: EntryFilter,
         mut approver: Option<ApprovalCallback>,
+        source_roots: &[PathBuf],
+        dest_root: &Path,
     ) -> Result<SyncResult> {
         let mut result = SyncResult::default();
+        let journal = self.journal();
+        let symlink_roots: Vec<_> = source_roots
+            .iter()
+            .map(|root| (root.clone(), dest_root.to_path_buf()))
+            .collect();
+        let mut executor = FileOperationExecutor::new(self.config.dry_run == Some(true))
+            .with_filter(include_file)
+            .with_retry(self.retry_policy())
+            .with_show_diff(self.config.show_diff == Some(true))
+            .with_dereference(self.config.follow_symlinks == Some(true))
+            .with_preserved_ownership(self.config.preserve_ownership == Some(true));
+        if let Some(journal) = &journal {
+            executor = executor.with_journal(journal);
+        }
+        if self.config.preserve_symlinks == Some(true) {
+            executor = executor.with_preserved_symlinks(&symlink_roots);
+        }
+        let index = ContentIndex::for_config_dirs(&[dest_root]);
+        if self.config.hardlink_identical == Some(true) {
+            executor = executor.with_hardlinks(&index);
+        }
+        let check = self.verify.then_some(SourceCheck {
+            digests,
+            include_file,
+        });
+        let mut executed = 0;
+        let mut completed = actions.len();
+        let outcome = actions
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, action)| {
+                if self.defer(action, &mut executed, &mut result) {
+                    completed = completed.min(index);
+                    return Ok(());
+                }
+                let rel_path = source_roots
+                    .iter()
+                    .find_map(|root| action.source_path().strip_prefix(root).ok());
+                self.process_action(
+                    action,
+                    rel_path,
+                    &executor,
+                    &mut approver,
+                    check.as_ref(),
+                    &mut result,
+                )
+            })
+            .and_then(|()| {
+                let pending = completed < actions.len();
+                let completed = &actions[..completed];
+                self.update_manifest(
+                    completed,
+                    pending,
+                    source_roots,
+                    dest_root,
+                    journal.as_ref(),
+                    &mut result,
+                )?;
+                self.record_baseline(completed, source_roots, dest_root, journal.as_ref())
+            });
+
+        // Keep the journal of completed changes even if the user aborted
+        if let Some(journal) = &journal {
+            journal.save()?;
+        }
+        outcome?;
 
+        Ok(result)
+    }
+
+    /// Whether `action` is past the limit and must be deferred
+    ///
+    /// Non-skip actions within the limit are counted in `executed`.
+    const fn defer(&self, action: &SyncAction, executed: &mut usize, result: &mut SyncResult) -> bool {
+        let Some(limit) = self.limit else {
+            return false;
+        };
+        if matches!(action, SyncAction::Skip { .. }) {
+            return false;
+        }
+        if *executed >= limit {
+            result.deferred += 1;
+            return true;
+        }
+        *executed += 1;
+        false
+    }
+
+    /// Add the entries `actions` created to the destination's manifest
+    ///
+    /// With `prune`, managed entries whose source is gone are then removed,
+    /// unless actions are still `pending` after hitting the limit.
+    fn update_manifest(
+        &self,
+        actions: &[SyncAction],
+        pending: bool,
+        source_roots: &[PathBuf],
+        dest_root: &Path,
+        journal: Option<&SyncJournal>,
+        result: &mut SyncResult,
+    ) -> Result<()> {
+        let dry_run = self.config.dry_run == Some(true);
+        let prune = self.config.prune == Some(true) && !pending;
+        if dry_run && !prune {
+            return Ok(());
+        }
+
+        let mut manifest = Manifest::load(dest_root)?;
+        for action in actions {
+            let (SyncAction::Create { dest, .. } | SyncAction::CreateDirectory { dest, .. }) = action
+            else {
+                continue;
+            };
+            // Creates the user declined or that failed left nothing behind
+            if let Ok(rel_path) = dest.strip_prefix(dest_root)
+                && fs::symlink_metadata(dest).is_ok()
+            {
+                manifest.record(rel_path);
+            }
+        }
+        if prune {
+            manifest.prune(source_roots, dest_root, dry_run, journal, result)?;
+        }
+        if dry_run {
+            return Ok(());
+        }
+        manifest.save(dest_root)
+    }
+
+    /// Update the destination's baseline with every planned entry now in sync
+    fn record_baseline(
+        &self,
+        actions: &[SyncAction],
+        source_roots: &[PathBuf],
+        dest_root: &Path,
+        journal: Option<&SyncJournal>,
+    ) -> Result<()> {
+        if self.config.track_baseline != Some(true) || self.config.dry_run == Some(true) {
+            return Ok(());
+        }
+
+        let mut baseline = Baseline::load(dest_root)?;
+        for action in actions {
+            if matches!(action, SyncAction::Skip { reason, .. } if reason != IDENTICAL_REASON) {
+                continue;
+            }
+            let source = action.source_path();
+            let Some(rel_path) = source_roots
+                .iter()
+                .find_map(|root| source.strip_prefix(root).ok())
+            else {
+                continue;
+            };
+            baseline.record(rel_path, source, &dest_root.join(rel_path))?;
+        }
+        baseline.save(dest_root, journal)
+    }
+
+    /// Plan the sync operation without touching the destination
+    ///
+    /// Scans the source and compares every entry against the destination,
+    /// returning the actions `sync` would execute, in scan order. Paths
+    /// excluded by rules or ignore/include patterns, or older than the
+    /// configured `modified_since` cutoff, are planned as `SyncAction::Skip`.
+    /// Files inside directories that rules exclude are left out of the
+    /// directory comparison. Files larger than `max_file_size`, including
+    /// files inside skill directories, are planned as skips (with a warning)
+    /// before anything is hashed.
+    ///
+    /// A directory with files excluded by ignore/include patterns is planned
+    /// file by file instead of as one directory action: excluded files are
+    /// planned as skips and the rest are compared individually.
+    ///
+    /// With `track_baseline` enabled, entries that differ are checked against
+    /// the destination's baseline: a source-only edit is planned as an
+    /// overwrite and a destination-only edit as a skip, leaving the conflict
+    /// strategy for paths edited on both sides.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a path cannot be compared.
+    pub fn plan(&self, source_root: &Path, dest_root: &Path) -> Result<Vec<SyncAction>> {
+        Ok(self.plan_with_digests(source_root, dest_root)?.0)
+    }
+
+    /// Plan like [`SyncEngine::plan`], digesting each copied source as its
+    /// action is decided when `verify` is on
+    fn plan_with_digests(
+        &self,
+        source_root: &Path,
+        dest_root: &Path,
+    ) -> Result<(Vec<SyncAction>, BTreeMap<PathBuf, String>)> {
         // Scan source directory
-        let filter = FileFilter::new();
-        let scanner = Scanner::new(filter, self.config.preserve_symlinks == Some(true));
-        let scan_result = scanner.scan(source_root);
+        let scan_result = self.scanner().scan(source_root);
 
-        // Process each scanned file
-        let executor = FileOperationExecutor::new(self.config.dry_run == Some(true));
         let conflict_strategy = self.get_conflict_strategy();
+        let mut actions = Vec::with_capacity(scan_result.files.len());
+        let baseline = if self.config.track_baseline == Some(true) {
+            Some(Baseline::load(dest_root)?)
+        } else {
+            None
+        };
+        // Case-folded relative path -> the first entry planned with it
+        let mut case_folded = HashMap::new();
+        let mut digests = BTreeMap::new();
 
         for file in &scan_result.files {
             // Get relative path first (needed for pattern matching)
@@ -89,54 +634,467 @@ impl SyncEngine {
                 .strip_prefix(source_root)
                 .with_context(|| format!("Failed to strip prefix from {}", file.path.display()))?;
 
-            // Apply pattern filter to relative path
+            // Apply rules, then pattern filter, to relative path
             let is_dir = file.path.is_dir();
-            if let Some(ref matcher) = self.pattern_matcher
-                && !matcher.should_include(rel_path, is_dir)
-            {
-                result.skipped += 1;
+            if let Some(reason) = self.exclusion_reason(&file.path, rel_path, is_dir) {
+                actions.push(SyncAction::Skip {
+                    path: file.path.clone(),
+                    reason: reason.to_string(),
+                });
+                continue;
+            }
+
+            // Skip oversized files before they are hashed or copied
+            if is_dir {
+                for nested in self.oversized_files(&file.path, source_root, dest_root) {
+                    actions.push(SyncAction::Skip {
+                        path: nested,
+                        reason: OVERSIZED_REASON.to_string(),
+                    });
+                }
+            } else if self.oversized(&file.path) {
+                actions.push(SyncAction::Skip {
+                    path: file.path.clone(),
+                    reason: OVERSIZED_REASON.to_string(),
+                });
+                continue;
+            }
+
+            // Pre-filter entries not modified since the cutoff
+            if self.older_than_cutoff(&file.path, is_dir)? {
+                actions.push(SyncAction::Skip {
+                    path: file.path.clone(),
+                    reason: SINCE_REASON.to_string(),
+                });
+                continue;
+            }
+
+            // Patterns that exclude files inside a directory split it into files
+            if is_dir && self.narrowed_by_patterns(&file.path, source_root) {
+                let planned = self.plan_directory_files(
+                    &file.path,
+                    source_root,
+                    dest_root,
+                    conflict_strategy,
+                    baseline.as_ref(),
+                )?;
+                self.record_digests(&planned, &mut digests)?;
+                actions.extend(planned);
+                continue;
+            }
+
+            // On a case-insensitive destination both would land on one path
+            if Self::collides_in_case(&mut case_folded, rel_path) {
+                actions.push(SyncAction::Skip {
+                    path: file.path.clone(),
+                    reason: CASE_COLLISION_REASON.to_string(),
+                });
                 continue;
             }
 
             let dest_path = dest_root.join(rel_path);
 
+            // A directory unchanged since the last sync needs no file-by-file comparison
+            if is_dir
+                && let Some(baseline) = &baseline
+                && baseline.unchanged(rel_path, &file.path, &dest_path)?
+            {
+                actions.push(SyncAction::Skip {
+                    path: file.path.clone(),
+                    reason: IDENTICAL_REASON.to_string(),
+                });
+                continue;
+            }
+
             // Determine action based on whether it's a file or directory
-            let action = Self::determine_sync_action(&file.path, &dest_path, is_dir, conflict_strategy)?;
+            let include_file = |path: &Path| self.includes_nested(path, source_root, dest_root);
+            let action = self.determine_filtered_action(
+                &file.path,
+                &dest_path,
+                is_dir,
+                conflict_strategy,
+                &include_file,
+            )?;
+            let action = match &baseline {
+                Some(baseline) => Self::apply_baseline(action, baseline, rel_path)?,
+                None => action,
+            };
+            self.record_digests(std::slice::from_ref(&action), &mut digests)?;
+            actions.push(action);
+        }
 
-            // Skip actions don't need approval (they're automatic decisions)
-            if matches!(action, super::actions::SyncAction::Skip { .. }) {
-                if let Err(e) = executor.execute(&action, &mut result) {
-                    eprintln!("Error: {e}");
-                    result.errors.push(e.to_string());
-                }
+        if let Some(on_decision) = &self.on_decision {
+            actions.iter().for_each(on_decision);
+        }
+        Ok((actions, digests))
+    }
+
+    /// With `verify`, digest the sources `actions` copy from into `digests`
+    fn record_digests(
+        &self,
+        actions: &[SyncAction],
+        digests: &mut BTreeMap<PathBuf, String>,
+    ) -> Result<()> {
+        if !self.verify {
+            return Ok(());
+        }
+        for action in actions {
+            if !matches!(action, SyncAction::Skip { .. }) {
+                let source = action.source_path();
+                digests.insert(source.to_path_buf(), digest(source)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether ignore/include patterns exclude any file inside a source directory
+    fn narrowed_by_patterns(&self, dir: &Path, source_root: &Path) -> bool {
+        let Some(matcher) = &self.pattern_matcher else {
+            return false;
+        };
+
+        Self::files_in(dir).any(|path| {
+            path.strip_prefix(source_root)
+                .is_ok_and(|rel_path| !matcher.should_include(rel_path, false))
+        })
+    }
+
+    /// Plan each file of a source directory as its own action
+    ///
+    /// Files excluded by patterns are planned as skips. Files left out by
+    /// rules or `max_file_size` are not planned (oversized files were already
+    /// reported), and files only present in the destination are kept.
+    fn plan_directory_files(
+        &self,
+        dir: &Path,
+        source_root: &Path,
+        dest_root: &Path,
+        conflict_strategy: ConflictStrategy,
+        baseline: Option<&Baseline>,
+    ) -> Result<Vec<SyncAction>> {
+        let mut actions = Vec::new();
+        for path in Self::files_in(dir) {
+            let rel_path = path
+                .strip_prefix(source_root)
+                .with_context(|| format!("Failed to strip prefix from {}", path.display()))?;
+
+            if self
+                .pattern_matcher
+                .as_ref()
+                .is_some_and(|matcher| !matcher.should_include(rel_path, false))
+            {
+                actions.push(SyncAction::Skip {
+                    path: path.clone(),
+                    reason: PATTERN_REASON.to_string(),
+                });
+                continue;
+            }
+            if !self.includes_nested(&path, source_root, dest_root) {
                 continue;
             }
 
+            let action = self.determine_sync_action(
+                &path,
+                &dest_root.join(rel_path),
+                false,
+                conflict_strategy,
+            )?;
+            actions.push(match baseline {
+                Some(baseline) => Self::apply_baseline(action, baseline, rel_path)?,
+                None => action,
+            });
+        }
+        Ok(actions)
+    }
+
+    /// Files inside a directory, recursively, in file name order
+    fn files_in(dir: &Path) -> impl Iterator<Item = PathBuf> {
+        WalkDir::new(dir)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(walkdir::DirEntry::into_path)
+    }
+
+    /// Execute a two-way sync between a local and a global root in a single pass
+    ///
+    /// Both sides are scanned and every path is planned once: paths present on
+    /// one side only are created on the other. Differing paths are checked
+    /// against the baseline in `local_root`, kept unless `track_baseline` is
+    /// disabled: a path edited on one side since the last sync is copied from
+    /// that side. A path edited on both sides, or one without a record, is a
+    /// conflict resolved with the configured strategy, copying from the newer
+    /// side.
+    ///
+    /// The engine's configured direction is not used: each path's direction is
+    /// decided individually.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if sync fails or approver returns an error.
+    pub fn sync_bidirectional(
+        &self,
+        local_root: &Path,
+        global_root: &Path,
+        mut approver: Option<ApprovalCallback>,
+    ) -> Result<SyncResult> {
+        let started = Instant::now();
+        let _span = info_span!(
+            "sync_bidirectional",
+            local = %local_root.display(),
+            global = %global_root.display()
+        )
+        .entered();
+        let mut result = SyncResult::default();
+
+        // Scan both sides and merge into one sorted set of relative paths
+        let scanner = self.scanner();
+        let mut entries = BTreeMap::new();
+
+        for root in [global_root, local_root] {
+            let scan_result = scanner.scan(root);
+
+            for file in scan_result.files {
+                let rel_path = file.path.strip_prefix(root).with_context(|| {
+                    format!("Failed to strip prefix from {}", file.path.display())
+                })?;
+                entries
+                    .entry(rel_path.to_path_buf())
+                    .or_insert_with(|| file.path.is_dir());
+            }
+        }
+
+        let journal = self.journal();
+        let include_file = |path: &Path| self.includes_nested(path, local_root, global_root);
+        let symlink_roots = [
+            (global_root.to_path_buf(), local_root.to_path_buf()),
+            (local_root.to_path_buf(), global_root.to_path_buf()),
+        ];
+        let mut executor = FileOperationExecutor::new(self.config.dry_run == Some(true))
+            .with_filter(&include_file)
+            .with_retry(self.retry_policy())
+            .with_show_diff(self.config.show_diff == Some(true))
+            .with_dereference(self.config.follow_symlinks == Some(true))
+            .with_preserved_ownership(self.config.preserve_ownership == Some(true));
+        if let Some(journal) = &journal {
+            executor = executor.with_journal(journal);
+        }
+        if self.config.preserve_symlinks == Some(true) {
+            executor = executor.with_preserved_symlinks(&symlink_roots);
+        }
+        let index = ContentIndex::for_config_dirs(&[local_root, global_root]);
+        if self.config.hardlink_identical == Some(true) {
+            executor = executor.with_hardlinks(&index);
+        }
+        let conflict_strategy = self.get_conflict_strategy();
+        let track_baseline =
+            self.config.track_baseline != Some(false) && self.config.dry_run != Some(true);
+        let mut baseline = if track_baseline {
+            Baseline::load(local_root)?
+        } else {
+            Baseline::default()
+        };
+
+        let mut executed = 0;
+        let mut case_folded = HashMap::new();
+        let outcome = entries.into_iter().try_for_each(|(rel_path, is_dir)| {
+            let global_path = global_root.join(&rel_path);
+            let local_path = local_root.join(&rel_path);
+            let sides = [&global_path, &local_path];
+            if let Some(reason) = self.bidirectional_skip(&rel_path, is_dir, sides, &mut case_folded)? {
+                let skip = SyncAction::Skip {
+                    path: global_path,
+                    reason: reason.to_string(),
+                };
+                return self.process_action(&skip, None, &executor, &mut approver, None, &mut result);
+            }
+
+            let action = self.determine_bidirectional_action(
+                &rel_path,
+                &global_path,
+                &local_path,
+                is_dir,
+                conflict_strategy,
+                &baseline,
+            )?;
+            if let Some(on_decision) = &self.on_decision {
+                on_decision(&action);
+            }
+            if self.defer(&action, &mut executed, &mut result) {
+                return Ok(());
+            }
+
+            self.process_action(
+                &action,
+                Some(&rel_path),
+                &executor,
+                &mut approver,
+                None,
+                &mut result,
+            )?;
+            if track_baseline {
+                baseline.record(&rel_path, &global_path, &local_path)?;
+            }
+            Ok(())
+        });
+
+        if track_baseline && outcome.is_ok() {
+            baseline.save(local_root, journal.as_ref())?;
+        }
+        if let Some(journal) = &journal {
+            journal.save()?;
+        }
+        outcome?;
+
+        Self::finish(result, started)
+    }
+
+    /// Run a single planned action through approval and the executor
+    ///
+    /// A failed action is recorded in `result.errors`, or returned as the
+    /// error when failing fast. Operations it performs are counted toward the
+    /// configuration type of `rel_path`. With a `check`, an approved action
+    /// whose source changed since planning is planned and approved again.
+    fn process_action(
+        &self,
+        action: &SyncAction,
+        rel_path: Option<&Path>,
+        executor: &FileOperationExecutor,
+        approver: &mut Option<ApprovalCallback>,
+        check: Option<&SourceCheck>,
+        result: &mut SyncResult,
+    ) -> Result<()> {
+        debug!(?action, "Planned action");
+        let before = result.total_operations();
+
+        // Skip actions don't need approval (they're automatic decisions)
+        let outcome = if matches!(action, SyncAction::Skip { .. }) {
+            executor.execute(action, result)
+        } else {
             // Check approval if callback provided (only for Create and Conflict actions)
-            match Self::apply_approval(&action, &mut approver, &mut result) {
-                Ok(Some(action_to_execute)) => {
-                    // Execute action
-                    if let Err(e) = executor.execute(&action_to_execute, &mut result) {
-                        eprintln!("Error: {e}");
-                        result.errors.push(e.to_string());
+            // User abort or an error in approval propagates
+            match Self::apply_approval(action, approver, result)? {
+                None => Ok(()),
+                Some(confirmed) => match self.replan_if_changed(&confirmed, check, result) {
+                    Ok(Some(replanned)) => {
+                        return self.process_action(
+                            &replanned, rel_path, executor, approver, None, result,
+                        );
                     }
-                }
-                Ok(None) => {
-                    // User skipped - move to next file
-                }
-                Err(e) => {
-                    // User aborted or error in approval
-                    return Err(e);
-                }
+                    Ok(None) => executor.execute(&confirmed, result),
+                    Err(e) => Err(e),
+                },
+            }
+        };
+
+        if let Err(e) = outcome {
+            if self.fail_fast {
+                return Err(e);
             }
+            error!("{e}");
+            result.errors.push(e.to_string());
+        }
+        if let Some(rel_path) = rel_path {
+            let performed = result.total_operations() - before;
+            result.record_type(rel_path, performed);
+        }
+
+        Ok(())
+    }
+
+    /// Plan `action` again if its source no longer has the digest in `check`
+    ///
+    /// Returns `None` when the source is unchanged. A changed source is
+    /// recorded as a warning; one that was deleted becomes a skip.
+    fn replan_if_changed(
+        &self,
+        action: &SyncAction,
+        check: Option<&SourceCheck>,
+        result: &mut SyncResult,
+    ) -> Result<Option<SyncAction>> {
+        let Some(check) = check else {
+            return Ok(None);
+        };
+        let (source, dest, is_dir) = match action {
+            SyncAction::Create { source, dest } | SyncAction::Conflict { source, dest, .. } => {
+                (source, dest, false)
+            }
+            SyncAction::CreateDirectory { source, dest }
+            | SyncAction::DirectoryConflict { source, dest, .. } => (source, dest, true),
+            SyncAction::Skip { .. } => return Ok(None),
+        };
+        let Some(expected) = check.digests.get(source) else {
+            return Ok(None);
+        };
+        let exists = fs::symlink_metadata(source).is_ok();
+        if exists && digest(source)? == *expected {
+            return Ok(None);
+        }
+
+        let warning = format!("{} changed after it was planned; re-evaluated", source.display());
+        warn!("{warning}");
+        result.warnings.push(warning);
+        if !exists {
+            return Ok(Some(SyncAction::Skip {
+                path: source.clone(),
+                reason: VANISHED_REASON.to_string(),
+            }));
+        }
+        self.determine_filtered_action(
+            source,
+            dest,
+            is_dir,
+            self.get_conflict_strategy(),
+            check.include_file,
+        )
+        .map(Some)
+    }
+
+    /// Scanner for source roots, honoring `path_match`, `preserve_symlinks`,
+    /// `skill_marker`, `command_extensions`, and `jobs`
+    fn scanner(&self) -> Scanner {
+        let filter = FileFilter::new().with_cli_patterns(
+            self.config
+                .path_match
+                .iter()
+                .map(|query| Pattern::Contains(query.clone()))
+                .collect(),
+        );
+        let scanner = Scanner::new(filter, self.config.preserve_symlinks == Some(true));
+        let scanner = match &self.config.skill_marker {
+            Some(marker) => scanner.with_skill_marker(marker),
+            None => scanner,
+        };
+        let scanner = match &self.config.command_extensions {
+            Some(extensions) => scanner.with_command_extensions(extensions),
+            None => scanner,
+        };
+        match self.config.jobs {
+            Some(jobs) => scanner.with_jobs(jobs),
+            None => scanner,
         }
+    }
 
-        // Log warnings from scanner
-        for warning in &scan_result.warnings {
-            eprintln!("Warning: {warning}");
+    /// Journal for a sync that writes to disk, if journaling is enabled
+    fn journal(&self) -> Option<SyncJournal> {
+        if self.config.dry_run == Some(true) {
+            return None;
         }
+        self.journal_dir.as_deref().map(SyncJournal::new)
+    }
 
-        // Fail fast if any errors occurred
+    /// Retry policy for copies, from `copy_retries` or the default
+    fn retry_policy(&self) -> RetryPolicy {
+        self.config
+            .copy_retries
+            .map_or_else(RetryPolicy::default, RetryPolicy::new)
+    }
+
+    /// Fail fast if any errors occurred during execution, else record how
+    /// long the sync took since `started`
+    fn finish(mut result: SyncResult, started: Instant) -> Result<SyncResult> {
         if !result.errors.is_empty() {
             anyhow::bail!(
                 "Sync failed with {} error(s):\n  - {}",
@@ -145,6 +1103,7 @@ impl SyncEngine {
             );
         }
 
+        result.elapsed = Some(started.elapsed());
         Ok(result)
     }
 
@@ -156,23 +1115,254 @@ impl SyncEngine {
         }
     }
 
+    /// Why a scanned entry is excluded from the sync, if it is
+    ///
+    /// A matching rule for the engine's direction decides outright; otherwise
+    /// the ignore/include patterns apply.
+    fn exclusion_reason(&self, path: &Path, rel_path: &Path, is_dir: bool) -> Option<&'static str> {
+        if self.is_excluded_hidden(rel_path) {
+            return Some(HIDDEN_REASON);
+        }
+
+        let decision = self
+            .rule_matcher
+            .decide(rel_path, is_dir, self.direction, || Self::classify(path));
+        match decision {
+            Some(true) => None,
+            Some(false) => Some("excluded by rule"),
+            None => self
+                .pattern_matcher
+                .as_ref()
+                .is_some_and(|matcher| !matcher.should_include(rel_path, is_dir))
+                .then_some(PATTERN_REASON),
+        }
+    }
+
+    /// Why an entry of a bidirectional sync is skipped before it is compared,
+    /// if it is
+    ///
+    /// Applies the same checks as `plan`: rules, patterns and the hidden-file
+    /// policy, the size limit, the `modified_since` cutoff (every existing
+    /// side must be older), and case-only collisions with earlier entries.
+    fn bidirectional_skip(
+        &self,
+        rel_path: &Path,
+        is_dir: bool,
+        sides: [&PathBuf; 2],
+        case_folded: &mut HashMap<String, PathBuf>,
+    ) -> Result<Option<&'static str>> {
+        let existing: Vec<&PathBuf> = sides.into_iter().filter(|path| path.exists()).collect();
+        let path = existing.first().copied().unwrap_or(sides[0]);
+        if let Some(reason) = self.exclusion_reason(path, rel_path, is_dir) {
+            return Ok(Some(reason));
+        }
+        if !is_dir && sides.iter().any(|side| self.oversized(side)) {
+            return Ok(Some(OVERSIZED_REASON));
+        }
+        if self.config.modified_since.is_some() {
+            let mut older = true;
+            for side in &existing {
+                older &= self.older_than_cutoff(side, is_dir)?;
+            }
+            if older {
+                return Ok(Some(SINCE_REASON));
+            }
+        }
+        if Self::collides_in_case(case_folded, rel_path) {
+            return Ok(Some(CASE_COLLISION_REASON));
+        }
+        Ok(None)
+    }
+
+    /// Whether an entry was last modified before the `modified_since` cutoff
+    fn older_than_cutoff(&self, path: &Path, is_dir: bool) -> Result<bool> {
+        self.config
+            .modified_since
+            .map_or(Ok(false), |cutoff| Self::modified_before(path, is_dir, cutoff))
+    }
+
+    /// Whether `rel_path` differs from an earlier entry only in case, warning
+    /// if it does
+    ///
+    /// Paths are recorded in `case_folded` as they are seen, so the first of
+    /// the colliding entries is kept.
+    fn collides_in_case(case_folded: &mut HashMap<String, PathBuf>, rel_path: &Path) -> bool {
+        let folded = rel_path.to_string_lossy().to_lowercase();
+        if let Some(first) = case_folded.get(&folded) {
+            warn!(
+                "{} differs from {} only in case; skipping it, since one would overwrite the other on a case-insensitive filesystem",
+                rel_path.display(),
+                first.display()
+            );
+            return true;
+        }
+        case_folded.insert(folded, rel_path.to_path_buf());
+        false
+    }
+
+    /// Whether a path is hidden (any component starts with `.`) and
+    /// `include_hidden = false` excludes it
+    fn is_excluded_hidden(&self, rel_path: &Path) -> bool {
+        self.config.include_hidden == Some(false)
+            && rel_path.components().any(|component| {
+                matches!(component, Component::Normal(name)
+                    if name.to_string_lossy().starts_with('.'))
+            })
+    }
+
+    /// Whether a file is larger than `max_file_size`, without logging
+    fn is_size_excluded(&self, path: &Path) -> bool {
+        self.config.max_file_size.is_some_and(|limit| {
+            fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.len() > limit)
+        })
+    }
+
+    /// Whether a file is larger than `max_file_size`, warning if it is
+    fn oversized(&self, path: &Path) -> bool {
+        let oversized = self.is_size_excluded(path);
+        if oversized {
+            warn!("Skipping {}: {OVERSIZED_REASON}", path.display());
+        }
+        oversized
+    }
+
+    /// Oversized files inside a source directory that rules would otherwise sync
+    fn oversized_files(&self, dir: &Path, source_root: &Path, dest_root: &Path) -> Vec<PathBuf> {
+        if self.config.max_file_size.is_none() {
+            return Vec::new();
+        }
+
+        WalkDir::new(dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .map(walkdir::DirEntry::into_path)
+            .filter(|path| {
+                self.is_size_excluded(path) && self.includes_by_rules(path, source_root, dest_root)
+            })
+            .inspect(|path| {
+                warn!("Skipping {}: {OVERSIZED_REASON}", path.display());
+            })
+            .collect()
+    }
+
+    /// Whether a file inside a synced directory is included
+    ///
+    /// Files over `max_file_size` are excluded, then rules and the
+    /// hidden-file policy decide.
+    ///
+    /// `path` may be on either side; it is matched relative to its root.
+    fn includes_nested(&self, path: &Path, source_root: &Path, dest_root: &Path) -> bool {
+        !self.is_size_excluded(path) && self.includes_by_rules(path, source_root, dest_root)
+    }
+
+    /// Whether rules and the hidden-file policy allow a file inside a synced
+    /// directory, ignoring its size
+    fn includes_by_rules(&self, path: &Path, source_root: &Path, dest_root: &Path) -> bool {
+        let Ok(rel_path) = path
+            .strip_prefix(source_root)
+            .or_else(|_| path.strip_prefix(dest_root))
+        else {
+            return true;
+        };
+        if self.is_excluded_hidden(rel_path) {
+            return false;
+        }
+        if self.rule_matcher.is_empty() {
+            return true;
+        }
+
+        self.rule_matcher
+            .decide(rel_path, false, self.direction, || Self::classify(path))
+            != Some(false)
+    }
+
+    /// Classify a path for type-scoped rules
+    ///
+    /// Directories have no file type. Unreadable files are left unclassified
+    /// so typed rules do not apply to them.
+    fn classify(path: &Path) -> Option<FileType> {
+        let metadata = fs::symlink_metadata(path).ok()?;
+        if metadata.file_type().is_symlink() {
+            return Some(FileType::Symlink);
+        }
+        if metadata.is_dir() {
+            return None;
+        }
+
+        match BinaryDetector::is_binary(path) {
+            Ok(true) => Some(FileType::Binary),
+            Ok(false) => Some(FileType::Text),
+            Err(e) => {
+                debug!("Could not classify {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Whether a source entry was last modified before the cutoff
+    ///
+    /// Directories are dated by their newest file.
+    fn modified_before(path: &Path, is_dir: bool, cutoff: SystemTime) -> Result<bool> {
+        let modified = if is_dir {
+            DirectoryComparator::newest_modified_time(path)?
+        } else {
+            Some(TimestampComparator::get_modified_time(path)?)
+        };
+        Ok(modified.is_none_or(|time| time < cutoff))
+    }
+
     /// Determine the sync action for a file or directory
     fn determine_sync_action(
+        &self,
+        source_path: &Path,
+        dest_path: &Path,
+        is_dir: bool,
+        conflict_strategy: ConflictStrategy,
+    ) -> Result<SyncAction> {
+        self.determine_filtered_action(source_path, dest_path, is_dir, conflict_strategy, &|_| {
+            true
+        })
+    }
+
+    /// Determine the sync action, comparing directories by included files only
+    fn determine_filtered_action(
+        &self,
         source_path: &Path,
         dest_path: &Path,
         is_dir: bool,
         conflict_strategy: ConflictStrategy,
+        include: &dyn Fn(&Path) -> bool,
     ) -> Result<SyncAction> {
+        let _span = debug_span!("compare", path = %source_path.display()).entered();
+
+        // A file can't be compared with, or copied over, a directory
+        if let Ok(metadata) = fs::metadata(dest_path)
+            && metadata.is_dir() != is_dir
+        {
+            let (source_kind, dest_kind) = if is_dir {
+                ("a directory", "a file")
+            } else {
+                ("a file", "a directory")
+            };
+            anyhow::bail!(
+                "{} is {source_kind} but {} is {dest_kind}; remove or rename one of them",
+                source_path.display(),
+                dest_path.display()
+            );
+        }
+
         if is_dir {
             // Handle directory syncing
             if dest_path.exists() {
                 // Both exist - compare directories
-                let dir_comparison = DirectoryComparator::compare(source_path, dest_path)?;
+                let dir_comparison =
+                    DirectoryComparator::compare_filtered(source_path, dest_path, include)?;
 
                 if dir_comparison.is_identical() {
                     Ok(SyncAction::Skip {
                         path: source_path.to_path_buf(),
-                        reason: "identical content".to_string(),
+                        reason: IDENTICAL_REASON.to_string(),
                     })
                 } else {
                     // Directories differ - check if source is newer
@@ -193,7 +1383,17 @@ impl SyncEngine {
             }
         } else {
             // Handle file syncing
-            let comparison = FileComparator::compare(source_path, dest_path, conflict_strategy)?;
+            let comparison = if self.config.preserve_symlinks == Some(true)
+                && is_dangling_symlink(source_path)
+            {
+                FileComparator::compare_links(source_path, dest_path, conflict_strategy)?
+            } else if let Some(comparator) = &self.comparator {
+                FileComparator::compare_with(source_path, dest_path, conflict_strategy, comparator)?
+            } else if self.config.semantic_frontmatter == Some(true) {
+                FileComparator::compare_semantic(source_path, dest_path, conflict_strategy)?
+            } else {
+                FileComparator::compare(source_path, dest_path, conflict_strategy)?
+            };
             Ok(SyncActionResolver::resolve(
                 source_path.to_path_buf(),
                 dest_path.to_path_buf(),
@@ -202,6 +1402,125 @@ impl SyncEngine {
         }
     }
 
+    /// Resolve a conflict that the baseline shows to be a one-sided edit
+    fn apply_baseline(
+        action: SyncAction,
+        baseline: &Baseline,
+        rel_path: &Path,
+    ) -> Result<SyncAction> {
+        let (SyncAction::Conflict { source, dest, .. }
+        | SyncAction::DirectoryConflict { source, dest, .. }) = &action
+        else {
+            return Ok(action);
+        };
+
+        match baseline.classify(rel_path, source, dest)? {
+            BaselineChange::SourceChanged => {
+                debug!(
+                    "Only the source changed since the last sync: {}",
+                    rel_path.display()
+                );
+                Ok(Self::overwriting(action))
+            }
+            BaselineChange::DestinationChanged => Ok(SyncAction::Skip {
+                path: source.clone(),
+                reason: "destination changed since last sync".to_string(),
+            }),
+            BaselineChange::BothChanged => Ok(action),
+        }
+    }
+
+    /// A conflict action resolved by overwriting the destination
+    fn overwriting(action: SyncAction) -> SyncAction {
+        match action {
+            SyncAction::Conflict {
+                source,
+                dest,
+                source_newer,
+                ..
+            } => SyncAction::Conflict {
+                source,
+                dest,
+                strategy: ConflictStrategy::Overwrite,
+                source_newer,
+            },
+            SyncAction::DirectoryConflict {
+                source,
+                dest,
+                source_newer,
+                ..
+            } => SyncAction::DirectoryConflict {
+                source,
+                dest,
+                strategy: ConflictStrategy::Overwrite,
+                source_newer,
+            },
+            other => other,
+        }
+    }
+
+    /// Determine the action for a path that may exist on either side
+    ///
+    /// Returned actions copy from the global side when it wins and from the
+    /// local side otherwise. When the sides differ, a side edited alone since
+    /// `baseline` was recorded wins outright; otherwise the newer side is the
+    /// source of a conflict left to `conflict_strategy`.
+    fn determine_bidirectional_action(
+        &self,
+        rel_path: &Path,
+        global_path: &Path,
+        local_path: &Path,
+        is_dir: bool,
+        conflict_strategy: ConflictStrategy,
+        baseline: &Baseline,
+    ) -> Result<SyncAction> {
+        if !global_path.exists() {
+            return self.determine_sync_action(local_path, global_path, is_dir, conflict_strategy);
+        }
+        if !local_path.exists() {
+            return self.determine_sync_action(global_path, local_path, is_dir, conflict_strategy);
+        }
+
+        let global_to_local =
+            self.determine_sync_action(global_path, local_path, is_dir, conflict_strategy)?;
+        let (SyncAction::Conflict {
+            source_newer: global_newer,
+            ..
+        }
+        | SyncAction::DirectoryConflict {
+            source_newer: global_newer,
+            ..
+        }) = global_to_local
+        else {
+            // Identical content on both sides
+            return Ok(global_to_local);
+        };
+
+        match baseline.classify(rel_path, global_path, local_path)? {
+            BaselineChange::SourceChanged => {
+                debug!(
+                    "Only the global side changed since the last sync: {}",
+                    rel_path.display()
+                );
+                Ok(Self::overwriting(global_to_local))
+            }
+            BaselineChange::DestinationChanged => {
+                debug!(
+                    "Only the local side changed since the last sync: {}",
+                    rel_path.display()
+                );
+                let local_to_global =
+                    self.determine_sync_action(local_path, global_path, is_dir, conflict_strategy)?;
+                Ok(Self::overwriting(local_to_global))
+            }
+            // Edited on both sides, or never synced: a genuine conflict
+            BaselineChange::BothChanged if global_newer => Ok(global_to_local),
+            BaselineChange::BothChanged => {
+                self.determine_sync_action(local_path, global_path, is_dir, conflict_strategy)
+            }
+        }
+    }
+
     /// Apply approval logic to a sync action
     /// Returns Ok(Some(action)) if approved, Ok(None) if user skipped, or Err if aborted
     fn apply_approval(
@@ -213,7 +1532,7 @@ impl SyncEngine {
             match approve(action) {
                 Ok(true) => {
                     // Approved - if this is a Fail conflict, treat as Overwrite
-                    Ok(Some(match action {
+                    let promoted = match action {
                         SyncAction::Conflict {
                             source,
                             dest,
@@ -237,7 +1556,11 @@ impl SyncEngine {
                             source_newer: *source_newer,
                         },
                         _ => action.clone(),
-                    }))
+                    };
+                    if promoted != *action {
+                        result.conflicts_resolved += 1;
+                    }
+                    Ok(Some(promoted))
                 }
                 Ok(false) => {
                     // Skipped by user
@@ -255,3 +1578,8 @@ impl SyncEngine {
         }
     }
 }
+
+/// Whether `path` is a symlink whose target does not exist
+fn is_dangling_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink()) && !path.exists()
+}
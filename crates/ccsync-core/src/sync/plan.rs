@@ -0,0 +1,329 @@
+//! Saved sync plans
+//!
+//! A plan written by a dry run lists the actions a sync would perform, as
+//! JSON, so it can be reviewed offline and applied later. Alongside the
+//! actions it records a digest of every source they read and the state of
+//! every destination they write, so a plan whose sources or destinations
+//! changed since it was made is refused instead of applied.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use super::actions::SyncAction;
+use super::baseline::digest;
+use super::reporting::format_bytes;
+use crate::config::SyncDirection;
+use crate::error::Result;
+
+/// A planned sync with the source state it was planned against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanFile {
+    /// Direction the plan was made for, which selects the rules that apply
+    pub direction: SyncDirection,
+    /// Root the sources were scanned from
+    pub source_root: PathBuf,
+    /// Root the actions write to
+    pub dest_root: PathBuf,
+    /// Actions in the order a sync would execute them
+    pub actions: Vec<SyncAction>,
+    /// Digest of each file or directory the actions copy from
+    sources: BTreeMap<PathBuf, String>,
+    /// Digest of each file or directory the actions write, `None` if absent
+    destinations: BTreeMap<PathBuf, Option<String>>,
+}
+
+impl PlanFile {
+    /// Capture `actions` planned from `source_root` into `dest_root`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a source or destination cannot be hashed.
+    pub fn new(
+        direction: SyncDirection,
+        source_root: &Path,
+        dest_root: &Path,
+        actions: Vec<SyncAction>,
+    ) -> Result<Self> {
+        let sources = Self::copied_sources(&actions)
+            .map(|source| Ok((source.to_path_buf(), digest(source)?)))
+            .collect::<Result<_>>()?;
+        let destinations = Self::written_destinations(&actions)
+            .map(|dest| Ok((dest.to_path_buf(), Self::state(dest)?)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            direction,
+            source_root: source_root.to_path_buf(),
+            dest_root: dest_root.to_path_buf(),
+            actions,
+            sources,
+            destinations,
+        })
+    }
+
+    /// Digest of each file or directory the actions copy from, as planned
+    #[must_use]
+    pub const fn sources(&self) -> &BTreeMap<PathBuf, String> {
+        &self.sources
+    }
+
+    /// Write the plan to `path` as pretty-printed JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plan cannot be serialized or written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize plan")?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write plan: {}", path.display()))
+    }
+
+    /// Read a plan written by [`PlanFile::save`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid plan.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read plan: {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid plan: {}", path.display()))
+    }
+
+    /// Check that every source and destination is as the plan found it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first source that is gone or changed, the
+    /// first destination that appeared, changed, or disappeared, or one the
+    /// plan uses without having recorded it.
+    pub fn verify(&self) -> Result<()> {
+        for source in Self::copied_sources(&self.actions) {
+            let Some(expected) = self.sources.get(source) else {
+                anyhow::bail!("Stale plan: no digest recorded for {}", source.display());
+            };
+            if !source.exists() {
+                anyhow::bail!("Stale plan: {} no longer exists", source.display());
+            }
+            if digest(source)? != *expected {
+                anyhow::bail!(
+                    "Stale plan: {} changed since the plan was made",
+                    source.display()
+                );
+            }
+        }
+
+        for dest in Self::written_destinations(&self.actions) {
+            let Some(expected) = self.destinations.get(dest) else {
+                anyhow::bail!("Stale plan: no state recorded for {}", dest.display());
+            };
+            match (expected, Self::state(dest)?) {
+                (None, Some(_)) => anyhow::bail!(
+                    "Stale plan: {} was created since the plan was made",
+                    dest.display()
+                ),
+                (Some(_), None) => anyhow::bail!(
+                    "Stale plan: {} was removed since the plan was made",
+                    dest.display()
+                ),
+                (Some(expected), Some(actual)) if actual != *expected => anyhow::bail!(
+                    "Stale plan: {} changed since the plan was made",
+                    dest.display()
+                ),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Digest of `dest`, or `None` if nothing is there
+    fn state(dest: &Path) -> Result<Option<String>> {
+        if fs::symlink_metadata(dest).is_err() {
+            return Ok(None);
+        }
+        digest(dest).map(Some)
+    }
+
+    /// Destinations of the actions that copy something
+    fn written_destinations(actions: &[SyncAction]) -> impl Iterator<Item = &Path> {
+        actions.iter().filter_map(|action| match action {
+            SyncAction::Create { dest, .. }
+            | SyncAction::CreateDirectory { dest, .. }
+            | SyncAction::Conflict { dest, .. }
+            | SyncAction::DirectoryConflict { dest, .. } => Some(dest.as_path()),
+            SyncAction::Skip { .. } => None,
+        })
+    }
+
+    /// Sources of the actions that copy something
+    fn copied_sources(actions: &[SyncAction]) -> impl Iterator<Item = &Path> {
+        actions
+            .iter()
+            .filter(|action| !matches!(action, SyncAction::Skip { .. }))
+            .map(SyncAction::source_path)
+    }
+}
+
+/// How many files and bytes a plan would copy to the destination
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlanSize {
+    /// Files written, counting each file inside a copied directory
+    pub files: usize,
+    /// Total size of those files
+    pub bytes: u64,
+}
+
+impl PlanSize {
+    /// Add up the sources `actions` copy from
+    ///
+    /// Conflicts count as copies, since resolving them may overwrite the
+    /// destination. Sources that can't be read are left out.
+    #[must_use]
+    pub fn of(actions: &[SyncAction]) -> Self {
+        let mut size = Self::default();
+        for source in PlanFile::copied_sources(actions) {
+            let files = WalkDir::new(source)
+                .follow_links(true)
+                .into_iter()
+                .filter_map(std::result::Result::ok)
+                .filter(|entry| entry.file_type().is_file());
+            for file in files {
+                size.files += 1;
+                size.bytes += file.metadata().map_or(0, |metadata| metadata.len());
+            }
+        }
+        size
+    }
+}
+
+impl fmt::Display for PlanSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} file(s), {}", self.files, format_bytes(self.bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparison::ConflictStrategy;
+    use tempfile::TempDir;
+
+    fn planned(tmp: &TempDir) -> PlanFile {
+        let source = tmp.path().join("global");
+        fs::create_dir_all(source.join("agents")).unwrap();
+        fs::create_dir_all(source.join("skills/demo")).unwrap();
+        fs::write(source.join("agents/a.md"), "a").unwrap();
+        fs::write(source.join("skills/demo/SKILL.md"), "skill").unwrap();
+
+        let actions = vec![
+            SyncAction::Create {
+                source: source.join("agents/a.md"),
+                dest: tmp.path().join("local/agents/a.md"),
+            },
+            SyncAction::Skip {
+                path: source.join("agents/gone.md"),
+                reason: "identical content".to_string(),
+            },
+            SyncAction::DirectoryConflict {
+                source: source.join("skills/demo"),
+                dest: tmp.path().join("local/skills/demo"),
+                strategy: ConflictStrategy::KeepBoth,
+                source_newer: true,
+            },
+        ];
+        PlanFile::new(
+            SyncDirection::ToLocal,
+            &source,
+            &tmp.path().join("local"),
+            actions,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("plan.json");
+        let plan = planned(&tmp);
+
+        plan.save(&path).unwrap();
+
+        let json = fs::read_to_string(&path).unwrap();
+        assert!(json.contains(r#""action": "directory-conflict""#));
+        assert!(json.contains(r#""strategy": "keep-both""#));
+        assert_eq!(PlanFile::load(&path).unwrap(), plan);
+    }
+
+    #[test]
+    fn test_plan_size_counts_copied_files() {
+        let tmp = TempDir::new().unwrap();
+        let plan = planned(&tmp);
+
+        // agents/a.md and skills/demo/SKILL.md; the skip is left out
+        let size = PlanSize::of(&plan.actions);
+        assert_eq!(size, PlanSize { files: 2, bytes: 6 });
+        assert_eq!(size.to_string(), "2 file(s), 6 B");
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_plan() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("plan.json");
+        fs::write(&path, r#"{"actions": [{"action": "explode"}]}"#).unwrap();
+
+        let err = PlanFile::load(&path).unwrap_err();
+        assert!(err.to_string().contains("Invalid plan"));
+    }
+
+    #[test]
+    fn test_verify_accepts_unchanged_sources() {
+        let tmp = TempDir::new().unwrap();
+        let plan = planned(&tmp);
+
+        plan.verify().unwrap();
+    }
+
+    #[test]
+    fn test_verify_rejects_changed_or_missing_sources() {
+        let tmp = TempDir::new().unwrap();
+        let plan = planned(&tmp);
+
+        fs::write(tmp.path().join("global/skills/demo/SKILL.md"), "edited").unwrap();
+        let err = plan.verify().unwrap_err();
+        assert!(err.to_string().contains("changed since the plan was made"));
+
+        fs::remove_file(tmp.path().join("global/agents/a.md")).unwrap();
+        let err = plan.verify().unwrap_err();
+        assert!(err.to_string().contains("no longer exists"));
+    }
+
+    #[test]
+    fn test_verify_rejects_changed_destinations() {
+        let tmp = TempDir::new().unwrap();
+        let local = tmp.path().join("local");
+        fs::create_dir_all(local.join("skills/demo")).unwrap();
+        fs::write(local.join("skills/demo/SKILL.md"), "local").unwrap();
+        let plan = planned(&tmp);
+
+        // A file appearing where the plan creates one
+        fs::create_dir_all(local.join("agents")).unwrap();
+        fs::write(local.join("agents/a.md"), "written meanwhile").unwrap();
+        let err = plan.verify().unwrap_err();
+        assert!(err.to_string().contains("was created since the plan was made"));
+        fs::remove_file(local.join("agents/a.md")).unwrap();
+        plan.verify().unwrap();
+
+        fs::write(local.join("skills/demo/SKILL.md"), "edited").unwrap();
+        let err = plan.verify().unwrap_err();
+        assert!(err.to_string().contains("changed since the plan was made"));
+
+        fs::remove_dir_all(local.join("skills/demo")).unwrap();
+        let err = plan.verify().unwrap_err();
+        assert!(err.to_string().contains("was removed since the plan was made"));
+    }
+}
@@ -1,8 +1,14 @@
 //! Sync operation reporting and statistics
 
+use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::time::Duration;
+
+use anyhow::Context;
 
 use super::SyncResult;
+use crate::color::{self, GREEN, RED};
+use crate::error::Result;
 
 /// Sync operation reporter
 pub struct SyncReporter;
@@ -17,21 +23,64 @@ impl SyncReporter {
         let _ = writeln!(output, "Created:  {}", result.created);
         let _ = writeln!(output, "Updated:  {}", result.updated);
         let _ = writeln!(output, "Deleted:  {}", result.deleted);
+        if !result.by_type.is_empty() {
+            let by_type: Vec<_> = result
+                .by_type
+                .iter()
+                .map(|(kind, count)| format!("{kind}: {count}"))
+                .collect();
+            let _ = writeln!(output, "By type:  {}", by_type.join(", "));
+        }
 
-        // Show skipped count with reasons breakdown
-        if result.skipped > 0 && !result.skip_reasons.is_empty() {
-            let _ = write!(output, "Skipped:  {}", result.skipped);
-            let mut reasons: Vec<_> = result.skip_reasons.iter().collect();
-            reasons.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
-            for (reason, count) in reasons {
-                let _ = write!(output, " ({reason}: {count})");
-            }
-            let _ = writeln!(output);
-        } else {
-            let _ = writeln!(output, "Skipped:  {}", result.skipped);
+        // Show skipped count, then each reason on its own line
+        let _ = writeln!(output, "Skipped:  {}", result.skipped);
+        let mut reasons: Vec<_> = result.skip_reasons.iter().collect();
+        reasons.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        for (reason, count) in reasons {
+            let _ = writeln!(output, "  {reason}: {count}");
         }
 
         let _ = writeln!(output, "Conflicts: {}", result.conflicts);
+        if result.conflicts_resolved > 0 {
+            let _ = writeln!(
+                output,
+                "Resolved: {} conflict(s) by approval",
+                result.conflicts_resolved
+            );
+        }
+        if result.deferred > 0 {
+            let _ = writeln!(
+                output,
+                "Deferred: {} (operation limit reached)",
+                result.deferred
+            );
+        }
+
+        if result.would_fail > 0 {
+            let _ = writeln!(
+                output,
+                "Would fail: {} conflict(s) (use --conflict to resolve)",
+                result.would_fail
+            );
+        }
+
+        if !result.kept_both.is_empty() {
+            let _ = writeln!(
+                output,
+                "\nKept both ({}), reconcile:",
+                result.kept_both.len()
+            );
+            for path in &result.kept_both {
+                let _ = writeln!(output, "  - {}", path.display());
+            }
+        }
+
+        if !result.warnings.is_empty() {
+            let _ = writeln!(output, "\nWarnings ({}):", result.warnings.len());
+            for warning in &result.warnings {
+                let _ = writeln!(output, "  - {warning}");
+            }
+        }
 
         if !result.errors.is_empty() {
             let _ = writeln!(output, "\nErrors ({}):", result.errors.len());
@@ -40,14 +89,270 @@ impl SyncReporter {
             }
         }
 
-        let _ = writeln!(output, "\nTotal operations: {}", result.total_operations());
+        let _ = writeln!(
+            output,
+            "\nTotal operations: {} ({} copied)",
+            result.total_operations(),
+            format_bytes(result.bytes_copied)
+        );
+        if let Some(elapsed) = result.elapsed {
+            let _ = writeln!(output, "Elapsed:  {}", format_timing(result, elapsed));
+        }
 
-        if result.is_success() {
-            output.push_str("Status: ✓ Success\n");
+        let status = if result.is_success() {
+            color::paint(GREEN, "✓ Success")
+        } else if result.errors.is_empty() {
+            color::paint(RED, "✗ The sync would fail")
         } else {
-            output.push_str("Status: ✗ Completed with errors\n");
-        }
+            color::paint(RED, "✗ Completed with errors")
+        };
+        let _ = writeln!(output, "Status: {status}");
 
         output
     }
+
+    /// Generate the summary as a pretty-printed JSON object
+    ///
+    /// Holds the same counts as [`Self::generate_summary`], with `by_type`
+    /// and `skip_reasons` as objects mapping each type or reason to its count.
+    /// Timing is left out of the counts: once the sync finished, it is nested
+    /// in a `timings` object as `elapsed_ms`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn generate_json(result: &SyncResult) -> Result<String> {
+        let skip_reasons: BTreeMap<_, _> = result.skip_reasons.iter().collect();
+        let kept_both: Vec<_> = result
+            .kept_both
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        let mut summary = serde_json::json!({
+            "created": result.created,
+            "updated": result.updated,
+            "deleted": result.deleted,
+            "by_type": result.by_type,
+            "skipped": result.skipped,
+            "skip_reasons": skip_reasons,
+            "conflicts": result.conflicts,
+            "conflicts_resolved": result.conflicts_resolved,
+            "deferred": result.deferred,
+            "would_fail": result.would_fail,
+            "kept_both": kept_both,
+            "warnings": result.warnings,
+            "errors": result.errors,
+            "total_operations": result.total_operations(),
+            "bytes_copied": result.bytes_copied,
+            "success": result.is_success(),
+        });
+        if let Some(elapsed) = result.elapsed {
+            summary["timings"] = serde_json::json!({ "elapsed_ms": elapsed.as_millis() });
+        }
+        serde_json::to_string_pretty(&summary).context("Failed to serialize sync summary")
+    }
+}
+
+/// Format a byte count using binary units (e.g. "1.4 MiB")
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    format!("{value:.1} {unit}")
+}
+
+/// Wall time, plus files and MiB per second when any time passed
+#[allow(clippy::cast_precision_loss)]
+fn format_timing(result: &SyncResult, elapsed: Duration) -> String {
+    let duration = format_duration(elapsed);
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 {
+        return duration;
+    }
+
+    let files = result.total_operations() as f64 / seconds;
+    let mib = result.bytes_copied as f64 / (1024.0 * 1024.0) / seconds;
+    format!("{duration} ({files:.1} files/s, {mib:.1} MiB/s)")
+}
+
+/// Format a duration for people (e.g. "340ms", "12.3s", "2m 05s")
+fn format_duration(elapsed: Duration) -> String {
+    if elapsed < Duration::from_secs(1) {
+        format!("{}ms", elapsed.as_millis())
+    } else if elapsed < Duration::from_mins(1) {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    } else {
+        let seconds = elapsed.as_secs();
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1_468_006), "1.4 MiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_summary_includes_bytes_copied() {
+        let result = SyncResult {
+            created: 2,
+            bytes_copied: 2048,
+            ..SyncResult::default()
+        };
+
+        let summary = SyncReporter::generate_summary(&result);
+        assert!(summary.contains("Total operations: 2 (2.0 KiB copied)"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(340)), "340ms");
+        assert_eq!(format_duration(Duration::from_millis(12_340)), "12.3s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 05s");
+    }
+
+    #[test]
+    fn test_summary_includes_elapsed_time_and_throughput() {
+        let result = SyncResult {
+            created: 6,
+            updated: 4,
+            bytes_copied: 4 * 1024 * 1024,
+            elapsed: Some(Duration::from_secs(2)),
+            ..SyncResult::default()
+        };
+
+        let summary = SyncReporter::generate_summary(&result);
+        assert!(summary.contains("Elapsed:  2.0s (5.0 files/s, 2.0 MiB/s)\n"));
+
+        // Without a measured duration the line is left out
+        let summary = SyncReporter::generate_summary(&SyncResult::default());
+        assert!(!summary.contains("Elapsed"));
+    }
+
+    #[test]
+    fn test_summary_breaks_down_skip_reasons() {
+        let result = SyncResult {
+            skipped: 15,
+            skip_reasons: [
+                ("ignored by pattern".to_string(), 3),
+                ("identical content".to_string(), 12),
+            ]
+            .into(),
+            ..SyncResult::default()
+        };
+
+        let summary = SyncReporter::generate_summary(&result);
+        assert!(summary.contains(
+            "Skipped:  15\n  identical content: 12\n  ignored by pattern: 3\nConflicts: 0"
+        ));
+    }
+
+    #[test]
+    fn test_summary_breaks_down_operations_by_type() {
+        let result = SyncResult {
+            created: 3,
+            updated: 1,
+            by_type: [
+                ("skills".to_string(), 1),
+                ("agents".to_string(), 2),
+                ("commands".to_string(), 1),
+            ]
+            .into(),
+            ..SyncResult::default()
+        };
+
+        let summary = SyncReporter::generate_summary(&result);
+        assert!(summary.contains("Deleted:  0\nBy type:  agents: 2, commands: 1, skills: 1\n"));
+        assert!(!SyncReporter::generate_summary(&SyncResult::default()).contains("By type"));
+    }
+
+    #[test]
+    fn test_json_nests_skip_reasons() {
+        let result = SyncResult {
+            created: 1,
+            skipped: 4,
+            skip_reasons: [
+                ("excluded by pattern".to_string(), 1),
+                ("identical content".to_string(), 3),
+            ]
+            .into(),
+            elapsed: Some(Duration::from_millis(1500)),
+            ..SyncResult::default()
+        };
+
+        let json = SyncReporter::generate_json(&result).unwrap();
+        let summary: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(summary["created"], 1);
+        assert_eq!(summary["skipped"], 4);
+        assert_eq!(
+            summary["skip_reasons"],
+            serde_json::json!({"excluded by pattern": 1, "identical content": 3})
+        );
+        assert_eq!(summary["timings"], serde_json::json!({"elapsed_ms": 1500}));
+        assert!(summary.get("elapsed_ms").is_none());
+        assert_eq!(summary["success"], true);
+
+        let json = SyncReporter::generate_json(&SyncResult::default()).unwrap();
+        let summary: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(summary.get("timings").is_none());
+    }
+
+    #[test]
+    fn test_summary_reports_conflicts_resolved_by_approval() {
+        let result = SyncResult {
+            updated: 3,
+            conflicts_resolved: 3,
+            ..SyncResult::default()
+        };
+
+        let summary = SyncReporter::generate_summary(&result);
+        assert!(summary.contains("Conflicts: 0\nResolved: 3 conflict(s) by approval\n"));
+    }
+
+    #[test]
+    fn test_summary_lists_kept_both_copies() {
+        let result = SyncResult {
+            conflicts: 1,
+            kept_both: vec!["agents/a.incoming.md".into()],
+            ..SyncResult::default()
+        };
+
+        let summary = SyncReporter::generate_summary(&result);
+        assert!(summary.contains("Kept both (1), reconcile:\n  - agents/a.incoming.md"));
+    }
+
+    #[test]
+    fn test_summary_reports_conflicts_that_would_fail() {
+        let result = SyncResult {
+            conflicts: 2,
+            would_fail: 2,
+            ..SyncResult::default()
+        };
+
+        let summary = SyncReporter::generate_summary(&result);
+        assert!(summary.contains("Would fail: 2 conflict(s)"));
+        assert!(summary.contains("✗ The sync would fail"));
+    }
 }
@@ -0,0 +1,175 @@
+//! Filesystem watching for continuous sync
+//!
+//! Watches the `agents/`, `skills/`, `commands/`, and `hooks/` directories
+//! under a source root and yields debounced batches of changed paths. Callers
+//! re-run the sync once per batch, so a burst of edits results in a single
+//! sync.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{DebounceEventResult, DebouncedEventKind, Debouncer, new_debouncer};
+
+use crate::error::Result;
+
+/// Default quiet period before a batch of changes is reported
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Directories under the source root that are watched
+const WATCHED_DIRS: [&str; 4] = ["agents", "skills", "commands", "hooks"];
+
+/// Debounced watcher over a sync source root
+pub struct SyncWatcher {
+    // Kept alive for the lifetime of the watcher; dropping it stops watching
+    _debouncer: Debouncer<RecommendedWatcher>,
+    events: Receiver<DebounceEventResult>,
+}
+
+impl SyncWatcher {
+    /// Start watching the configuration directories under `source_root`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if none of the configuration directories exist or the
+    /// platform watcher cannot be set up.
+    pub fn new(source_root: &Path, debounce: Duration) -> Result<Self> {
+        let (tx, events) = mpsc::channel();
+        let mut debouncer =
+            new_debouncer(debounce, tx).context("Failed to create filesystem watcher")?;
+
+        let mut watched = 0;
+        for name in WATCHED_DIRS {
+            let dir = source_root.join(name);
+            if dir.is_dir() {
+                debouncer
+                    .watcher()
+                    .watch(&dir, RecursiveMode::Recursive)
+                    .with_context(|| format!("Failed to watch {}", dir.display()))?;
+                watched += 1;
+            }
+        }
+
+        if watched == 0 {
+            anyhow::bail!(
+                "Nothing to watch: none of {} exist under {}",
+                WATCHED_DIRS.join(", "),
+                source_root.display()
+            );
+        }
+
+        Ok(Self {
+            _debouncer: debouncer,
+            events,
+        })
+    }
+
+    /// Block until the next batch of changes and return the changed paths
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the watcher reports a failure or stops unexpectedly.
+    pub fn next_batch(&self) -> Result<Vec<PathBuf>> {
+        loop {
+            let batch = self
+                .events
+                .recv()
+                .context("Filesystem watcher stopped unexpectedly")?;
+            let paths = Self::settled_paths(batch)?;
+            if !paths.is_empty() {
+                return Ok(paths);
+            }
+        }
+    }
+
+    /// Wait up to `timeout` for the next batch of changes
+    ///
+    /// Returns `Ok(None)` if no changes were reported in time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the watcher reports a failure or stops unexpectedly.
+    pub fn next_batch_timeout(&self, timeout: Duration) -> Result<Option<Vec<PathBuf>>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.events.recv_timeout(remaining) {
+                Ok(batch) => {
+                    let paths = Self::settled_paths(batch)?;
+                    if !paths.is_empty() {
+                        return Ok(Some(paths));
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => {
+                    anyhow::bail!("Filesystem watcher stopped unexpectedly")
+                }
+            }
+        }
+    }
+
+    /// Paths that have been quiet for the whole debounce period
+    ///
+    /// Paths still changing are reported again once they settle, so they are
+    /// dropped here to avoid syncing mid-edit.
+    fn settled_paths(batch: DebounceEventResult) -> Result<Vec<PathBuf>> {
+        let events = batch.context("Filesystem watcher error")?;
+        Ok(events
+            .into_iter()
+            .filter(|event| event.kind == DebouncedEventKind::Any)
+            .map(|event| event.path)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const TEST_DEBOUNCE: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn test_touch_triggers_single_batch() {
+        let tmp = TempDir::new().unwrap();
+        let agents = tmp.path().join("agents");
+        fs::create_dir(&agents).unwrap();
+
+        let watcher = SyncWatcher::new(tmp.path(), TEST_DEBOUNCE).unwrap();
+
+        // A burst of writes within the debounce window
+        let agent = agents.join("agent.md");
+        fs::write(&agent, "v1").unwrap();
+        fs::write(&agent, "v2").unwrap();
+        fs::write(&agent, "v3").unwrap();
+
+        let batch = watcher
+            .next_batch_timeout(Duration::from_secs(5))
+            .unwrap()
+            .expect("change should be reported");
+        assert!(batch.iter().any(|p| p.ends_with("agent.md")));
+
+        // ...results in exactly one batch
+        let next = watcher.next_batch_timeout(TEST_DEBOUNCE * 4).unwrap();
+        assert!(next.is_none(), "expected a single batch, got {next:?}");
+    }
+
+    #[test]
+    fn test_nothing_to_watch() {
+        let tmp = TempDir::new().unwrap();
+
+        let result = SyncWatcher::new(tmp.path(), TEST_DEBOUNCE);
+
+        assert!(result.is_err());
+        assert!(
+            result
+                .err()
+                .unwrap()
+                .to_string()
+                .contains("Nothing to watch")
+        );
+    }
+}
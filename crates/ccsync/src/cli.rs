@@ -1,5 +1,7 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Claude Configuration Synchronization Tool
 ///
@@ -9,18 +11,90 @@ use std::path::PathBuf;
 #[command(about, long_about = None, version)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Cli {
-    /// Enable verbose output
-    #[arg(short, long, global = true)]
-    pub verbose: bool,
+    /// Enable verbose output (repeat for more detail: -v debug, -vv trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print nothing but errors (for cron jobs and scripts)
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print only the final summary, without per-file lines or warnings (overrides -v)
+    #[arg(long, global = true, conflicts_with_all = ["quiet", "show_diff"])]
+    pub summary_only: bool,
+
+    /// Print the final summary as JSON, with skip reasons keyed by reason
+    #[arg(long, global = true, conflicts_with_all = ["quiet", "verbose", "show_diff", "output_format"])]
+    pub json: bool,
+
+    /// Format of the final summary (overrides `output_format` in the config)
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub output_format: Option<SummaryFormat>,
 
     /// Accept all items in interactive mode without prompting
     #[arg(long, global = true)]
     pub yes_all: bool,
 
+    /// Prompt for each action even during a dry run or with piped stdin
+    #[arg(long, global = true, conflicts_with_all = ["yes_all", "non_interactive"])]
+    pub interactive: bool,
+
+    /// Never prompt; conflicts are resolved by the configured strategy
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+
     /// Preview changes without executing (dry-run)
     #[arg(long, global = true)]
     pub dry_run: bool,
 
+    /// Execute changes even if a config file sets `dry_run = true`
+    #[arg(long, global = true, conflicts_with = "dry_run")]
+    pub no_dry_run: bool,
+
+    /// With --dry-run, also print the diff of each conflicting file
+    #[arg(long, global = true, requires = "dry_run")]
+    pub show_diff: bool,
+
+    /// When to color output
+    #[arg(long, global = true, value_enum, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorWhen,
+
+    /// Record a failed file and keep syncing the rest, exiting nonzero at the end (default)
+    #[arg(long, global = true, visible_alias = "continue-on-error")]
+    pub keep_going: bool,
+
+    /// Re-hash each source just before copying it, re-planning any that changed since the scan
+    #[arg(long, global = true)]
+    pub verify: bool,
+
+    /// Abort at the first file that fails to sync
+    #[arg(long, global = true, conflicts_with = "keep_going")]
+    pub fail_fast: bool,
+
+    /// Remove destination files ccsync created whose source was deleted
+    #[arg(long, global = true)]
+    pub prune: bool,
+
+    /// Stop after N changes, leaving the rest for a later run
+    #[arg(long, global = true, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Scan up to N directory types at once (default: number of CPUs; 1 is serial)
+    #[arg(long, global = true, value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// With --dry-run, also write the planned actions to this file as JSON
+    #[arg(long, global = true, value_name = "PATH", requires = "dry_run")]
+    pub plan_out: Option<PathBuf>,
+
+    /// With --dry-run, exit with code 1 if the sync would change anything
+    #[arg(long, global = true, requires = "dry_run")]
+    pub detect_changes: bool,
+
+    /// Exit with code 2 when conflicts are left unresolved (e.g. with --conflict skip)
+    #[arg(long, global = true)]
+    pub fail_on_conflict: bool,
+
     /// Override global path (default: ~/.claude)
     #[arg(long, global = true, value_name = "PATH")]
     pub global_path: Option<PathBuf>,
@@ -29,6 +103,10 @@ pub struct Cli {
     #[arg(long, global = true, value_name = "PATH")]
     pub local_path: Option<PathBuf>,
 
+    /// Allow writing to a destination outside the home and project directories
+    #[arg(long, global = true)]
+    pub allow_outside_home: bool,
+
     /// Use specific config file
     #[arg(long, global = true, value_name = "PATH", conflicts_with = "no_config")]
     pub config: Option<PathBuf>,
@@ -37,10 +115,34 @@ pub struct Cli {
     #[arg(long, global = true, conflicts_with = "config")]
     pub no_config: bool,
 
+    /// Apply a named profile from the config files (e.g. [profiles.work])
+    #[arg(long, global = true, value_name = "NAME", conflicts_with = "no_config")]
+    pub profile: Option<String>,
+
     /// Preserve symlinks instead of following them
     #[arg(long, global = true)]
     pub preserve_symlinks: bool,
 
+    /// Copy what symlinks point to as regular files, replacing links in the destination
+    #[arg(long, global = true, conflicts_with = "preserve_symlinks")]
+    pub dereference: bool,
+
+    /// Treat files whose YAML frontmatter differs only in key order as identical
+    #[arg(long, global = true)]
+    pub semantic_frontmatter: bool,
+
+    /// Hardlink files whose content already exists in the destination instead of copying
+    #[arg(long, global = true)]
+    pub hardlink_identical: bool,
+
+    /// Give synced files the owner and group of their source (Unix; usually needs root)
+    #[arg(long, global = true)]
+    pub preserve_ownership: bool,
+
+    /// Skip an action when its interactive prompt gets no answer within SECS seconds
+    #[arg(long, global = true, value_name = "SECS")]
+    pub prompt_timeout: Option<u64>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -56,6 +158,21 @@ pub enum Commands {
         /// Conflict resolution strategy
         #[arg(long, value_enum, default_value = "fail")]
         conflict: ConflictMode,
+
+        #[command(flatten)]
+        patterns: PatternArgs,
+
+        /// Keep running and re-sync whenever the source changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Only sync files modified within this window (e.g. 30m, 2h, 1d)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        since: Option<Duration>,
+
+        /// Also preview what syncing in the opposite direction would do
+        #[arg(long)]
+        reverse: bool,
     },
 
     /// Sync from local (./.claude) to global (~/.claude)
@@ -67,13 +184,54 @@ pub enum Commands {
         /// Conflict resolution strategy
         #[arg(long, value_enum, default_value = "fail")]
         conflict: ConflictMode,
+
+        #[command(flatten)]
+        patterns: PatternArgs,
+
+        /// Keep running and re-sync whenever the source changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Only sync files modified within this window (e.g. 30m, 2h, 1d)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        since: Option<Duration>,
+
+        /// Also preview what syncing in the opposite direction would do
+        #[arg(long)]
+        reverse: bool,
+    },
+
+    /// Sync both ways, copying whichever side is newer
+    Sync {
+        /// Filter by configuration type(s)
+        #[arg(short = 't', long = "type", value_enum)]
+        types: Vec<ConfigType>,
+
+        /// Strategy for files edited on both sides that cannot be ordered by time
+        #[arg(long, value_enum, default_value = "fail")]
+        conflict: ConflictMode,
+
+        #[command(flatten)]
+        patterns: PatternArgs,
     },
 
     /// Show sync status without making changes
+    #[command(
+        after_help = "Exit status: 0 when in sync, 1 when differences exist, 2 on error."
+    )]
     Status {
         /// Filter by configuration type(s)
         #[arg(short = 't', long = "type", value_enum)]
         types: Vec<ConfigType>,
+
+        /// List conflicting files with the side `--conflict newer` would keep
+        #[arg(long)]
+        newer: bool,
+
+        /// List local files missing from the global side, including files
+        /// inside directories on both sides (nothing is deleted)
+        #[arg(long)]
+        orphans: bool,
     },
 
     /// Display detailed differences between configurations
@@ -81,10 +239,75 @@ pub enum Commands {
         /// Filter by configuration type(s)
         #[arg(short = 't', long = "type", value_enum)]
         types: Vec<ConfigType>,
+
+        /// Show changed line counts per file instead of full diffs
+        #[arg(long)]
+        stat: bool,
+
+        /// Only show files that exist on both sides with different content
+        #[arg(long)]
+        only_conflicts: bool,
+
+        /// Output format of full diffs
+        #[arg(long, value_enum, default_value = "text", conflicts_with = "stat")]
+        format: DiffFormat,
     },
 
     /// Show active configuration and debug settings
     Config,
+
+    /// Check the environment and configuration for common problems
+    Doctor,
+
+    /// Revert the most recent sync (restores overwritten files, removes created ones)
+    Undo,
+
+    /// Remove ccsync's state files (baselines, manifests, and the undo journal)
+    Clean,
+
+    /// Show whether a path passes the ignore/include patterns, and which pattern decided
+    CheckPattern {
+        /// Path relative to the .claude directory, e.g. agents/git-commit.md
+        #[arg(long, value_name = "PATH")]
+        path: PathBuf,
+
+        /// Check the path as a directory (patterns ending in / only match directories)
+        #[arg(long)]
+        dir: bool,
+
+        /// Filter by configuration type(s), as the sync commands do
+        #[arg(short = 't', long = "type", value_enum)]
+        types: Vec<ConfigType>,
+
+        #[command(flatten)]
+        patterns: PatternArgs,
+    },
+
+    /// Execute a plan saved with --plan-out, refusing it if its sources changed
+    Apply {
+        /// Plan file written by `--dry-run --plan-out`
+        plan: PathBuf,
+    },
+}
+
+/// Ad-hoc filter flags shared by the sync subcommands
+#[derive(Args, Debug, Clone, Default)]
+pub struct PatternArgs {
+    /// Exclude paths matching a gitignore-style pattern (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+
+    /// Include paths matching a pattern even if ignored (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub include: Vec<String>,
+
+    /// Only sync paths containing this text, e.g. "git-commit" (repeatable)
+    #[arg(long = "match", value_name = "TEXT")]
+    pub path_match: Vec<String>,
+
+    /// Skip files larger than this size (e.g. 500K, 10M, 1G)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub max_file_size: Option<u64>,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -95,6 +318,8 @@ pub enum ConfigType {
     Skills,
     /// Command configurations
     Commands,
+    /// Hook configurations
+    Hooks,
     /// All configuration types
     All,
 }
@@ -109,4 +334,120 @@ pub enum ConflictMode {
     Skip,
     /// Keep newer file
     Newer,
+    /// Keep the existing file and write the incoming one next to it as name.incoming.ext
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ColorWhen {
+    /// Color only when stdout is a terminal (default)
+    Auto,
+    /// Always color, even when piped
+    Always,
+    /// Never color
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SummaryFormat {
+    /// Human-readable summary (default)
+    Text,
+    /// JSON object with skip reasons keyed by reason
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    /// Unified diff text (default)
+    Text,
+    /// JSON array of files with line counts and hunks
+    Json,
+}
+
+/// Parse a duration such as `45s`, `30m`, `2h`, or `1d`
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration '{value}': expected e.g. 30m or 2h"))?;
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid duration unit in '{value}': use s, m, h, or d"
+            ));
+        }
+    };
+
+    amount
+        .checked_mul(seconds_per_unit)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration '{value}' is too large"))
+}
+
+/// Parse a size such as `512`, `500K`, `10MB`, or `1GiB` (binary units)
+fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid size '{value}': expected e.g. 500K or 10M"))?;
+    let multiplier: u64 = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => {
+            return Err(format!("invalid size unit in '{value}': use K, M, or G"));
+        }
+    };
+
+    amount
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size '{value}' is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("45s"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_mins(30)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_hours(2)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_hours(24)));
+    }
+
+    #[test]
+    fn test_parse_size_units() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("500K"), Ok(500 * 1024));
+        assert_eq!(parse_size("10mb"), Ok(10 * 1024 * 1024));
+        assert_eq!(parse_size("1GiB"), Ok(1024 * 1024 * 1024));
+        assert!(parse_size("").is_err());
+        assert!(parse_size("10T").is_err());
+        assert!(parse_size("-1M").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_invalid_input() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("10").is_err());
+        assert!(parse_duration("10w").is_err());
+        assert!(parse_duration("-5m").is_err());
+    }
 }
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use anyhow::Context;
+use ccsync_core::sync::{PlanFile, SyncEngine};
+
+use crate::commands::{SyncOptions, common};
+
+pub struct Apply;
+
+impl Apply {
+    /// Execute a plan saved by `--plan-out` without re-scanning the source
+    pub fn execute(plan_path: &Path, options: &SyncOptions) -> anyhow::Result<()> {
+        if options.verbose {
+            println!("Executing apply command");
+            println!("Plan: {}", plan_path.display());
+            println!("Dry run: {}", options.dry_run);
+        }
+
+        let plan = PlanFile::load(plan_path)?;
+
+        // Rules and retries still come from the config; the plan fixes the rest
+        let config = options.load_config()?;
+        common::check_destination(&plan.dest_root, &config, options)?;
+        let engine = SyncEngine::new(config, plan.direction)
+            .context("Failed to initialize sync engine")?
+            .with_journal(common::journal_path(options)?)
+            .with_limit(options.limit)
+            .with_fail_fast(options.fail_fast)
+            .with_verify(options.verify);
+        let result = engine.apply(&plan).context("Failed to apply plan")?;
+
+        common::report(&result, engine.output_format(), options)
+    }
+}
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use anyhow::Context;
+use ccsync_core::config::{PatternDecision, PatternMatcher};
+
+use crate::cli::{ConfigType, PatternArgs};
+use crate::commands::{SyncOptions, common};
+
+pub struct CheckPattern;
+
+impl CheckPattern {
+    /// Print whether `path` passes the configured patterns, and which one decided
+    ///
+    /// `--type`, `--include`, and `--exclude` are merged in as a sync would.
+    pub fn execute(
+        path: &Path,
+        is_dir: bool,
+        types: &[ConfigType],
+        patterns: &PatternArgs,
+        options: &SyncOptions,
+    ) -> anyhow::Result<()> {
+        let mut config = options.load_config()?;
+        common::apply_patterns(&mut config, types, patterns);
+
+        if options.verbose {
+            println!("Executing check-pattern command");
+            println!("Ignore patterns: {:?}", config.ignore);
+            println!("Include patterns: {:?}", config.include);
+            println!("Exclude patterns: {:?}", config.exclude);
+        }
+
+        let matcher = PatternMatcher::with_patterns(&config.ignore, &config.include)
+            .and_then(|matcher| matcher.with_excludes(&config.exclude))
+            .context("Failed to compile the configured patterns")?;
+        let verdict = match matcher.decide(path, is_dir) {
+            PatternDecision::Unmatched => "included (no pattern matches)".to_string(),
+            PatternDecision::Ignored(pattern) if config.exclude.contains(&pattern) => {
+                format!("excluded by exclude pattern '{pattern}'")
+            }
+            PatternDecision::Ignored(pattern) => format!("excluded by ignore pattern '{pattern}'"),
+            PatternDecision::Included(pattern) => {
+                format!("included by include pattern '{pattern}'")
+            }
+        };
+        println!("{}: {verdict}", path.display());
+
+        Ok(())
+    }
+}
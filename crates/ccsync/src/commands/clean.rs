@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use ccsync_core::sync::{BASELINE_FILE, MANIFEST_FILE};
+
+use crate::commands::{SyncOptions, common};
+
+pub struct Clean;
+
+impl Clean {
+    /// Remove the baseline and manifest from both directories, and the undo journal
+    pub fn execute(options: &SyncOptions) -> anyhow::Result<()> {
+        let global_path = options.global_path()?;
+        let local_path = options.local_path()?;
+        let journal_path = common::journal_path(options)?;
+
+        if options.verbose {
+            println!("Executing clean command");
+            println!("Local path: {}", local_path.display());
+            println!("Global path: {}", global_path.display());
+            println!("Dry run: {}", options.dry_run);
+        }
+
+        let state_files: Vec<PathBuf> = [&local_path, &global_path]
+            .into_iter()
+            .flat_map(|root| [root.join(BASELINE_FILE), root.join(MANIFEST_FILE)])
+            .chain([journal_path])
+            .filter(|path| fs::symlink_metadata(path).is_ok())
+            .collect();
+
+        for path in &state_files {
+            if !options.dry_run {
+                remove(path)?;
+            }
+            if !options.quiet && !options.summary_only {
+                if options.dry_run {
+                    println!("[DRY RUN] Would remove {}", path.display());
+                } else {
+                    println!("Removed {}", path.display());
+                }
+            }
+        }
+
+        if options.quiet {
+            return Ok(());
+        }
+
+        if state_files.is_empty() {
+            println!("No ccsync state files found");
+        } else if options.dry_run {
+            println!("[DRY RUN] Would remove {} state file(s)", state_files.len());
+        } else {
+            println!("✓ Clean complete: {} state file(s) removed", state_files.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove a state file, or the journal directory with everything in it
+fn remove(path: &Path) -> anyhow::Result<()> {
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    removed.with_context(|| format!("Failed to remove {}", path.display()))?;
+
+    // Drop the journal's parent (.ccsync) too once nothing else is in it
+    if let Some(parent) = path.parent()
+        && parent.file_name().is_some_and(|name| name == ".ccsync")
+    {
+        let _ = fs::remove_dir(parent);
+    }
+    Ok(())
+}
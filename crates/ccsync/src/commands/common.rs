@@ -1,20 +1,110 @@
 //! Common types and utilities for command execution
 
-use ccsync_core::config::{Config, ConfigManager};
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use ccsync_core::comparison::ConflictStrategy;
+use ccsync_core::config::{Config, ConfigManager, OutputFormat, SyncDirection};
+use ccsync_core::sync::{
+    Hook, PlanFile, PlanSize, SyncAction, SyncEngine, SyncReporter, SyncResult,
+};
+use ccsync_core::watch::{DEFAULT_DEBOUNCE, SyncWatcher};
+
+use crate::cli::{ConfigType, ConflictMode, PatternArgs};
+use crate::interactive::{InteractivePrompter, UserAborted};
+
+/// Exit code used when `--fail-on-conflict` finds unresolved conflicts
+pub const CONFLICT_EXIT_CODE: i32 = 2;
+
+/// Error returned when `--fail-on-conflict` finds unresolved conflicts
+///
+/// `main` exits with [`CONFLICT_EXIT_CODE`] on it, while watch mode reports it
+/// and keeps watching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnresolvedConflicts(pub usize);
+
+impl std::fmt::Display for UnresolvedConflicts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} conflict(s) left unresolved", self.0)
+    }
+}
+
+impl std::error::Error for UnresolvedConflicts {}
+
+/// Exit code used when `--detect-changes` finds that a dry run would change something
+pub const CHANGES_EXIT_CODE: i32 = 1;
+
+/// Plans copying more files than this ask for confirmation first
+const PREFLIGHT_MAX_FILES: usize = 500;
+
+/// Plans copying more bytes than this (1 GiB) ask for confirmation first
+const PREFLIGHT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
 
 /// Execution options for sync commands
 #[allow(clippy::struct_excessive_bools)]
 pub struct SyncOptions<'a> {
     /// Enable verbose output
     pub verbose: bool,
+    /// Suppress all output except errors
+    pub quiet: bool,
     /// Preview changes without applying (dry-run)
     pub dry_run: bool,
+    /// Apply changes even if the config sets `dry_run` (`--no-dry-run`)
+    pub no_dry_run: bool,
     /// Auto-approve all operations without prompting
     pub yes_all: bool,
     /// Path to custom config file
-    pub config_path: Option<&'a std::path::Path>,
+    pub config_path: Option<&'a Path>,
     /// Skip loading all config files
     pub no_config: bool,
+    /// Named config profile to overlay on the merged config
+    pub profile: Option<&'a str>,
+    /// Override for the global directory (default: ~/.claude)
+    pub global_path: Option<&'a Path>,
+    /// Override for the local directory (default: ./.claude)
+    pub local_path: Option<&'a Path>,
+    /// Exit nonzero when conflicts remain unresolved
+    pub fail_on_conflict: bool,
+    /// Print conflict diffs during a dry run
+    pub show_diff: bool,
+    /// Hardlink identical files instead of copying them
+    pub hardlink_identical: bool,
+    /// Copy the owner and group of synced files
+    pub preserve_ownership: bool,
+    /// Compare YAML frontmatter regardless of key order
+    pub semantic_frontmatter: bool,
+    /// Copy symlink targets as regular files (sets `follow_symlinks`)
+    pub dereference: bool,
+    /// Print only the final summary, without per-item lines
+    pub summary_only: bool,
+    /// Explicit `--interactive` (true) or `--non-interactive` (false) mode
+    pub interactive: Option<bool>,
+    /// Summary format chosen on the command line (`--json`/`--output-format`)
+    pub output_format: Option<OutputFormat>,
+    /// Where a dry run writes its planned actions as JSON
+    pub plan_out: Option<&'a Path>,
+    /// Exit with [`CHANGES_EXIT_CODE`] when a dry run would change something
+    pub detect_changes: bool,
+    /// Maximum number of changes per sync
+    pub limit: Option<usize>,
+    /// Directory types scanned concurrently
+    pub jobs: Option<usize>,
+    /// Remove managed destination entries whose source is gone
+    pub prune: bool,
+    /// Abort at the first failed file instead of continuing
+    pub fail_fast: bool,
+    /// Re-hash sources just before copying them
+    pub verify: bool,
+    /// Print the planned action for every file and why it was chosen (`-vv`)
+    pub trace_decisions: bool,
+    /// Also print the plan for the opposite direction
+    pub reverse: bool,
+    /// Skip an action when its prompt gets no answer in time
+    pub prompt_timeout: Option<Duration>,
+    /// Write to destinations outside the home and project directories
+    pub allow_outside_home: bool,
 }
 
 impl<'a> SyncOptions<'a> {
@@ -23,26 +113,264 @@ impl<'a> SyncOptions<'a> {
     #[allow(clippy::fn_params_excessive_bools)]
     pub const fn new(
         verbose: bool,
+        quiet: bool,
         dry_run: bool,
         yes_all: bool,
-        config_path: Option<&'a std::path::Path>,
+        config_path: Option<&'a Path>,
         no_config: bool,
+        profile: Option<&'a str>,
     ) -> Self {
         Self {
             verbose,
+            quiet,
             dry_run,
+            no_dry_run: false,
             yes_all,
             config_path,
             no_config,
+            profile,
+            global_path: None,
+            local_path: None,
+            fail_on_conflict: false,
+            show_diff: false,
+            hardlink_identical: false,
+            preserve_ownership: false,
+            semantic_frontmatter: false,
+            dereference: false,
+            summary_only: false,
+            interactive: None,
+            output_format: None,
+            plan_out: None,
+            detect_changes: false,
+            limit: None,
+            jobs: None,
+            prune: false,
+            fail_fast: false,
+            verify: false,
+            trace_decisions: false,
+            reverse: false,
+            prompt_timeout: None,
+            allow_outside_home: false,
+        }
+    }
+
+    /// Use these directories instead of ~/.claude and ./.claude
+    #[must_use]
+    pub const fn with_paths(mut self, global: Option<&'a Path>, local: Option<&'a Path>) -> Self {
+        self.global_path = global;
+        self.local_path = local;
+        self
+    }
+
+    /// Resolve the global directory: `--global-path`, or ~/.claude
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no override and the home directory cannot
+    /// be determined.
+    pub fn global_path(&self) -> anyhow::Result<PathBuf> {
+        self.global_path
+            .map_or_else(global_path, |path| Ok(path.to_path_buf()))
+    }
+
+    /// Resolve the local directory: `--local-path`, or ./.claude
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no override and the current directory
+    /// cannot be determined.
+    pub fn local_path(&self) -> anyhow::Result<PathBuf> {
+        self.local_path
+            .map_or_else(local_path, |path| Ok(path.to_path_buf()))
+    }
+
+    /// Treat unresolved conflicts as a failure
+    #[must_use]
+    pub const fn with_fail_on_conflict(mut self, enabled: bool) -> Self {
+        self.fail_on_conflict = enabled;
+        self
+    }
+
+    /// Override a config-level `dry_run = true`
+    #[must_use]
+    pub const fn with_no_dry_run(mut self, enabled: bool) -> Self {
+        self.no_dry_run = enabled;
+        self
+    }
+
+    /// Print the diff of each conflict during a dry run
+    #[must_use]
+    pub const fn with_show_diff(mut self, enabled: bool) -> Self {
+        self.show_diff = enabled;
+        self
+    }
+
+    /// Prune destination entries ccsync created whose source was deleted
+    #[must_use]
+    pub const fn with_prune(mut self, enabled: bool) -> Self {
+        self.prune = enabled;
+        self
+    }
+
+    /// Compare frontmatter as parsed YAML instead of bytes
+    #[must_use]
+    pub const fn with_semantic_frontmatter(mut self, enabled: bool) -> Self {
+        self.semantic_frontmatter = enabled;
+        self
+    }
+
+    /// Give up on unanswered interactive prompts after `timeout`
+    #[must_use]
+    pub const fn with_prompt_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.prompt_timeout = timeout;
+        self
+    }
+
+    /// Write symlinked files as regular files holding their target's content
+    #[must_use]
+    pub const fn with_dereference(mut self, enabled: bool) -> Self {
+        self.dereference = enabled;
+        self
+    }
+
+    /// Hardlink files whose content already exists in the destination
+    #[must_use]
+    pub const fn with_hardlink_identical(mut self, enabled: bool) -> Self {
+        self.hardlink_identical = enabled;
+        self
+    }
+
+    /// Give synced files the owner and group of their source
+    #[must_use]
+    pub const fn with_preserve_ownership(mut self, enabled: bool) -> Self {
+        self.preserve_ownership = enabled;
+        self
+    }
+
+    /// Print only the final summary of each command
+    #[must_use]
+    pub const fn with_summary_only(mut self, enabled: bool) -> Self {
+        self.summary_only = enabled;
+        self
+    }
+
+    /// Force prompting on (`Some(true)`) or off (`Some(false)`)
+    ///
+    /// `None` infers the mode from `--yes-all` and `--dry-run`.
+    #[must_use]
+    pub const fn with_interactive(mut self, interactive: Option<bool>) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    /// Whether each action is prompted for
+    ///
+    /// An explicit `--interactive`/`--non-interactive` decides; otherwise
+    /// `--yes-all` and dry runs are non-interactive.
+    #[must_use]
+    pub const fn prompts(&self, dry_run: bool) -> bool {
+        match self.interactive {
+            Some(interactive) => interactive,
+            None => !(self.yes_all || dry_run),
         }
     }
 
+    /// Render the final summary in `format` instead of the configured one
+    #[must_use]
+    pub const fn with_output_format(mut self, format: Option<OutputFormat>) -> Self {
+        self.output_format = format;
+        self
+    }
+
+    /// Make a dry run that would change something exit nonzero
+    #[must_use]
+    pub const fn with_detect_changes(mut self, enabled: bool) -> Self {
+        self.detect_changes = enabled;
+        self
+    }
+
+    /// Stop each sync after this many changes
+    #[must_use]
+    pub const fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Scan at most this many directory types at once
+    #[must_use]
+    pub const fn with_jobs(mut self, jobs: Option<usize>) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// Abort each sync at the first failed file
+    #[must_use]
+    pub const fn with_fail_fast(mut self, enabled: bool) -> Self {
+        self.fail_fast = enabled;
+        self
+    }
+
+    /// Re-plan sources that change between the scan and the copy
+    #[must_use]
+    pub const fn with_verify(mut self, enabled: bool) -> Self {
+        self.verify = enabled;
+        self
+    }
+
+    /// Print each per-file decision
+    #[must_use]
+    pub const fn with_trace_decisions(mut self, enabled: bool) -> Self {
+        self.trace_decisions = enabled;
+        self
+    }
+
+    /// Preview the opposite direction after each sync
+    #[must_use]
+    pub const fn with_reverse(mut self, enabled: bool) -> Self {
+        self.reverse = enabled;
+        self
+    }
+
+    /// Allow writing outside the home and project directories
+    #[must_use]
+    pub const fn with_allow_outside_home(mut self, enabled: bool) -> Self {
+        self.allow_outside_home = enabled;
+        self
+    }
+
+    /// Write the plan of a dry run to this file
+    #[must_use]
+    pub const fn with_plan_out(mut self, path: Option<&'a Path>) -> Self {
+        self.plan_out = path;
+        self
+    }
+
     /// Load configuration from files or use defaults
     ///
+    /// `--dry-run`, `--no-dry-run`, and `--jobs` override the values from the files.
+    ///
     /// # Errors
     ///
-    /// Returns an error if config file is explicitly specified but cannot be loaded.
+    /// Returns an error if a config file or profile is explicitly specified but
+    /// cannot be loaded.
     pub fn load_config(&self) -> anyhow::Result<Config> {
+        let mut config = self.load_config_files()?;
+        if self.dry_run {
+            config.dry_run = Some(true);
+        } else if self.no_dry_run {
+            config.dry_run = Some(false);
+        }
+        if self.jobs.is_some() {
+            config.jobs = self.jobs;
+        }
+        if self.output_format.is_some() {
+            config.output_format = self.output_format;
+        }
+        Ok(config)
+    }
+
+    /// Load the config files, falling back to defaults unless one was requested
+    fn load_config_files(&self) -> anyhow::Result<Config> {
         if self.no_config {
             if self.verbose {
                 println!("Skipping config file loading (--no-config)");
@@ -50,11 +378,11 @@ impl<'a> SyncOptions<'a> {
             return Ok(Config::default());
         }
 
-        match ConfigManager::load(self.config_path) {
+        match ConfigManager::load(self.config_path, self.profile) {
             Ok(config) => Ok(config),
             Err(e) => {
-                // If user explicitly specified a config file, fail hard
-                if self.config_path.is_some() {
+                // If user explicitly specified a config file or profile, fail hard
+                if self.config_path.is_some() || self.profile.is_some() {
                     anyhow::bail!("Failed to load config file: {e}");
                 }
 
@@ -68,3 +396,638 @@ impl<'a> SyncOptions<'a> {
         }
     }
 }
+
+/// Attach a printer of each per-file decision when `-vv` is given
+///
+/// # Errors
+///
+/// Returns an error if the global or local path cannot be resolved.
+pub fn with_decision_trace(engine: SyncEngine, options: &SyncOptions) -> anyhow::Result<SyncEngine> {
+    if !options.trace_decisions {
+        return Ok(engine);
+    }
+
+    let roots = [options.global_path()?, options.local_path()?];
+    Ok(engine.with_decision_callback(Box::new(move |action| {
+        let path = action.source_path();
+        let path = roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        println!("  {}: {}", path.display(), action.explain());
+    })))
+}
+
+/// Run one sync pass and print its summary
+///
+/// When [`SyncOptions::prompts`], previews the plan, asks once whether to
+/// proceed, and then prompts for each action. A dry run with
+/// `--plan-out` also writes the plan to that file, and one with
+/// `--detect-changes` exits with [`CHANGES_EXIT_CODE`] after the summary if
+/// the plan would change anything. With `--reverse`, the
+/// plan for the opposite direction is printed after the summary. The
+/// `pre_sync` hook runs first and `post_sync` after a successful sync.
+///
+/// # Errors
+///
+/// Returns an error if a hook or the sync fails. A user abort exits the
+/// process cleanly.
+pub fn run_sync(
+    engine: &SyncEngine,
+    source: &Path,
+    dest: &Path,
+    options: &SyncOptions,
+) -> anyhow::Result<()> {
+    run_hook(engine, Hook::PreSync, options)?;
+
+    let dry_run = engine.dry_run();
+    let preview = if dry_run && (options.plan_out.is_some() || options.detect_changes) {
+        Some(engine.plan(source, dest).context("Failed to plan sync")?)
+    } else {
+        None
+    };
+    if let (Some(actions), Some(plan_out)) = (&preview, options.plan_out) {
+        PlanFile::new(engine.direction(), source, dest, actions.clone())?.save(plan_out)?;
+    }
+
+    let result = if options.prompts(dry_run) {
+        // Interactive mode: prompt for each action (requires a terminal unless forced)
+        if options.interactive.is_none() {
+            InteractivePrompter::ensure_terminal()?;
+        }
+
+        let plan = engine.plan(source, dest).context("Failed to plan sync")?;
+        if !preflight(&plan, options, |size| {
+            InteractivePrompter::confirm_large_plan(size, options.prompt_timeout)
+        })? || !InteractivePrompter::confirm_plan(&plan, options.prompt_timeout)?
+        {
+            eprintln!("\nSync cancelled by user.");
+            return Ok(());
+        }
+
+        let mut prompter =
+            InteractivePrompter::with_plan(plan).with_prompt_timeout(options.prompt_timeout);
+        match engine.sync_with_approver(
+            source,
+            dest,
+            Some(Box::new(move |action| prompter.prompt(action))),
+        ) {
+            Ok(result) => result,
+            Err(e) if e.is::<UserAborted>() => {
+                eprintln!("\nSync cancelled by user.");
+                std::process::exit(0); // Clean exit, not an error
+            }
+            Err(e) => return Err(e).context("Sync operation failed"),
+        }
+    } else {
+        // Non-interactive: apply the configured conflict strategy or just preview
+        engine.sync(source, dest).context("Sync operation failed")?
+    };
+
+    // The reverse preview is printed even when the summary reports a failure
+    let reported = report(&result, engine.output_format(), options);
+    if options.reverse {
+        print_reverse_plan(engine, source, dest)?;
+    }
+    reported?;
+
+    if options.detect_changes
+        && let Some(actions) = &preview
+    {
+        let changes = actions
+            .iter()
+            .filter(|action| !matches!(action, SyncAction::Skip { .. }))
+            .count();
+        if changes > 0 {
+            if !options.quiet {
+                eprintln!("Changes detected: {changes} entry(ies) would be synced");
+            }
+            std::process::exit(CHANGES_EXIT_CODE);
+        }
+    }
+
+    run_hook(engine, Hook::PostSync, options)
+}
+
+/// Run the engine's `pre_sync` or `post_sync` command, if configured
+///
+/// The hook's output is printed with `--verbose`.
+///
+/// # Errors
+///
+/// Returns an error if the hook fails.
+pub fn run_hook(engine: &SyncEngine, hook: Hook, options: &SyncOptions) -> anyhow::Result<()> {
+    if let Some(output) = engine.run_hook(hook)?
+        && options.verbose
+    {
+        println!("Ran {hook} hook");
+        print!("{output}");
+    }
+    Ok(())
+}
+
+/// Check a plan against the preflight limits before syncing
+///
+/// A plan copying more than [`PREFLIGHT_MAX_FILES`] files or
+/// [`PREFLIGHT_MAX_BYTES`] bytes is passed to `confirm`, which decides
+/// whether to go on. Smaller plans, and any plan with `--yes-all`, go on
+/// without asking.
+///
+/// # Errors
+///
+/// Returns an error if `confirm` fails.
+fn preflight(
+    plan: &[SyncAction],
+    options: &SyncOptions,
+    confirm: impl FnOnce(PlanSize) -> anyhow::Result<bool>,
+) -> anyhow::Result<bool> {
+    if options.yes_all {
+        return Ok(true);
+    }
+    let size = PlanSize::of(plan);
+    if size.files > PREFLIGHT_MAX_FILES || size.bytes > PREFLIGHT_MAX_BYTES {
+        confirm(size)
+    } else {
+        Ok(true)
+    }
+}
+
+/// Print what syncing from `dest` back to `source` would do
+///
+/// # Errors
+///
+/// Returns an error if the reverse plan cannot be computed.
+pub fn print_reverse_plan(engine: &SyncEngine, source: &Path, dest: &Path) -> anyhow::Result<()> {
+    let reversed = engine
+        .reversed()
+        .context("Failed to initialize reverse sync engine")?;
+    let plan = reversed
+        .plan(dest, source)
+        .context("Failed to plan reverse sync")?;
+
+    let command = match reversed.direction() {
+        SyncDirection::ToLocal => "to-local",
+        SyncDirection::ToGlobal => "to-global",
+    };
+    println!("\n=== Reverse preview ({command}) ===");
+    let mut changes = 0;
+    for action in plan.iter().filter(|action| !matches!(action, SyncAction::Skip { .. })) {
+        let path = action.source_path();
+        let path = path.strip_prefix(dest).unwrap_or(path);
+        println!("  {}: {}", path.display(), action.explain());
+        changes += 1;
+    }
+    if changes == 0 {
+        println!("  Nothing to sync");
+    }
+    Ok(())
+}
+
+/// Print the summary of a finished sync in `format`
+///
+/// With `--quiet` the summary is suppressed and only errors are written to
+/// stderr.
+///
+/// # Errors
+///
+/// In quiet mode, returns an error if any operation failed so the process
+/// exits nonzero. With `--fail-on-conflict`, unresolved conflicts return
+/// [`UnresolvedConflicts`]. A dry run that found conflicts the real run would
+/// fail on (`--conflict fail`) also returns an error, after the summary.
+pub fn report(
+    result: &SyncResult,
+    format: OutputFormat,
+    options: &SyncOptions,
+) -> anyhow::Result<()> {
+    if options.quiet {
+        for error in &result.errors {
+            eprintln!("{error}");
+        }
+        if !result.errors.is_empty() {
+            anyhow::bail!("{} operation(s) failed", result.errors.len());
+        }
+    } else if format == OutputFormat::Json {
+        println!("{}", SyncReporter::generate_json(result)?);
+    } else {
+        let summary = SyncReporter::generate_summary(result);
+        println!("{summary}");
+    }
+
+    if options.fail_on_conflict && result.conflicts > 0 {
+        return Err(UnresolvedConflicts(result.conflicts).into());
+    }
+
+    if result.would_fail > 0 {
+        anyhow::bail!(
+            "The sync would fail on {} conflict(s); choose a --conflict strategy",
+            result.would_fail
+        );
+    }
+
+    Ok(())
+}
+
+/// Stay resident and re-run the sync after each debounced batch of changes
+///
+/// Runs until interrupted (Ctrl+C is handled in `main`). A failed pass is
+/// reported and watching continues.
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be watched or the user aborts.
+pub fn watch(
+    engine: &SyncEngine,
+    source: &Path,
+    dest: &Path,
+    options: &SyncOptions,
+) -> anyhow::Result<()> {
+    let watcher =
+        SyncWatcher::new(source, DEFAULT_DEBOUNCE).context("Failed to start watch mode")?;
+    if !options.quiet {
+        println!(
+            "Watching {} for changes (Ctrl+C to stop)...",
+            source.display()
+        );
+    }
+
+    loop {
+        watch_pass(&watcher, options, || run_sync(engine, source, dest, options))?;
+    }
+}
+
+/// Wait for the next batch of changes and run `sync` once for it
+///
+/// # Errors
+///
+/// Returns an error if the watcher fails or the user aborts; other sync
+/// failures are only reported.
+fn watch_pass(
+    watcher: &SyncWatcher,
+    options: &SyncOptions,
+    sync: impl FnOnce() -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let changed = watcher.next_batch()?;
+    if options.verbose {
+        for path in &changed {
+            println!("Changed: {}", path.display());
+        }
+    }
+    match sync() {
+        Err(e) if e.is::<UserAborted>() => Err(e),
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Resolve the global Claude directory (~/.claude)
+///
+/// # Errors
+///
+/// Returns an error suggesting `--global-path` if the home directory cannot
+/// be determined.
+pub fn global_path() -> anyhow::Result<PathBuf> {
+    let home = home_dir(
+        std::env::var_os("HOME"),
+        std::env::var_os("USERPROFILE"),
+        dirs::home_dir,
+    )?;
+    Ok(home.join(".claude"))
+}
+
+/// Pick the home directory: `home` (`HOME`), then `profile` (`USERPROFILE`),
+/// then the platform's own `lookup` (e.g. the user database)
+///
+/// Empty variables count as unset.
+fn home_dir(
+    home: Option<OsString>,
+    profile: Option<OsString>,
+    lookup: impl FnOnce() -> Option<PathBuf>,
+) -> anyhow::Result<PathBuf> {
+    home.into_iter()
+        .chain(profile)
+        .find(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(lookup)
+        .context(
+            "Cannot find the home directory (HOME and USERPROFILE are unset); \
+             pass --global-path to choose the global Claude directory",
+        )
+}
+
+/// Resolve the directory holding the undo journal (<global>/.ccsync/undo)
+///
+/// # Errors
+///
+/// Returns an error if the global directory cannot be determined.
+pub fn journal_path(options: &SyncOptions) -> anyhow::Result<PathBuf> {
+    Ok(options.global_path()?.join(".ccsync").join("undo"))
+}
+
+/// Resolve the project Claude directory (./.claude)
+///
+/// # Errors
+///
+/// Returns an error if the current directory cannot be determined.
+pub fn local_path() -> anyhow::Result<PathBuf> {
+    let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+    Ok(current_dir.join(".claude"))
+}
+
+/// Refuse a destination outside the home and project directories
+///
+/// `--allow-outside-home` lifts the check, and dry runs, which write
+/// nothing, skip it. Symlinks in the existing part of `dest` are resolved
+/// first, so a link cannot smuggle writes elsewhere.
+///
+/// # Errors
+///
+/// Returns an error if `dest` is not inside the home directory or the
+/// current directory.
+pub fn check_destination(dest: &Path, config: &Config, options: &SyncOptions) -> anyhow::Result<()> {
+    if options.allow_outside_home || config.dry_run == Some(true) {
+        return Ok(());
+    }
+
+    let home = home_dir(
+        std::env::var_os("HOME"),
+        std::env::var_os("USERPROFILE"),
+        dirs::home_dir,
+    )
+    .ok();
+    let roots: Vec<PathBuf> = home
+        .into_iter()
+        .chain(std::env::current_dir().ok())
+        .filter_map(|root| root.canonicalize().ok())
+        .collect();
+
+    let inside = resolve_existing(dest)?.is_some_and(|dest| roots.iter().any(|root| dest.starts_with(root)));
+    if !inside {
+        anyhow::bail!(
+            "Refusing to write to {}: it is outside the home and project directories \
+             (pass --allow-outside-home to allow it)",
+            dest.display()
+        );
+    }
+    Ok(())
+}
+
+/// Make `path` absolute, resolving symlinks in its longest existing prefix
+///
+/// Returns `None` if the part that does not exist yet contains `..`, since
+/// where that leads cannot be known.
+fn resolve_existing(path: &Path) -> anyhow::Result<Option<PathBuf>> {
+    let absolute = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    for ancestor in absolute.ancestors() {
+        let Ok(canonical) = ancestor.canonicalize() else {
+            continue;
+        };
+        let rest = absolute.strip_prefix(ancestor).unwrap_or_else(|_| Path::new(""));
+        if rest.components().any(|c| c == Component::ParentDir) {
+            return Ok(None);
+        }
+        return Ok(Some(canonical.join(rest)));
+    }
+    Ok(None)
+}
+
+/// Merge CLI flags into a loaded config (CLI takes precedence)
+pub fn merge_cli_flags(
+    config: &mut Config,
+    types: &[ConfigType],
+    conflict: &ConflictMode,
+    patterns: &PatternArgs,
+    options: &SyncOptions,
+) {
+    if options.show_diff {
+        config.show_diff = Some(true);
+    }
+    if options.hardlink_identical {
+        config.hardlink_identical = Some(true);
+    }
+    if options.preserve_ownership {
+        config.preserve_ownership = Some(true);
+    }
+    if options.prune {
+        config.prune = Some(true);
+    }
+    if options.semantic_frontmatter {
+        config.semantic_frontmatter = Some(true);
+    }
+    if options.dereference {
+        config.follow_symlinks = Some(true);
+        config.preserve_symlinks = Some(false);
+    }
+
+    // Set conflict strategy (override config)
+    config.conflict_strategy = Some(convert_conflict_mode(conflict));
+
+    apply_patterns(config, types, patterns);
+    config.path_match.clone_from(&patterns.path_match);
+
+    if patterns.max_file_size.is_some() {
+        config.max_file_size = patterns.max_file_size;
+    }
+}
+
+/// Add `--type` filters to the config's include patterns
+pub fn apply_types(config: &mut Config, types: &[ConfigType]) {
+    // Handle type filters - ADD to config patterns (additive, not replace)
+    if !types.is_empty() {
+        let cli_patterns = build_type_patterns(types);
+        config.include.extend(cli_patterns);
+    }
+}
+
+/// Add `--type` filters and ad-hoc `--include`/`--exclude` patterns to the config
+///
+/// Ad-hoc includes are appended after all config file patterns, while
+/// excludes are checked last so no include pattern can override them.
+pub fn apply_patterns(config: &mut Config, types: &[ConfigType], patterns: &PatternArgs) {
+    apply_types(config, types);
+    config.include.extend(patterns.include.iter().cloned());
+    config.exclude.extend(patterns.exclude.iter().cloned());
+}
+
+/// Restrict the sync to entries modified within `since` of now
+pub fn apply_since(config: &mut Config, since: Option<Duration>) {
+    if let Some(window) = since {
+        config.modified_since = Some(
+            SystemTime::now()
+                .checked_sub(window)
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        );
+    }
+}
+
+const fn convert_conflict_mode(mode: &ConflictMode) -> ConflictStrategy {
+    match mode {
+        ConflictMode::Fail => ConflictStrategy::Fail,
+        ConflictMode::Overwrite => ConflictStrategy::Overwrite,
+        ConflictMode::Skip => ConflictStrategy::Skip,
+        ConflictMode::Newer => ConflictStrategy::Newer,
+        ConflictMode::KeepBoth => ConflictStrategy::KeepBoth,
+    }
+}
+
+fn build_type_patterns(types: &[ConfigType]) -> Vec<String> {
+    let mut patterns = Vec::new();
+
+    for config_type in types {
+        match config_type {
+            ConfigType::Agents => patterns.push("agents/**".to_string()),
+            ConfigType::Skills => patterns.push("skills/**".to_string()),
+            ConfigType::Commands => patterns.push("commands/**".to_string()),
+            ConfigType::Hooks => patterns.push("hooks/**".to_string()),
+            ConfigType::All => {
+                patterns.push("**".to_string());
+                break;
+            }
+        }
+    }
+
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_existing_rejects_parent_dirs_in_missing_part() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+
+        let resolved = resolve_existing(&dir.path().join("new/.claude")).unwrap();
+        assert_eq!(resolved, Some(root.join("new/.claude")));
+
+        assert_eq!(resolve_existing(&dir.path().join("new/../../etc")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_burst_of_changes_runs_one_failing_sync_and_keeps_watching() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agents = dir.path().join("agents");
+        std::fs::create_dir(&agents).unwrap();
+        let debounce = Duration::from_millis(200);
+        let watcher = SyncWatcher::new(dir.path(), debounce).unwrap();
+
+        for version in ["v1", "v2", "v3"] {
+            std::fs::write(agents.join("agent.md"), version).unwrap();
+        }
+
+        let options = SyncOptions::new(false, false, false, true, None, false, None);
+        let mut runs = 0;
+        let pass = watch_pass(&watcher, &options, || {
+            runs += 1;
+            anyhow::bail!("sync failed")
+        });
+
+        assert!(pass.is_ok());
+        assert_eq!(runs, 1);
+        assert!(watcher.next_batch_timeout(debounce * 4).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unresolved_conflicts_keep_watching() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agents = dir.path().join("agents");
+        std::fs::create_dir(&agents).unwrap();
+        let watcher = SyncWatcher::new(dir.path(), Duration::from_millis(50)).unwrap();
+        std::fs::write(agents.join("agent.md"), "v1").unwrap();
+
+        let options = SyncOptions::new(false, true, false, true, None, false, None)
+            .with_fail_on_conflict(true);
+        let result = SyncResult {
+            conflicts: 1,
+            ..SyncResult::default()
+        };
+        let pass = watch_pass(&watcher, &options, || {
+            report(&result, OutputFormat::Text, &options)
+        });
+
+        assert!(pass.is_ok());
+    }
+
+    #[test]
+    fn test_type_patterns_extend_config_includes() {
+        let mut config = Config {
+            include: vec!["commands/keep.md".to_string()],
+            ..Config::default()
+        };
+
+        apply_types(&mut config, &[ConfigType::Agents]);
+
+        assert_eq!(config.include, ["commands/keep.md", "agents/**"]);
+    }
+
+    #[test]
+    fn test_preflight_asks_only_above_limits() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let create = |name: &str| SyncAction::Create {
+            source: tmp.path().join(name),
+            dest: tmp.path().join("dest").join(name),
+        };
+        let files: Vec<_> = (0..=PREFLIGHT_MAX_FILES)
+            .map(|i| {
+                let name = format!("{i}.md");
+                std::fs::write(tmp.path().join(&name), "x").unwrap();
+                create(&name)
+            })
+            .collect();
+        let huge = std::fs::File::create(tmp.path().join("huge.bin")).unwrap();
+        huge.set_len(PREFLIGHT_MAX_BYTES + 1).unwrap();
+
+        let options = SyncOptions::new(false, false, false, false, None, false, None);
+        let asked = |plan: &[SyncAction], options: &SyncOptions| {
+            let mut asked = None;
+            let proceed = preflight(plan, options, |size| {
+                asked = Some(size);
+                Ok(false)
+            })
+            .unwrap();
+            (proceed, asked)
+        };
+
+        // Small plans go ahead without asking
+        assert_eq!(asked(&files[..3], &options), (true, None));
+
+        // Too many files, or too many bytes, need an answer
+        let (proceed, size) = asked(&files, &options);
+        assert!(!proceed);
+        assert_eq!(size.unwrap().files, PREFLIGHT_MAX_FILES + 1);
+        let (proceed, size) = asked(&[create("huge.bin")], &options);
+        assert!(!proceed);
+        assert_eq!(size.unwrap().bytes, PREFLIGHT_MAX_BYTES + 1);
+
+        // --yes-all never asks
+        let options = SyncOptions::new(false, false, false, true, None, false, None);
+        assert_eq!(asked(&files, &options), (true, None));
+    }
+
+    #[test]
+    fn test_home_dir_falls_back_and_explains_failure() {
+        let lookup = || Some(PathBuf::from("/from/lookup"));
+        let home = |dir: &str| Some(OsString::from(dir));
+
+        assert_eq!(
+            home_dir(home("/home/me"), home("C:\\Users\\me"), lookup).unwrap(),
+            PathBuf::from("/home/me")
+        );
+        assert_eq!(
+            home_dir(home(""), home("C:\\Users\\me"), lookup).unwrap(),
+            PathBuf::from("C:\\Users\\me")
+        );
+        assert_eq!(
+            home_dir(None, None, lookup).unwrap(),
+            PathBuf::from("/from/lookup")
+        );
+
+        let err = home_dir(None, None, || None).unwrap_err().to_string();
+        assert!(err.contains("HOME and USERPROFILE are unset"));
+        assert!(err.contains("--global-path"));
+    }
+}
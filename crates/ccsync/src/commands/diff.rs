@@ -1,16 +1,146 @@
-use crate::cli::ConfigType;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use ccsync_core::comparison::{BinaryDetector, DiffGenerator, DiffStat, DirectoryComparator};
+use ccsync_core::config::SyncDirection;
+use ccsync_core::sync::{SyncAction, SyncEngine};
+
+use crate::cli::{ConfigType, DiffFormat};
+use crate::commands::{SyncOptions, common};
+
+/// Files ranked by size in `diff --stat` when more than this many changed
+const LARGEST_CHANGES: usize = 5;
 
 pub struct Diff;
 
 impl Diff {
-    #[allow(clippy::unnecessary_wraps)]
-    pub fn execute(types: &[ConfigType], verbose: bool) -> anyhow::Result<()> {
-        if verbose {
+    pub fn execute(
+        types: &[ConfigType],
+        stat: bool,
+        only_conflicts: bool,
+        format: DiffFormat,
+        options: &SyncOptions,
+    ) -> anyhow::Result<()> {
+        if options.verbose {
             println!("Executing diff command");
             println!("Types: {types:?}");
+            println!("Stat: {stat}");
+            println!("Only conflicts: {only_conflicts}");
+            println!("Format: {format:?}");
+        }
+
+        let json = format == DiffFormat::Json;
+        let global_path = options.global_path()?;
+        let local_path = options.local_path()?;
+
+        let mut config = options.load_config()?;
+        common::apply_types(&mut config, types);
+
+        // Differences are shown as what `to-local` would change
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal)
+            .context("Failed to initialize sync engine")?;
+        let plan = engine
+            .plan(&global_path, &local_path)
+            .context("Failed to compare configurations")?;
+
+        // Conflicts are paths on both sides with differing content
+        let changed: Vec<(&Path, &Path)> = plan
+            .iter()
+            .filter_map(|action| match action {
+                SyncAction::Create { source, dest }
+                | SyncAction::CreateDirectory { source, dest }
+                    if !only_conflicts =>
+                {
+                    Some((source.as_path(), dest.as_path()))
+                }
+                SyncAction::Conflict { source, dest, .. }
+                | SyncAction::DirectoryConflict { source, dest, .. } => {
+                    Some((source.as_path(), dest.as_path()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        if json {
+            let mut diffs = Vec::new();
+            for (source, dest) in changed {
+                let files = DiffGenerator::file_diffs(source, dest)
+                    .with_context(|| format!("Failed to diff {}", source.display()))?;
+                diffs.extend(files.into_iter().map(|mut file| {
+                    if let Ok(rel_path) = file.path.strip_prefix(&local_path) {
+                        file.path = rel_path.to_path_buf();
+                    }
+                    file
+                }));
+            }
+            println!("{}", DiffGenerator::format_json(&diffs)?);
+            return Ok(());
+        }
+
+        if changed.is_empty() {
+            let message = if only_conflicts {
+                "No conflicts"
+            } else {
+                "No differences"
+            };
+            println!("{message}");
+            return Ok(());
+        }
+
+        if stat {
+            let relative = |path: &Path| path.strip_prefix(&local_path).unwrap_or(path).to_path_buf();
+            let mut entries = Vec::new();
+            let mut files = Vec::new();
+            for (source, dest) in changed {
+                let file_stats = DiffGenerator::file_stats(source, dest)
+                    .with_context(|| format!("Failed to diff {}", source.display()))?;
+                let mut stat = DiffStat::default();
+                for (path, file_stat) in file_stats {
+                    stat += file_stat;
+                    files.push((relative(&path), file_stat));
+                }
+                entries.push((relative(dest), stat));
+            }
+            print!("{}", DiffGenerator::format_stat(&entries));
+            if files.len() > LARGEST_CHANGES {
+                let largest = DiffGenerator::largest_changes(&files, LARGEST_CHANGES);
+                print!("{}", DiffGenerator::format_largest(&largest));
+            }
+        } else {
+            for (source, dest) in changed {
+                println!("{}", Self::full_diff(source, dest)?);
+            }
         }
 
-        println!("diff: Not yet implemented");
         Ok(())
     }
+
+    /// Full diff of one changed file, or a per-file summary for a directory
+    ///
+    /// A file missing from `dest` is diffed against empty content.
+    fn full_diff(source: &Path, dest: &Path) -> anyhow::Result<String> {
+        if source.is_dir() {
+            let comparison = DirectoryComparator::compare(source, dest)
+                .with_context(|| format!("Failed to compare {}", source.display()))?;
+            let name = dest.file_name().unwrap_or_default().to_string_lossy();
+            return DiffGenerator::generate_directory_summary(&comparison, source, dest, &name);
+        }
+
+        let dest_exists = dest.exists();
+        if BinaryDetector::is_binary(source)? || (dest_exists && BinaryDetector::is_binary(dest)?) {
+            return Ok(format!(
+                "Binary files {} and {} differ",
+                dest.display(),
+                source.display()
+            ));
+        }
+        if !dest_exists {
+            let bytes = fs::read(source)
+                .with_context(|| format!("Failed to read source file: {}", source.display()))?;
+            let content = String::from_utf8_lossy(&bytes);
+            return Ok(DiffGenerator::generate_from_content(&content, "", source, dest));
+        }
+        DiffGenerator::generate(source, dest)
+    }
 }
@@ -0,0 +1,132 @@
+use ccsync_core::config::{Config, ConfigDiscovery, ConfigFiles, ConfigMerger, ConfigValidator};
+
+use crate::commands::{SyncOptions, common};
+
+pub struct Doctor;
+
+/// One line of the checklist: the detail on success, or the problem found
+struct Check {
+    label: &'static str,
+    outcome: Result<String, String>,
+}
+
+impl Check {
+    const fn new(label: &'static str, outcome: Result<String, String>) -> Self {
+        Self { label, outcome }
+    }
+}
+
+impl Doctor {
+    /// Check the environment and configuration, printing a pass/fail checklist
+    ///
+    /// Fails if any check does, so the process exits nonzero.
+    pub fn execute(options: &SyncOptions) -> anyhow::Result<()> {
+        if options.verbose {
+            println!("Executing doctor command");
+        }
+
+        let checks = Self::run_checks(options);
+        for check in &checks {
+            match &check.outcome {
+                Ok(detail) => println!("✓ {}: {detail}", check.label),
+                Err(problem) => println!("✗ {}: {problem}", check.label),
+            }
+        }
+
+        let failed = checks.iter().filter(|check| check.outcome.is_err()).count();
+        if failed > 0 {
+            anyhow::bail!("{failed} check(s) failed");
+        }
+        println!("\nAll checks passed");
+        Ok(())
+    }
+
+    fn run_checks(options: &SyncOptions) -> Vec<Check> {
+        let mut checks = vec![Check::new(
+            "Home directory",
+            common::global_path()
+                .map(|global| global.parent().unwrap_or(&global).display().to_string())
+                .map_err(|e| e.to_string()),
+        )];
+
+        for (label, path) in [
+            ("Global directory", options.global_path()),
+            ("Local directory", options.local_path()),
+        ] {
+            let outcome = match path {
+                Ok(path) if path.is_dir() => Ok(path.display().to_string()),
+                Ok(path) => Err(format!("{} not found", path.display())),
+                Err(e) => Err(e.to_string()),
+            };
+            checks.push(Check::new(label, outcome));
+        }
+
+        if options.no_config {
+            checks.push(Check::new(
+                "Config files",
+                Ok("skipped (--no-config)".to_string()),
+            ));
+            return checks;
+        }
+
+        let files = match ConfigDiscovery::discover(options.config_path) {
+            Ok(files) => files,
+            Err(e) => {
+                checks.push(Check::new("Config files", Err(format!("{e:#}"))));
+                return checks;
+            }
+        };
+        let found: Vec<_> = [&files.global, &files.project, &files.local, &files.cli]
+            .into_iter()
+            .flatten()
+            .map(|path| path.display().to_string())
+            .collect();
+        checks.push(Check::new(
+            "Config files",
+            Ok(if found.is_empty() {
+                "none found, using defaults".to_string()
+            } else {
+                found.join(", ")
+            }),
+        ));
+
+        let config = match Self::merge(&files, options.profile) {
+            Ok(config) => config,
+            Err(e) => {
+                checks.push(Check::new("Config parses", Err(format!("{e:#}"))));
+                return checks;
+            }
+        };
+        checks.push(Check::new("Config parses", Ok("ok".to_string())));
+
+        let symlinks = if config.follow_symlinks == Some(true)
+            && config.preserve_symlinks == Some(true)
+        {
+            Err("follow_symlinks and preserve_symlinks are both enabled".to_string())
+        } else {
+            Ok("consistent".to_string())
+        };
+        checks.push(Check::new("Symlink settings", symlinks));
+
+        checks.push(Check::new(
+            "Config is valid",
+            ConfigValidator::validate(&config)
+                .map(|()| "ok".to_string())
+                .map_err(|e| format!("{e:#}")),
+        ));
+
+        checks
+    }
+
+    /// Merge the discovered files and overlay the selected profile
+    fn merge(
+        files: &ConfigFiles,
+        profile: Option<&str>,
+    ) -> anyhow::Result<Config> {
+        let mut config = ConfigMerger::merge(files)?;
+        if let Some(name) = profile {
+            ConfigMerger::apply_profile(&mut config, name)?;
+        }
+        Ok(config)
+    }
+}
@@ -1,13 +1,25 @@
+pub mod apply;
+pub mod check_pattern;
+pub mod clean;
 pub mod common;
 pub mod config;
 pub mod diff;
+pub mod doctor;
 pub mod status;
+pub mod sync;
 pub mod to_global;
 pub mod to_local;
+pub mod undo;
 
+pub use apply::Apply;
+pub use check_pattern::CheckPattern;
+pub use clean::Clean;
 pub use common::SyncOptions;
 pub use config::Config;
 pub use diff::Diff;
+pub use doctor::Doctor;
 pub use status::Status;
+pub use sync::Sync;
 pub use to_global::ToGlobal;
 pub use to_local::ToLocal;
+pub use undo::Undo;
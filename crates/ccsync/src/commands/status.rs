@@ -1,16 +1,199 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use ccsync_core::comparison::{ConflictStrategy, DirectoryComparator};
+use ccsync_core::config::SyncDirection;
+use ccsync_core::sync::{SyncAction, SyncEngine};
+
 use crate::cli::ConfigType;
+use crate::commands::{SyncOptions, common};
+
+/// Exit code when the two sides differ
+pub const DIFFERENCES_EXIT_CODE: i32 = 1;
+
+/// Exit code when the status could not be determined
+pub const ERROR_EXIT_CODE: i32 = 2;
 
 pub struct Status;
 
 impl Status {
-    #[allow(clippy::unnecessary_wraps)]
-    pub fn execute(types: &[ConfigType], verbose: bool) -> anyhow::Result<()> {
-        if verbose {
+    /// Compare the global and local directories and print what differs
+    ///
+    /// With `orphans`, also lists the local files `to-local` has no source
+    /// for. Exits the process with [`DIFFERENCES_EXIT_CODE`] when anything
+    /// differs; returns normally (exit 0) when both sides are in sync. `main`
+    /// maps an error to [`ERROR_EXIT_CODE`].
+    pub fn execute(
+        types: &[ConfigType],
+        newer: bool,
+        orphans: bool,
+        options: &SyncOptions,
+    ) -> anyhow::Result<()> {
+        if options.verbose {
             println!("Executing status command");
             println!("Types: {types:?}");
+            println!("Newer: {newer}");
+            println!("Orphans: {orphans}");
+        }
+
+        let global_path = options.global_path()?;
+        let local_path = options.local_path()?;
+
+        let mut config = options.load_config()?;
+        common::apply_types(&mut config, types);
+        config.conflict_strategy = Some(ConflictStrategy::Newer);
+        // A baseline turns one-sided edits into skips, which would hide them here
+        config.track_baseline = Some(false);
+
+        // Conflicts are judged as `to-local` would see them; the reverse plan
+        // only contributes entries missing from the global side
+        let to_local = SyncEngine::new(config.clone(), SyncDirection::ToLocal)
+            .context("Failed to initialize sync engine")?
+            .plan(&global_path, &local_path)
+            .context("Failed to compare configurations")?;
+        let to_global = SyncEngine::new(config, SyncDirection::ToGlobal)
+            .context("Failed to initialize sync engine")?
+            .plan(&local_path, &global_path)
+            .context("Failed to compare configurations")?;
+
+        let relative = |path: &Path, root: &Path| {
+            path.strip_prefix(root).unwrap_or(path).to_path_buf()
+        };
+        let mut global_only = Vec::new();
+        let mut winners: Vec<(PathBuf, bool)> = Vec::new();
+        for action in &to_local {
+            match action {
+                SyncAction::Create { dest, .. } | SyncAction::CreateDirectory { dest, .. } => {
+                    global_only.push(relative(dest, &local_path));
+                }
+                SyncAction::Conflict {
+                    dest, source_newer, ..
+                }
+                | SyncAction::DirectoryConflict {
+                    dest, source_newer, ..
+                } => winners.push((relative(dest, &local_path), *source_newer)),
+                SyncAction::Skip { .. } => {}
+            }
+        }
+        let local_only: Vec<PathBuf> = to_global
+            .iter()
+            .filter_map(|action| match action {
+                SyncAction::Create { dest, .. } | SyncAction::CreateDirectory { dest, .. } => {
+                    Some(relative(dest, &global_path))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let differences = global_only.len() + local_only.len() + winners.len();
+        if differences == 0 {
+            if !options.quiet {
+                println!("In sync");
+            }
+            return Ok(());
+        }
+
+        if !options.quiet {
+            for (label, paths) in [("global only", &global_only), ("local only", &local_only)] {
+                for path in paths {
+                    println!("  {label:<11}  {}", path.display());
+                }
+            }
+            for (path, _) in &winners {
+                println!("  {:<11}  {}", "different", path.display());
+            }
+            println!("\n{differences} difference(s)");
+
+            if newer && !winners.is_empty() {
+                print!("\n{}", format_newer_table(&winners));
+            }
+
+            if orphans {
+                let orphaned = find_orphans(&to_local, &local_only, &local_path)?;
+                if !orphaned.is_empty() {
+                    println!("\nOrphaned in local (not in global):");
+                    for path in &orphaned {
+                        println!("  {}", path.display());
+                    }
+                }
+            }
         }
 
-        println!("status: Not yet implemented");
-        Ok(())
+        std::process::exit(DIFFERENCES_EXIT_CODE);
+    }
+}
+
+/// Local files with no global counterpart, relative to the local root
+///
+/// Covers local-only entries and, inside directories present on both sides,
+/// files only the local copy has (including the old path of a renamed file).
+fn find_orphans(
+    to_local: &[SyncAction],
+    local_only: &[PathBuf],
+    local_path: &Path,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut orphaned = local_only.to_vec();
+    for action in to_local {
+        let SyncAction::DirectoryConflict { source, dest, .. } = action else {
+            continue;
+        };
+        let dir = dest.strip_prefix(local_path).unwrap_or(dest);
+        let comparison = DirectoryComparator::compare(source, dest)
+            .with_context(|| format!("Failed to compare {}", dir.display()))?;
+        orphaned.extend(
+            comparison
+                .removed
+                .iter()
+                .chain(comparison.renamed.iter().map(|rename| &rename.from))
+                .map(|path| dir.join(path)),
+        );
+    }
+    orphaned.sort();
+    Ok(orphaned)
+}
+
+/// Two-column table of conflicting paths and the side `--conflict newer` keeps
+///
+/// Ties go to the destination, since `newer` only copies a strictly newer source.
+fn format_newer_table(winners: &[(PathBuf, bool)]) -> String {
+    const PATH_HEADER: &str = "Path";
+
+    let width = winners
+        .iter()
+        .map(|(path, _)| path.display().to_string().len())
+        .chain([PATH_HEADER.len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!("{PATH_HEADER:<width$}  Newer\n");
+    for (path, source_newer) in winners {
+        let side = if *source_newer {
+            "source (global)"
+        } else {
+            "dest (local)"
+        };
+        let _ = writeln!(table, "{:<width$}  {side}", path.display().to_string());
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_newer_table_aligns_columns() {
+        let winners = vec![
+            (PathBuf::from("agents/a.md"), true),
+            (PathBuf::from("skills/demo"), false),
+        ];
+
+        assert_eq!(
+            format_newer_table(&winners),
+            "Path         Newer\n\
+             agents/a.md  source (global)\n\
+             skills/demo  dest (local)\n"
+        );
     }
 }
@@ -0,0 +1,97 @@
+use anyhow::Context;
+use ccsync_core::config::SyncDirection;
+use ccsync_core::sync::{Hook, SyncEngine};
+
+use crate::cli::{ConfigType, ConflictMode, PatternArgs};
+use crate::commands::{SyncOptions, common};
+use crate::interactive::{InteractivePrompter, UserAborted};
+
+pub struct Sync;
+
+impl Sync {
+    pub fn execute(
+        types: &[ConfigType],
+        conflict: &ConflictMode,
+        patterns: &PatternArgs,
+        options: &SyncOptions,
+    ) -> anyhow::Result<()> {
+        if options.verbose {
+            println!("Executing sync command");
+            println!("Types: {types:?}");
+            println!("Conflict mode: {conflict:?}");
+            println!("Patterns: {patterns:?}");
+            println!("Dry run: {}", options.dry_run);
+        }
+
+        if options.plan_out.is_some() {
+            anyhow::bail!("--plan-out is only supported by to-local and to-global");
+        }
+        if options.detect_changes {
+            anyhow::bail!("--detect-changes is only supported by to-local and to-global");
+        }
+        if options.prune {
+            anyhow::bail!("--prune is only supported by to-local and to-global");
+        }
+        if options.verify {
+            anyhow::bail!("--verify is only supported by to-local, to-global and apply");
+        }
+
+        // Determine paths
+        let global_path = options.global_path()?;
+        let local_path = options.local_path()?;
+
+        if options.verbose {
+            println!("Global path: {}", global_path.display());
+            println!("Local path: {}", local_path.display());
+        }
+
+        // Load configuration from files
+        let mut config = options.load_config()?;
+
+        // Merge CLI flags into loaded config (CLI takes precedence)
+        common::merge_cli_flags(&mut config, types, conflict, patterns, options);
+        common::check_destination(&global_path, &config, options)?;
+        common::check_destination(&local_path, &config, options)?;
+
+        // Initialize sync engine (direction is decided per path)
+        let engine = SyncEngine::new(config, SyncDirection::ToLocal)
+            .context("Failed to initialize sync engine")?
+            .with_journal(common::journal_path(options)?)
+            .with_limit(options.limit)
+            .with_fail_fast(options.fail_fast);
+        let engine = common::with_decision_trace(engine, options)?;
+
+        common::run_hook(&engine, Hook::PreSync, options)?;
+
+        // Execute sync with optional interactive approval
+        let result = if options.prompts(engine.dry_run()) {
+            // Interactive mode: prompt for each action (requires a terminal unless forced)
+            if options.interactive.is_none() {
+                InteractivePrompter::ensure_terminal()?;
+            }
+            let mut prompter =
+                InteractivePrompter::new().with_prompt_timeout(options.prompt_timeout);
+            match engine.sync_bidirectional(
+                &local_path,
+                &global_path,
+                Some(Box::new(move |action| prompter.prompt(action))),
+            ) {
+                Ok(result) => result,
+                Err(e) if e.is::<UserAborted>() => {
+                    eprintln!("\nSync cancelled by user.");
+                    std::process::exit(0); // Clean exit, not an error
+                }
+                Err(e) => return Err(e).context("Sync operation failed"),
+            }
+        } else {
+            // Non-interactive: apply the configured conflict strategy or just preview
+            engine
+                .sync_bidirectional(&local_path, &global_path, None)
+                .context("Sync operation failed")?
+        };
+
+        // Display results
+        common::report(&result, engine.output_format(), options)?;
+        common::run_hook(&engine, Hook::PostSync, options)
+    }
+}
@@ -0,0 +1,45 @@
+use anyhow::Context;
+use ccsync_core::sync::SyncJournal;
+
+use crate::commands::{SyncOptions, common};
+
+pub struct Undo;
+
+impl Undo {
+    pub fn execute(options: &SyncOptions) -> anyhow::Result<()> {
+        let journal_path = common::journal_path(options)?;
+
+        if options.verbose {
+            println!("Executing undo command");
+            println!("Journal path: {}", journal_path.display());
+            println!("Dry run: {}", options.dry_run);
+        }
+
+        let report = SyncJournal::undo(&journal_path, options.dry_run)
+            .context("Failed to undo the last sync")?;
+
+        if !options.summary_only {
+            for skipped in &report.skipped {
+                eprintln!("⚠️  Skipped {skipped}");
+            }
+        }
+
+        if options.quiet {
+            return Ok(());
+        }
+
+        if options.dry_run {
+            println!(
+                "[DRY RUN] Would restore {} file(s) and remove {} file(s)",
+                report.restored, report.removed
+            );
+        } else {
+            println!(
+                "✓ Undo complete: {} file(s) restored, {} file(s) removed",
+                report.restored, report.removed
+            );
+        }
+
+        Ok(())
+    }
+}
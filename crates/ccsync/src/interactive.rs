@@ -1,10 +1,33 @@
 //! Interactive prompting for sync operations
 
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
 use anyhow::{bail, Context, Result};
+use ccsync_core::color::{self, GREEN};
 use ccsync_core::comparison::{ConflictStrategy, DiffGenerator, DirectoryComparator, FileComparator};
-use ccsync_core::sync::SyncAction;
+use ccsync_core::sync::{PlanSize, SyncAction};
 use dialoguer::console::Term;
 
+/// Error returned when the user quits an interactive sync
+///
+/// Commands downcast to this to tell a clean abort apart from a real failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserAborted;
+
+impl std::fmt::Display for UserAborted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("User aborted sync operation")
+    }
+}
+
+impl std::error::Error for UserAborted {}
+
 /// User's choice for a sync action
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UserChoice {
@@ -18,8 +41,14 @@ pub enum UserChoice {
     None,
     /// Show diff and re-prompt
     Diff,
+    /// Show diffs for every pending action and re-prompt
+    DiffAll,
     /// Show content diff (for directories) and re-prompt
     ContentDiff,
+    /// Open the source file in $EDITOR and re-prompt
+    Edit,
+    /// Open both sides in $DIFFTOOL and re-prompt
+    View,
     /// Quit immediately
     Quit,
 }
@@ -35,9 +64,165 @@ enum SessionDecision {
     SkipAll,
 }
 
+/// Counts of planned actions shown before per-file prompting
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct PlanPreview {
+    /// New files and directories
+    creates: usize,
+    /// Conflicts the strategy resolves by copying the source
+    updates: usize,
+    /// Conflicts that need a decision
+    conflicts: usize,
+    /// Entries left untouched
+    skipped: usize,
+}
+
+impl PlanPreview {
+    /// Tally a planned action list
+    fn from_actions(actions: &[SyncAction]) -> Self {
+        let mut preview = Self::default();
+        for action in actions {
+            match action {
+                SyncAction::Create { .. } | SyncAction::CreateDirectory { .. } => {
+                    preview.creates += 1;
+                }
+                SyncAction::Skip { .. } => preview.skipped += 1,
+                SyncAction::Conflict {
+                    strategy,
+                    source_newer,
+                    ..
+                }
+                | SyncAction::DirectoryConflict {
+                    strategy,
+                    source_newer,
+                    ..
+                } => match strategy {
+                    ConflictStrategy::Overwrite => preview.updates += 1,
+                    ConflictStrategy::Newer if *source_newer => preview.updates += 1,
+                    ConflictStrategy::Newer => preview.skipped += 1,
+                    ConflictStrategy::Fail
+                    | ConflictStrategy::Skip
+                    | ConflictStrategy::KeepBoth => preview.conflicts += 1,
+                },
+            }
+        }
+        preview
+    }
+
+    /// Number of actions that may change the destination
+    const fn changes(&self) -> usize {
+        self.creates + self.updates + self.conflicts
+    }
+}
+
+/// Reads a single key press
+type KeySource = Box<dyn FnMut() -> std::io::Result<char> + Send>;
+
+/// Keys read on demand by one background thread
+///
+/// The thread only reads after a prompt asks for a key, so nothing competes
+/// for the terminal while no prompt is waiting (e.g. while `$EDITOR` runs).
+/// A prompt that times out leaves its request open, and the key pressed
+/// next answers the following prompt.
+struct KeyChannel {
+    requests: Sender<()>,
+    keys: Receiver<std::io::Result<char>>,
+    /// A key was requested and has not been received yet
+    requested: bool,
+}
+
+impl KeyChannel {
+    fn spawn(mut source: KeySource) -> Self {
+        let (requests, request_rx) = mpsc::channel::<()>();
+        let (key_tx, keys) = mpsc::channel();
+        std::thread::spawn(move || {
+            for () in request_rx {
+                let key = source();
+                let failed = key.is_err();
+                if key_tx.send(key).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            keys,
+            requested: false,
+        }
+    }
+
+    /// Wait for the next key, or `None` if `timeout` passes first
+    fn read(&mut self, timeout: Option<Duration>) -> Result<Option<char>> {
+        if !self.requested {
+            if self.requests.send(()).is_err() {
+                bail!("Input source is closed");
+            }
+            self.requested = true;
+        }
+
+        let key = match timeout {
+            Some(timeout) => self.keys.recv_timeout(timeout),
+            None => self.keys.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match key {
+            Ok(key) => {
+                self.requested = false;
+                key.map(Some).context("Failed to read user input")
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => bail!("Input source is closed"),
+        }
+    }
+}
+
+/// The terminal's key channel, shared by every prompt in the process
+fn terminal_keys() -> &'static Mutex<KeyChannel> {
+    static KEYS: OnceLock<Mutex<KeyChannel>> = OnceLock::new();
+    KEYS.get_or_init(|| Mutex::new(KeyChannel::spawn(Box::new(|| Term::stderr().read_char()))))
+}
+
+/// Key input for prompts, optionally giving up after a timeout
+struct KeyReader {
+    /// Channel of an injected source; `None` reads the shared terminal channel
+    channel: Option<KeyChannel>,
+    timeout: Option<Duration>,
+}
+
+impl KeyReader {
+    /// Read keys from the terminal
+    const fn terminal(timeout: Option<Duration>) -> Self {
+        Self {
+            channel: None,
+            timeout,
+        }
+    }
+
+    #[cfg(test)]
+    fn new(source: KeySource, timeout: Option<Duration>) -> Self {
+        Self {
+            channel: Some(KeyChannel::spawn(source)),
+            timeout,
+        }
+    }
+
+    /// Wait for the next key, or `None` if the timeout passes first
+    fn read(&mut self) -> Result<Option<char>> {
+        match &mut self.channel {
+            Some(channel) => channel.read(self.timeout),
+            None => terminal_keys()
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Input source is closed"))?
+                .read(self.timeout),
+        }
+    }
+}
+
 /// Interactive prompter for sync operations
 pub struct InteractivePrompter {
     session_state: SessionDecision,
+    /// Planned actions that need approval, in the order they are prompted
+    pending: Vec<SyncAction>,
+    keys: KeyReader,
 }
 
 impl InteractivePrompter {
@@ -46,9 +231,100 @@ impl InteractivePrompter {
     pub const fn new() -> Self {
         Self {
             session_state: SessionDecision::AskEach,
+            pending: Vec::new(),
+            keys: KeyReader::terminal(None),
         }
     }
 
+    /// Create a prompter that knows the full plan, enabling the diff-all view
+    ///
+    /// Skips are dropped since they are never prompted for.
+    #[must_use]
+    pub fn with_plan(plan: Vec<SyncAction>) -> Self {
+        Self {
+            session_state: SessionDecision::AskEach,
+            pending: plan
+                .into_iter()
+                .filter(|action| !matches!(action, SyncAction::Skip { .. }))
+                .collect(),
+            keys: KeyReader::terminal(None),
+        }
+    }
+
+    /// Skip an action when its prompt gets no answer within `timeout`
+    #[must_use]
+    pub fn with_prompt_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.keys = KeyReader::terminal(timeout);
+        self
+    }
+
+    /// Ensure stdin is attached to a terminal so prompts can be answered
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if stdin is not a TTY (e.g. piped input or CI).
+    pub fn ensure_terminal() -> Result<()> {
+        if !std::io::stdin().is_terminal() {
+            bail!(
+                "Interactive mode requires a TTY; use --yes-all (with --conflict to choose \
+                 a conflict strategy) or --dry-run when running non-interactively"
+            );
+        }
+        Ok(())
+    }
+
+    /// Show a preview of the planned changes and ask once whether to continue
+    ///
+    /// Returns true without prompting when nothing would change, and false if
+    /// no answer arrives within `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if terminal interaction fails.
+    pub fn confirm_plan(actions: &[SyncAction], timeout: Option<Duration>) -> Result<bool> {
+        let preview = PlanPreview::from_actions(actions);
+        let changes = preview.changes();
+        if changes == 0 {
+            return Ok(true);
+        }
+
+        println!(
+            "\n📋 Sync plan: {} to create, {} to update, {} conflict(s), {} unchanged",
+            preview.creates, preview.updates, preview.conflicts, preview.skipped
+        );
+        print!("Proceed with {changes} change(s)? [y/N] ");
+        std::io::Write::flush(&mut std::io::stdout()).context("Failed to flush stdout")?;
+
+        let Some(key) = KeyReader::terminal(timeout).read()? else {
+            println!("(no answer, defaulted to 'no')");
+            return Ok(false);
+        };
+        println!("{key}");
+
+        Ok(matches!(key, 'y' | 'Y'))
+    }
+
+    /// Warn that a plan is unusually large and ask whether to go on anyway
+    ///
+    /// Returns false if no answer arrives within `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if terminal interaction fails.
+    pub fn confirm_large_plan(size: PlanSize, timeout: Option<Duration>) -> Result<bool> {
+        println!("\n⚠️  This sync would copy {size}. Check that the source and destination are the right ones.");
+        print!("Continue anyway? [y/N] ");
+        std::io::Write::flush(&mut std::io::stdout()).context("Failed to flush stdout")?;
+
+        let Some(key) = KeyReader::terminal(timeout).read()? else {
+            println!("(no answer, defaulted to 'no')");
+            return Ok(false);
+        };
+        println!("{key}");
+
+        Ok(matches!(key, 'y' | 'Y'))
+    }
+
     /// Prompt user for approval of a sync action
     ///
     /// Returns true to proceed with the action, false to skip it.
@@ -74,7 +350,7 @@ impl InteractivePrompter {
 
         // Prompt with options
         loop {
-            let choice = Self::show_prompt(action)?;
+            let choice = self.show_prompt(action)?;
 
             match choice {
                 UserChoice::Yes => return Ok(true),
@@ -91,37 +367,61 @@ impl InteractivePrompter {
                     Self::show_diff(action);
                     // Loop back to re-prompt
                 }
+                UserChoice::DiffAll => {
+                    print!("{}", self.render_pending_diffs(action));
+                    // Loop back to re-prompt
+                }
                 UserChoice::ContentDiff => {
                     Self::show_content_diff(action);
                     // Loop back to re-prompt
                 }
+                UserChoice::View => {
+                    Self::view_in_difftool(action);
+                    // Loop back to re-prompt
+                }
+                UserChoice::Edit => {
+                    if Self::edit_source(action) {
+                        // Show the action again; diffs re-read the edited file
+                        println!("\n{}", Self::describe_action(action));
+                    }
+                }
                 UserChoice::Quit => {
-                    bail!("User aborted sync operation");
+                    return Err(UserAborted.into());
                 }
             }
         }
     }
 
     /// Show the selection prompt
-    fn show_prompt(action: &SyncAction) -> Result<UserChoice> {
-        let term = Term::stderr();
-
+    ///
+    /// With a prompt timeout, an unanswered prompt skips the action.
+    fn show_prompt(&mut self, action: &SyncAction) -> Result<UserChoice> {
         // Check if this is a directory conflict (after showing 'd' diff)
         let has_content_diff = matches!(action, SyncAction::DirectoryConflict { .. });
+        let can_view = Self::conflict_paths(action).is_some();
+        let can_diff_all = !self.pending.is_empty();
+        let keys = self.prompt_keys(action);
 
-        let prompt_text = if has_content_diff {
-            "Proceed? [y/n/a/s/d/c/q] (yes/no/all/skip-all/diff/content-diff/quit): "
-        } else {
-            "Proceed? [y/n/a/s/d/q] (yes/no/all/skip-all/diff/quit): "
-        };
+        let valid_keys = keys
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let key_names = keys
+            .iter()
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join("/");
+        let prompt_text = format!("Proceed? [{valid_keys}] ({key_names}): ");
 
         print!("{prompt_text}");
         std::io::Write::flush(&mut std::io::stdout()).context("Failed to flush stdout")?;
 
         loop {
-            let key = term
-                .read_char()
-                .context("Failed to read user input")?;
+            let Some(key) = self.keys.read()? else {
+                println!("(no answer, defaulted to 'no')");
+                return Ok(UserChoice::No);
+            };
 
             // Echo the character
             println!("{key}");
@@ -132,7 +432,10 @@ impl InteractivePrompter {
                 'a' | 'A' => return Ok(UserChoice::All),
                 's' | 'S' => return Ok(UserChoice::None),
                 'd' | 'D' => return Ok(UserChoice::Diff),
+                'p' | 'P' if can_diff_all => return Ok(UserChoice::DiffAll),
                 'c' | 'C' if has_content_diff => return Ok(UserChoice::ContentDiff),
+                'e' | 'E' => return Ok(UserChoice::Edit),
+                'v' | 'V' if can_view => return Ok(UserChoice::View),
                 'q' | 'Q' => return Ok(UserChoice::Quit),
                 '\n' | '\r' => {
                     // Enter key - default to no
@@ -140,11 +443,6 @@ impl InteractivePrompter {
                     return Ok(UserChoice::No);
                 }
                 _ => {
-                    let valid_keys = if has_content_diff {
-                        "y/n/a/s/d/c/q"
-                    } else {
-                        "y/n/a/s/d/q"
-                    };
                     println!("Invalid key. Press {valid_keys}");
                     print!("{prompt_text}");
                     std::io::Write::flush(&mut std::io::stdout())
@@ -154,6 +452,87 @@ impl InteractivePrompter {
         }
     }
 
+    /// Keys offered for an action, with their long names for the help text
+    fn prompt_keys(&self, action: &SyncAction) -> Vec<(char, &'static str)> {
+        let mut keys = vec![
+            ('y', "yes"),
+            ('n', "no"),
+            ('a', "all"),
+            ('s', "skip-all"),
+            ('d', "diff"),
+        ];
+        if !self.pending.is_empty() {
+            keys.push(('p', "diff-all"));
+        }
+        if matches!(action, SyncAction::DirectoryConflict { .. }) {
+            keys.push(('c', "content-diff"));
+        }
+        if Self::editable_source(action).is_some() {
+            keys.push(('e', "edit"));
+        }
+        if Self::conflict_paths(action).is_some() {
+            keys.push(('v', "view"));
+        }
+        keys.push(('q', "quit"));
+        keys
+    }
+
+    /// Source and destination of a file or directory conflict
+    fn conflict_paths(action: &SyncAction) -> Option<[&Path; 2]> {
+        match action {
+            SyncAction::Conflict { source, dest, .. }
+            | SyncAction::DirectoryConflict { source, dest, .. } => Some([source, dest]),
+            _ => None,
+        }
+    }
+
+    /// Open both sides of a conflict in the configured diff tool
+    ///
+    /// Falls back to the inline diff when `$DIFFTOOL` is not set or the tool
+    /// cannot be launched.
+    fn view_in_difftool(action: &SyncAction) {
+        let Some(paths) = Self::conflict_paths(action) else {
+            Self::show_diff(action);
+            return;
+        };
+
+        let Some(tool) = difftool_command(std::env::var("DIFFTOOL").ok().as_deref()) else {
+            Self::show_diff(action);
+            return;
+        };
+
+        if run_external(&tool, &paths).is_none() {
+            Self::show_diff(action);
+        }
+    }
+
+    /// Source file that can be opened in an editor for this action
+    fn editable_source(action: &SyncAction) -> Option<&Path> {
+        match action {
+            SyncAction::Create { source, .. } | SyncAction::Conflict { source, .. } => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+
+    /// Open the action's source file in the user's editor
+    ///
+    /// Returns true if the editor ran successfully. Directories cannot be
+    /// edited; a message is shown instead.
+    fn edit_source(action: &SyncAction) -> bool {
+        let Some(source) = Self::editable_source(action) else {
+            println!("\nEditing is only available for individual files.");
+            return false;
+        };
+
+        let editor = editor_command(
+            std::env::var("VISUAL").ok().as_deref(),
+            std::env::var("EDITOR").ok().as_deref(),
+        );
+        run_external(&editor, &[source]).is_some_and(|success| success)
+    }
+
     /// Describe the action in user-friendly terms
     fn describe_action(action: &SyncAction) -> String {
         match action {
@@ -197,6 +576,7 @@ impl InteractivePrompter {
                         }
                     }
                     ConflictStrategy::Skip => "Will skip (files differ)",
+                    ConflictStrategy::KeepBoth => "Will keep dest and write source as .incoming",
                 };
 
                 format!(
@@ -229,6 +609,7 @@ impl InteractivePrompter {
                         }
                     }
                     ConflictStrategy::Skip => "Will skip (directories differ)",
+                    ConflictStrategy::KeepBoth => "Will keep dest and copy source as .incoming",
                 };
 
                 format!(
@@ -243,22 +624,31 @@ impl InteractivePrompter {
 
     /// Show a diff for the action
     fn show_diff(action: &SyncAction) {
+        print!("{}", Self::render_diff(action));
+    }
+
+    /// Render the diff shown for an action
+    ///
+    /// Failures to read or compare are rendered as warnings in the output so
+    /// one unreadable entry does not hide the rest of a batch.
+    fn render_diff(action: &SyncAction) -> String {
+        let mut out = String::new();
         match action {
             SyncAction::Create { source, dest } => {
                 // Show new file content as additions
-                println!("\n--- New file ---");
-                println!("+++ {}", dest.display());
+                let _ = writeln!(out, "\n--- New file ---");
+                let _ = writeln!(out, "+++ {}", dest.display());
 
                 match std::fs::read_to_string(source) {
                     Ok(content) => {
-                        println!();
+                        let _ = writeln!(out);
                         for line in content.lines() {
-                            println!("\x1b[32m+{line}\x1b[0m");
+                            let _ = writeln!(out, "{}", color::paint(GREEN, format!("+{line}")));
                         }
                     }
                     Err(e) => {
-                        eprintln!("\nWarning: Failed to read file: {e}");
-                        eprintln!("Source: {}", source.display());
+                        let _ = writeln!(out, "\nWarning: Failed to read file: {e}");
+                        let _ = writeln!(out, "Source: {}", source.display());
                     }
                 }
             }
@@ -272,82 +662,102 @@ impl InteractivePrompter {
                             .and_then(|n| n.to_str())
                             .unwrap_or("unknown");
 
-                        println!("\n📦 New directory: {skill_name}");
-                        println!("   Will create {} file(s)", comparison.added.len());
+                        let _ = writeln!(out, "\n📦 New directory: {skill_name}");
+                        let _ = writeln!(out, "   Will create {} file(s)", comparison.added.len());
 
-                        if comparison.added.len() <= 10 {
-                            // Show all files if there aren't too many
-                            for file in &comparison.added {
-                                println!("     \x1b[32m+\x1b[0m {}", file.display());
-                            }
-                        } else {
-                            // Show first 10 files
-                            for file in comparison.added.iter().take(10) {
-                                println!("     \x1b[32m+\x1b[0m {}", file.display());
-                            }
-                            println!("     ... and {} more file(s)", comparison.added.len() - 10);
+                        // Show at most 10 files
+                        for file in comparison.added.iter().take(10) {
+                            let _ = writeln!(out, "     {} {}", color::paint(GREEN, "+"), file.display());
+                        }
+                        if comparison.added.len() > 10 {
+                            let _ = writeln!(
+                                out,
+                                "     ... and {} more file(s)",
+                                comparison.added.len() - 10
+                            );
                         }
                     }
                     Err(e) => {
-                        eprintln!("\nWarning: Failed to analyze directory: {e}");
-                        println!("\n--- New directory ---");
-                        println!("+++ {} (from {})", dest.display(), source.display());
+                        let _ = writeln!(out, "\nWarning: Failed to analyze directory: {e}");
+                        let _ = writeln!(out, "\n--- New directory ---");
+                        let _ = writeln!(out, "+++ {} (from {})", dest.display(), source.display());
                     }
                 }
             }
             SyncAction::Skip { .. } => {
-                println!("\n--- No diff (file will be skipped) ---");
+                let _ = writeln!(out, "\n--- No diff (file will be skipped) ---");
             }
             SyncAction::Conflict { source, dest, .. } => {
                 // Generate and display diff
                 match FileComparator::generate_diff(source, dest) {
                     Ok(diff) => {
-                        println!("\n{diff}");
+                        let _ = writeln!(out, "\n{diff}");
                     }
                     Err(e) => {
-                        eprintln!("\nWarning: Failed to generate diff: {e}");
-                        eprintln!("Source: {}", source.display());
-                        eprintln!("Dest:   {}", dest.display());
-                        eprintln!("You can inspect these files manually.");
+                        let _ = writeln!(out, "\nWarning: Failed to generate diff: {e}");
+                        let _ = writeln!(out, "Source: {}", source.display());
+                        let _ = writeln!(out, "Dest:   {}", dest.display());
+                        let _ = writeln!(out, "You can inspect these files manually.");
                     }
                 }
             }
             SyncAction::DirectoryConflict { source, dest, .. } => {
                 // Compare directories to get detailed diff
-                match DirectoryComparator::compare(source, dest) {
-                    Ok(comparison) => {
-                        // Extract skill name from source path
-                        let skill_name = source
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("unknown");
+                let summary = DirectoryComparator::compare(source, dest).and_then(|comparison| {
+                    // Extract skill name from source path
+                    let skill_name = source
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("unknown");
+                    DiffGenerator::generate_directory_summary(&comparison, source, dest, skill_name)
+                });
 
-                        match DiffGenerator::generate_directory_summary(
-                            &comparison,
-                            source,
-                            dest,
-                            skill_name,
-                        ) {
-                            Ok(summary) => {
-                                println!("{summary}");
-                            }
-                            Err(e) => {
-                                eprintln!("\nWarning: Failed to generate directory summary: {e}");
-                                println!("\n--- Directory conflict ---");
-                                println!("+++ {}", dest.display());
-                                println!("--- {}", source.display());
-                            }
-                        }
+                match summary {
+                    Ok(summary) => {
+                        let _ = writeln!(out, "{summary}");
                     }
                     Err(e) => {
-                        eprintln!("\nWarning: Failed to compare directories: {e}");
-                        println!("\n--- Directory conflict ---");
-                        println!("+++ {}", dest.display());
-                        println!("--- {}", source.display());
+                        let _ = writeln!(out, "\nWarning: Failed to compare directories: {e}");
+                        let _ = writeln!(out, "\n--- Directory conflict ---");
+                        let _ = writeln!(out, "+++ {}", dest.display());
+                        let _ = writeln!(out, "--- {}", source.display());
                     }
                 }
             }
         }
+        out
+    }
+
+    /// Render diffs for the current action and every action still pending after it
+    ///
+    /// Without a plan, or if the action is not part of it, only the current
+    /// action's diff is rendered.
+    fn render_pending_diffs(&self, action: &SyncAction) -> String {
+        let pending = self.pending_from(action);
+        if pending.is_empty() {
+            return Self::render_diff(action);
+        }
+
+        let mut out = format!("\n📝 Diffs for {} pending action(s):\n", pending.len());
+        for (idx, pending_action) in pending.iter().enumerate() {
+            let _ = write!(
+                out,
+                "\n━━━ {}/{}: {} ━━━\n{}",
+                idx + 1,
+                pending.len(),
+                pending_action.source_path().display(),
+                Self::render_diff(pending_action)
+            );
+        }
+        out
+    }
+
+    /// Actions from the plan that still need a decision, starting at `action`
+    fn pending_from(&self, action: &SyncAction) -> &[SyncAction] {
+        self.pending
+            .iter()
+            .position(|planned| planned == action)
+            .map_or(&[], |start| &self.pending[start..])
     }
 
     /// Show content diffs for modified files in a directory conflict
@@ -399,6 +809,48 @@ impl InteractivePrompter {
     }
 }
 
+/// Build the editor command line from `$VISUAL` / `$EDITOR`, falling back to `vi`
+fn editor_command(visual: Option<&str>, editor: Option<&str>) -> Vec<String> {
+    let command = [visual, editor]
+        .into_iter()
+        .flatten()
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or("vi");
+
+    command.split_whitespace().map(str::to_string).collect()
+}
+
+/// Build the diff viewer command line from `$DIFFTOOL`, if configured
+fn difftool_command(difftool: Option<&str>) -> Option<Vec<String>> {
+    let command: Vec<String> = difftool?.split_whitespace().map(str::to_string).collect();
+    (!command.is_empty()).then_some(command)
+}
+
+/// Run an external tool on the given paths and wait for it to exit
+///
+/// Returns whether the tool exited successfully, or `None` if it could not be
+/// launched. The cursor is restored afterwards since full-screen tools may
+/// leave it hidden. Keys are only read while a prompt waits, so none are
+/// taken from the tool.
+fn run_external(command: &[String], paths: &[&Path]) -> Option<bool> {
+    let (program, args) = command.split_first()?;
+
+    let status = Command::new(program).args(args).args(paths).status();
+    let _ = Term::stdout().show_cursor();
+
+    match status {
+        Ok(status) if status.success() => Some(true),
+        Ok(status) => {
+            eprintln!("\n'{program}' exited with {status}");
+            Some(false)
+        }
+        Err(e) => {
+            eprintln!("\nFailed to launch '{program}': {e}");
+            None
+        }
+    }
+}
+
 impl Default for InteractivePrompter {
     fn default() -> Self {
         Self::new()
@@ -421,9 +873,264 @@ mod tests {
         assert_ne!(UserChoice::Yes, UserChoice::No);
     }
 
+    #[test]
+    fn test_plan_preview_counts() {
+        let conflict = |strategy, source_newer| SyncAction::Conflict {
+            source: "src/a.md".into(),
+            dest: "dst/a.md".into(),
+            strategy,
+            source_newer,
+        };
+        let actions = vec![
+            SyncAction::Create {
+                source: "src/new.md".into(),
+                dest: "dst/new.md".into(),
+            },
+            SyncAction::CreateDirectory {
+                source: "src/skill".into(),
+                dest: "dst/skill".into(),
+            },
+            SyncAction::Skip {
+                path: "src/same.md".into(),
+                reason: "identical content".to_string(),
+            },
+            conflict(ConflictStrategy::Overwrite, false),
+            conflict(ConflictStrategy::Newer, true),
+            conflict(ConflictStrategy::Newer, false),
+            conflict(ConflictStrategy::Fail, true),
+        ];
+
+        let preview = PlanPreview::from_actions(&actions);
+
+        assert_eq!(
+            preview,
+            PlanPreview {
+                creates: 2,
+                updates: 2,
+                conflicts: 1,
+                skipped: 2,
+            }
+        );
+        assert_eq!(preview.changes(), 5);
+    }
+
+    #[test]
+    fn test_plan_preview_empty_plan_has_no_changes() {
+        let actions = vec![SyncAction::Skip {
+            path: "src/same.md".into(),
+            reason: "identical content".to_string(),
+        }];
+
+        assert_eq!(PlanPreview::from_actions(&actions).changes(), 0);
+    }
+
+    #[test]
+    fn test_editor_command_precedence() {
+        assert_eq!(editor_command(None, None), vec!["vi"]);
+        assert_eq!(editor_command(None, Some("nano")), vec!["nano"]);
+        assert_eq!(
+            editor_command(Some("code --wait"), Some("nano")),
+            vec!["code", "--wait"]
+        );
+        assert_eq!(editor_command(Some("  "), Some("nano")), vec!["nano"]);
+    }
+
+    #[test]
+    fn test_difftool_command() {
+        assert_eq!(difftool_command(None), None);
+        assert_eq!(difftool_command(Some(" ")), None);
+        assert_eq!(
+            difftool_command(Some("code --diff --wait")),
+            Some(vec!["code".into(), "--diff".into(), "--wait".into()])
+        );
+    }
+
+    #[test]
+    fn test_prompt_keys_per_action() {
+        let prompter = InteractivePrompter::new();
+        let keys = |action: &SyncAction| -> String {
+            prompter
+                .prompt_keys(action)
+                .iter()
+                .map(|(key, _)| *key)
+                .collect()
+        };
+
+        let create = SyncAction::Create {
+            source: "src/a.md".into(),
+            dest: "dst/a.md".into(),
+        };
+        let conflict = SyncAction::Conflict {
+            source: "src/a.md".into(),
+            dest: "dst/a.md".into(),
+            strategy: ConflictStrategy::Fail,
+            source_newer: true,
+        };
+        let directory = SyncAction::DirectoryConflict {
+            source: "src/skill".into(),
+            dest: "dst/skill".into(),
+            strategy: ConflictStrategy::Fail,
+            source_newer: true,
+        };
+
+        assert_eq!(keys(&create), "ynasdeq");
+        assert_eq!(keys(&conflict), "ynasdevq");
+        assert_eq!(keys(&directory), "ynasdcvq");
+
+        let planned = InteractivePrompter::with_plan(vec![create.clone()]);
+        let keys: String = planned.prompt_keys(&create).iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, "ynasdpeq");
+    }
+
+    #[test]
+    fn test_diff_all_renders_every_pending_action() {
+        let source = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        std::fs::write(source.path().join("first.md"), "first body").unwrap();
+        std::fs::write(source.path().join("second.md"), "second body").unwrap();
+        std::fs::write(source.path().join("third.md"), "third source").unwrap();
+        std::fs::write(dest.path().join("third.md"), "third dest").unwrap();
+
+        let create = |name: &str| SyncAction::Create {
+            source: source.path().join(name),
+            dest: dest.path().join(name),
+        };
+        let plan = vec![
+            create("first.md"),
+            SyncAction::Skip {
+                path: source.path().join("same.md"),
+                reason: "identical content".to_string(),
+            },
+            create("second.md"),
+            SyncAction::Conflict {
+                source: source.path().join("third.md"),
+                dest: dest.path().join("third.md"),
+                strategy: ConflictStrategy::Fail,
+                source_newer: true,
+            },
+        ];
+        let prompter = InteractivePrompter::with_plan(plan);
+
+        let all = prompter.render_pending_diffs(&create("first.md"));
+        assert!(all.contains("3 pending action(s)"));
+        assert!(all.contains("first body"));
+        assert!(all.contains("second body"));
+        assert!(all.contains("third source"));
+        assert!(all.contains("third dest"));
+
+        // Actions already decided are not shown again
+        let rest = prompter.render_pending_diffs(&create("second.md"));
+        assert!(rest.contains("2 pending action(s)"));
+        assert!(!rest.contains("first body"));
+        assert!(rest.contains("second body"));
+    }
+
+    #[test]
+    fn test_only_files_are_editable() {
+        let create = SyncAction::Create {
+            source: "src/a.md".into(),
+            dest: "dst/a.md".into(),
+        };
+        let directory = SyncAction::DirectoryConflict {
+            source: "src/skill".into(),
+            dest: "dst/skill".into(),
+            strategy: ConflictStrategy::Fail,
+            source_newer: true,
+        };
+
+        assert_eq!(
+            InteractivePrompter::editable_source(&create),
+            Some(Path::new("src/a.md"))
+        );
+        assert_eq!(InteractivePrompter::editable_source(&directory), None);
+    }
+
+    #[test]
+    fn test_unanswered_prompt_defaults_to_skip() {
+        let action = SyncAction::Create {
+            source: "src/a.md".into(),
+            dest: "dst/a.md".into(),
+        };
+        let mut prompter = InteractivePrompter::new();
+        prompter.keys = KeyReader::new(
+            Box::new(|| {
+                std::thread::sleep(Duration::from_secs(30));
+                Ok('y')
+            }),
+            Some(Duration::from_millis(10)),
+        );
+
+        assert_eq!(prompter.show_prompt(&action).unwrap(), UserChoice::No);
+        assert!(!prompter.prompt(&action).unwrap());
+    }
+
+    #[test]
+    fn test_answered_prompt_within_timeout() {
+        let action = SyncAction::Create {
+            source: "src/a.md".into(),
+            dest: "dst/a.md".into(),
+        };
+        let mut keys = "xa".chars();
+        let mut prompter = InteractivePrompter::new();
+        prompter.keys = KeyReader::new(
+            Box::new(move || {
+                keys.next()
+                    .ok_or_else(|| std::io::ErrorKind::UnexpectedEof.into())
+            }),
+            Some(Duration::from_secs(30)),
+        );
+
+        assert_eq!(prompter.show_prompt(&action).unwrap(), UserChoice::All);
+    }
+
+    #[test]
+    fn test_keys_are_read_only_on_demand() {
+        let reads = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = std::sync::Arc::clone(&reads);
+        let mut keys = KeyReader::new(
+            Box::new(move || {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok('y')
+            }),
+            Some(Duration::from_secs(30)),
+        );
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        assert_eq!(keys.read().unwrap(), Some('y'));
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_late_key_answers_next_prompt() {
+        let (tx, rx) = mpsc::channel::<char>();
+        let rx = Mutex::new(rx);
+        let mut keys = KeyReader::new(
+            Box::new(move || Ok(rx.lock().unwrap().recv().unwrap())),
+            Some(Duration::from_millis(10)),
+        );
+
+        assert_eq!(keys.read().unwrap(), None);
+        tx.send('n').unwrap();
+        keys.timeout = Some(Duration::from_secs(30));
+        assert_eq!(keys.read().unwrap(), Some('n'));
+    }
+
     #[test]
     fn test_prompter_creation() {
         let _prompter = InteractivePrompter::new();
         let _default_prompter = InteractivePrompter::default();
     }
+
+    #[test]
+    fn test_user_aborted_downcasts_through_context() {
+        let err: anyhow::Error = UserAborted.into();
+        let err = err.context("Sync operation failed");
+
+        assert!(err.is::<UserAborted>());
+        assert_eq!(err.root_cause().to_string(), "User aborted sync operation");
+        assert!(!anyhow::anyhow!("User aborted sync operation").is::<UserAborted>());
+    }
 }
@@ -2,10 +2,18 @@ mod cli;
 mod commands;
 mod interactive;
 
+use std::io::IsTerminal;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
 use anyhow::Context;
 use clap::Parser;
-use cli::{Cli, Commands};
+use ccsync_core::color::{self, ColorMode};
+use ccsync_core::config::OutputFormat;
+use cli::{Cli, ColorWhen, Commands, SummaryFormat};
 use commands::SyncOptions;
+use commands::common::{CONFLICT_EXIT_CODE, UnresolvedConflicts};
+use tracing::Level;
 
 fn main() -> anyhow::Result<()> {
     // Set up Ctrl+C handler for graceful interruption
@@ -16,43 +24,183 @@ fn main() -> anyhow::Result<()> {
     .context("Failed to set Ctrl+C handler")?;
 
     let cli = Cli::parse();
+    color::set_mode(match cli.color {
+        ColorWhen::Auto => ColorMode::Auto,
+        ColorWhen::Always => ColorMode::Always,
+        ColorWhen::Never => ColorMode::Never,
+    });
+    init_logging(cli.verbose, cli.quiet || cli.summary_only);
+    let verbose = cli.verbose > 0 && !cli.summary_only;
 
-    if cli.verbose {
+    if verbose {
         println!("Verbose mode enabled");
         println!("Dry run: {}", cli.dry_run);
         println!("Yes all: {}", cli.yes_all);
     }
 
-    // Create sync options from CLI flags
-    let options = SyncOptions::new(
-        cli.verbose,
-        cli.dry_run,
-        cli.yes_all,
-        cli.config.as_deref(),
-        cli.no_config,
-    );
+    let options = sync_options(&cli, verbose);
+    if let Err(e) = run(&cli, &options, verbose) {
+        if let Some(conflicts) = e.downcast_ref::<UnresolvedConflicts>() {
+            eprintln!("{conflicts}");
+            std::process::exit(CONFLICT_EXIT_CODE);
+        }
+        return Err(e);
+    }
 
+    Ok(())
+}
+
+/// Execute the selected subcommand
+fn run(cli: &Cli, options: &SyncOptions, verbose: bool) -> anyhow::Result<()> {
     match &cli.command {
-        Commands::ToLocal { types, conflict } => {
-            commands::ToLocal::execute(types, conflict, &options)
+        Commands::ToLocal {
+            types,
+            conflict,
+            patterns,
+            watch,
+            since,
+            ..
+        } => {
+            commands::ToLocal::execute(types, conflict, patterns, *watch, *since, options)
                 .context("Failed to execute to-local command")?;
         }
-        Commands::ToGlobal { types, conflict } => {
-            commands::ToGlobal::execute(types, conflict, &options)
+        Commands::ToGlobal {
+            types,
+            conflict,
+            patterns,
+            watch,
+            since,
+            ..
+        } => {
+            commands::ToGlobal::execute(types, conflict, patterns, *watch, *since, options)
                 .context("Failed to execute to-global command")?;
         }
-        Commands::Status { types } => {
-            commands::Status::execute(types, cli.verbose)
-                .context("Failed to execute status command")?;
+        Commands::Sync {
+            types,
+            conflict,
+            patterns,
+        } => {
+            commands::Sync::execute(types, conflict, patterns, options)
+                .context("Failed to execute sync command")?;
+        }
+        Commands::Status {
+            types,
+            newer,
+            orphans,
+        } => {
+            // Exit 1 is reserved for "differences found", so errors use 2
+            if let Err(e) = commands::Status::execute(types, *newer, *orphans, options)
+                .context("Failed to execute status command")
+            {
+                eprintln!("Error: {e:?}");
+                std::process::exit(commands::status::ERROR_EXIT_CODE);
+            }
         }
-        Commands::Diff { types } => {
-            commands::Diff::execute(types, cli.verbose)
+        Commands::Diff {
+            types,
+            stat,
+            only_conflicts,
+            format,
+        } => {
+            commands::Diff::execute(types, *stat, *only_conflicts, *format, options)
                 .context("Failed to execute diff command")?;
         }
         Commands::Config => {
-            commands::Config::execute(cli.verbose).context("Failed to execute config command")?;
+            commands::Config::execute(verbose).context("Failed to execute config command")?;
+        }
+        Commands::Doctor => {
+            commands::Doctor::execute(options).context("Failed to execute doctor command")?;
+        }
+        Commands::Undo => {
+            commands::Undo::execute(options).context("Failed to execute undo command")?;
+        }
+        Commands::Clean => {
+            commands::Clean::execute(options).context("Failed to execute clean command")?;
+        }
+        Commands::CheckPattern {
+            path,
+            dir,
+            types,
+            patterns,
+        } => {
+            commands::CheckPattern::execute(path, *dir, types, patterns, options)
+                .context("Failed to execute check-pattern command")?;
+        }
+        Commands::Apply { plan } => {
+            commands::Apply::execute(plan, options).context("Failed to execute apply command")?;
         }
     }
 
     Ok(())
 }
+
+/// Create sync options from CLI flags
+fn sync_options(cli: &Cli, verbose: bool) -> SyncOptions<'_> {
+    SyncOptions::new(
+        verbose,
+        cli.quiet,
+        cli.dry_run,
+        cli.yes_all,
+        cli.config.as_deref(),
+        cli.no_config,
+        cli.profile.as_deref(),
+    )
+    .with_no_dry_run(cli.no_dry_run)
+    .with_paths(cli.global_path.as_deref(), cli.local_path.as_deref())
+    .with_allow_outside_home(cli.allow_outside_home)
+    .with_fail_on_conflict(cli.fail_on_conflict)
+    .with_show_diff(cli.show_diff)
+    .with_hardlink_identical(cli.hardlink_identical)
+    .with_preserve_ownership(cli.preserve_ownership)
+    .with_semantic_frontmatter(cli.semantic_frontmatter)
+    .with_dereference(cli.dereference)
+    .with_prompt_timeout(cli.prompt_timeout.map(Duration::from_secs))
+    .with_plan_out(cli.plan_out.as_deref())
+    .with_detect_changes(cli.detect_changes)
+    .with_limit(cli.limit)
+    .with_jobs(cli.jobs.map(NonZeroUsize::get))
+    .with_prune(cli.prune)
+    .with_fail_fast(cli.fail_fast)
+    .with_verify(cli.verify)
+    .with_trace_decisions(cli.verbose >= 2 && !cli.summary_only)
+    .with_reverse(matches!(
+        cli.command,
+        Commands::ToLocal { reverse: true, .. } | Commands::ToGlobal { reverse: true, .. }
+    ))
+    .with_summary_only(cli.summary_only)
+    .with_output_format(if cli.json {
+        Some(OutputFormat::Json)
+    } else {
+        cli.output_format.map(|format| match format {
+            SummaryFormat::Text => OutputFormat::Text,
+            SummaryFormat::Json => OutputFormat::Json,
+        })
+    })
+    .with_interactive(if cli.interactive {
+        Some(true)
+    } else if cli.non_interactive {
+        Some(false)
+    } else {
+        None
+    })
+}
+
+/// Install the stderr log subscriber, raising the level with each `-v`
+///
+/// `errors_only` (from `--quiet` or `--summary-only`) lowers it to errors.
+fn init_logging(verbosity: u8, errors_only: bool) {
+    let level = match verbosity {
+        _ if errors_only => Level::ERROR,
+        0 => Level::INFO,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false)
+        .without_time()
+        .init();
+}
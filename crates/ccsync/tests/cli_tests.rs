@@ -12,6 +12,7 @@ fn test_help_output() {
         ))
         .stdout(predicate::str::contains("to-local"))
         .stdout(predicate::str::contains("to-global"))
+        .stdout(predicate::str::contains("sync"))
         .stdout(predicate::str::contains("status"))
         .stdout(predicate::str::contains("diff"))
         .stdout(predicate::str::contains("config"));
@@ -28,36 +29,145 @@ fn test_version_output() {
 
 #[test]
 fn test_to_local_command() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    // May succeed or fail depending on whether directories exist
-    // Just verify it doesn't panic and produces some output
-    cmd.arg("to-local").assert();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local"])
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/agent.md").exists());
 }
 
 #[test]
 fn test_to_global_command() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-global"])
+        .assert()
+        .success();
+
+    assert!(home.path().join(".claude/agents/agent.md").exists());
+}
+
+#[test]
+fn test_status_in_sync_exits_zero() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    for root in [home.path(), project.path()] {
+        std::fs::create_dir_all(root.join(".claude/agents")).unwrap();
+        std::fs::write(root.join(".claude/agents/same.md"), "same").unwrap();
+    }
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    // May succeed or fail depending on whether directories exist
-    // Just verify it doesn't panic and produces some output
-    cmd.arg("to-global").assert();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("status")
+        .assert()
+        .code(0)
+        .stdout(predicate::str::contains("In sync"));
 }
 
 #[test]
-fn test_status_command() {
+fn test_status_diverging_exits_one() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("global.md"), "global").unwrap();
+    std::fs::write(local_agents.join("local.md"), "local").unwrap();
+    std::fs::write(global_agents.join("changed.md"), "global").unwrap();
+    std::fs::write(local_agents.join("changed.md"), "local").unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    cmd.arg("status")
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("status")
         .assert()
-        .success()
-        .stdout(predicate::str::contains("Not yet implemented"));
+        .code(1)
+        .stdout(predicate::str::contains("global only  agents/global.md"))
+        .stdout(predicate::str::contains("local only   agents/local.md"))
+        .stdout(predicate::str::contains("different    agents/changed.md"))
+        .stdout(predicate::str::contains("3 difference(s)"));
+}
+
+#[test]
+fn test_status_reports_local_edits_with_baseline() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "global").unwrap();
+    std::fs::write(project.path().join(".ccsync.toml"), "track_baseline = true\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local"])
+        .assert()
+        .success();
+    std::fs::write(project.path().join(".claude/agents/a.md"), "edited locally").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("status")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("different    agents/a.md"));
+}
+
+#[test]
+fn test_status_error_exits_two() {
+    let project = tempfile::TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.current_dir(project.path())
+        .args(["--config", "missing.toml", "status"])
+        .assert()
+        .code(2);
 }
 
 #[test]
 fn test_diff_command() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("new.md"), "brand new\n").unwrap();
+    std::fs::write(global_agents.join("changed.md"), "global line\n").unwrap();
+    std::fs::write(local_agents.join("changed.md"), "local line\n").unwrap();
+    std::fs::write(global_agents.join("blob.md"), b"a\0b").unwrap();
+    std::fs::write(local_agents.join("blob.md"), b"a\0c").unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    cmd.arg("diff")
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("diff")
         .assert()
         .success()
-        .stdout(predicate::str::contains("Not yet implemented"));
+        .stdout(predicate::str::contains("+brand new"))
+        .stdout(predicate::str::contains("-local line"))
+        .stdout(predicate::str::contains("+global line"))
+        .stdout(predicate::str::is_match("Binary files .*blob.md and .*blob.md differ").unwrap())
+        .stdout(predicate::str::contains("Not yet implemented").not());
 }
 
 #[test]
@@ -86,24 +196,73 @@ fn test_dry_run_flag() {
 
 #[test]
 fn test_to_local_with_type_filter() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    // May succeed or fail depending on whether directories exist
-    cmd.args(["to-local", "--type", "agents"]).assert();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--type", "agents"])
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/agent.md").exists());
 }
 
 #[test]
 fn test_to_local_with_multiple_types() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+    let skill = home.path().join(".claude/skills/tool");
+    std::fs::create_dir_all(&skill).unwrap();
+    std::fs::write(skill.join("SKILL.md"), "skill").unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    // May succeed or fail depending on whether directories exist
-    cmd.args(["to-local", "--type", "agents", "--type", "skills"])
-        .assert();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args([
+            "--yes-all",
+            "to-local",
+            "--type",
+            "agents",
+            "--type",
+            "skills",
+        ])
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/agent.md").exists());
+    assert!(project.path().join(".claude/skills/tool/SKILL.md").exists());
 }
 
 #[test]
 fn test_to_local_with_conflict_mode() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(local_agents.join("agent.md"), "local").unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    // May succeed or fail depending on whether directories exist
-    cmd.args(["to-local", "--conflict", "overwrite"]).assert();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--conflict", "overwrite"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(local_agents.join("agent.md")).unwrap(),
+        "agent"
+    );
 }
 
 #[test]
@@ -143,29 +302,46 @@ fn test_no_subcommand() {
 
 #[test]
 fn test_global_flags_with_to_local() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    // May succeed or fail depending on whether directories exist
-    cmd.args([
-        "--verbose",
-        "--dry-run",
-        "--non-interactive",
-        "to-local",
-        "--conflict",
-        "skip",
-    ])
-    .assert();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args([
+            "--verbose",
+            "--dry-run",
+            "--non-interactive",
+            "to-local",
+            "--conflict",
+            "skip",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Executing to-local command"));
 }
 
 #[test]
 fn test_preserve_symlinks_flag() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
     let mut cmd = Command::cargo_bin("ccsync").unwrap();
-    // May succeed or fail depending on whether directories exist
-    cmd.args(["--preserve-symlinks", "to-local"]).assert();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--preserve-symlinks", "to-local"])
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/agent.md").exists());
 }
 
 #[test]
 fn test_help_for_subcommands() {
-    for subcommand in &["to-local", "to-global", "status", "diff", "config"] {
+    for subcommand in &["to-local", "to-global", "sync", "status", "diff", "config"] {
         let mut cmd = Command::cargo_bin("ccsync").unwrap();
         cmd.args([subcommand, "--help"])
             .assert()
@@ -173,3 +349,1501 @@ fn test_help_for_subcommands() {
             .stdout(predicate::str::contains("Usage"));
     }
 }
+
+#[test]
+fn test_interactive_mode_requires_tty() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    // Piped stdin is not a terminal, so prompting must be refused up front
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("to-local")
+        .write_stdin("y\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Interactive mode requires a TTY"));
+
+    assert!(!project.path().join(".claude/agents/agent.md").exists());
+}
+
+#[test]
+fn test_interactive_flag_prompts_during_dry_run() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    // Forced prompting skips the stdin check and previews the plan; reading
+    // the answer then fails because the test has no terminal at all
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--interactive", "--dry-run", "to-local"])
+        .write_stdin("y\n")
+        .assert()
+        .stdout(predicate::str::contains("Sync plan: 1 to create"))
+        .stderr(predicate::str::contains("requires a TTY").not());
+
+    assert!(!project.path().join(".claude/agents/agent.md").exists());
+}
+
+#[test]
+fn test_non_interactive_flag_applies_configured_strategy() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    for (root, content) in [(home.path(), "global"), (project.path(), "local")] {
+        let agents = root.join(".claude/agents");
+        std::fs::create_dir_all(&agents).unwrap();
+        std::fs::write(agents.join("agent.md"), content).unwrap();
+    }
+
+    // Without --yes-all or a terminal, no prompt is attempted
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--non-interactive", "to-local", "--conflict", "overwrite"])
+        .write_stdin("n\n")
+        .assert()
+        .success();
+
+    let synced = std::fs::read_to_string(project.path().join(".claude/agents/agent.md")).unwrap();
+    assert_eq!(synced, "global");
+}
+
+#[test]
+fn test_yes_all_works_without_tty() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local"])
+        .write_stdin("")
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/agent.md").exists());
+}
+
+#[test]
+fn test_sync_command_copies_both_ways() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("from-global.md"), "g").unwrap();
+    std::fs::write(local_agents.join("from-local.md"), "l").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "sync"])
+        .assert()
+        .success();
+
+    assert!(local_agents.join("from-global.md").exists());
+    assert!(global_agents.join("from-local.md").exists());
+}
+
+#[test]
+fn test_sync_command_rejects_verify() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--verify", "sync"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--verify is only supported"));
+}
+
+#[test]
+fn test_dry_run_logs_planned_operations() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--dry-run", "to-local"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[DRY RUN] Would create"));
+
+    assert!(!project.path().join(".claude/agents/agent.md").exists());
+}
+
+#[test]
+fn test_repeated_verbose_flag_enables_debug_logs() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["-vv", "--yes-all", "to-local"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("DEBUG"));
+}
+
+#[test]
+fn test_since_skips_files_older_than_window() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("recent.md"), "recent").unwrap();
+    std::fs::write(agents.join("old.md"), "old").unwrap();
+    let two_hours_ago = std::time::SystemTime::now() - std::time::Duration::from_hours(2);
+    std::fs::File::options()
+        .write(true)
+        .open(agents.join("old.md"))
+        .unwrap()
+        .set_modified(two_hours_ago)
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--since", "1h"])
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/recent.md").exists());
+    assert!(!project.path().join(".claude/agents/old.md").exists());
+}
+
+#[test]
+fn test_since_rejects_invalid_duration() {
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.args(["to-local", "--since", "soon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid duration"));
+}
+
+#[test]
+fn test_exclude_flag_skips_matching_files() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("git-commit.md"), "git").unwrap();
+    std::fs::write(agents.join("git-review.md"), "git").unwrap();
+    std::fs::write(agents.join("reviewer.md"), "reviewer").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--exclude", "agents/git-*"])
+        .assert()
+        .success();
+
+    let local_agents = project.path().join(".claude/agents");
+    assert!(local_agents.join("reviewer.md").exists());
+    assert!(!local_agents.join("git-commit.md").exists());
+    assert!(!local_agents.join("git-review.md").exists());
+}
+
+#[test]
+fn test_exclude_flag_wins_over_type_filter() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("git-a.md"), "git").unwrap();
+    std::fs::write(agents.join("reviewer.md"), "reviewer").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--type", "agents", "--exclude", "agents/git-*"])
+        .assert()
+        .success();
+
+    let local_agents = project.path().join(".claude/agents");
+    assert!(local_agents.join("reviewer.md").exists());
+    assert!(!local_agents.join("git-a.md").exists());
+}
+
+#[test]
+fn test_include_flag_overrides_config_ignore() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("draft-a.md"), "a").unwrap();
+    std::fs::write(agents.join("draft-b.md"), "b").unwrap();
+    std::fs::write(
+        project.path().join(".ccsync.toml"),
+        "ignore = [\"agents/draft-*\"]\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--include", "agents/draft-a.md"])
+        .assert()
+        .success();
+
+    let local_agents = project.path().join(".claude/agents");
+    assert!(local_agents.join("draft-a.md").exists());
+    assert!(!local_agents.join("draft-b.md").exists());
+}
+
+#[test]
+fn test_json_summary_reports_skip_reasons() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("a.md"), "a").unwrap();
+    std::fs::write(agents.join("draft-b.md"), "b").unwrap();
+    std::fs::write(
+        project.path().join(".ccsync.toml"),
+        "ignore = [\"agents/draft-*\"]\n",
+    )
+    .unwrap();
+
+    for command in ["to-local", "sync"] {
+        let mut cmd = Command::cargo_bin("ccsync").unwrap();
+        cmd.env("HOME", home.path())
+            .current_dir(project.path())
+            .args(["--yes-all", "--dry-run", "--json", command])
+            .assert()
+            .success()
+            .stdout(predicate::str::starts_with("{"))
+            .stdout(predicate::str::contains(
+                "\"skip_reasons\": {\n    \"excluded by pattern\": 1\n  }",
+            ));
+    }
+}
+
+#[test]
+fn test_config_output_format_defaults_summary_to_json() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("a.md"), "a").unwrap();
+    std::fs::write(project.path().join(".ccsync.toml"), "output_format = \"json\"\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--dry-run", "to-local"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{"))
+        .stdout(predicate::str::contains("\"created\": 1"));
+
+    // The command line overrides the config
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--dry-run", "--output-format", "text", "to-local"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Sync Summary ==="));
+}
+
+#[test]
+fn test_config_dry_run_writes_nothing() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("a.md"), "a").unwrap();
+    std::fs::write(project.path().join(".ccsync.toml"), "dry_run = true\n").unwrap();
+
+    // No --yes-all: a dry run never prompts, even without a terminal
+    for command in ["to-local", "sync"] {
+        let mut cmd = Command::cargo_bin("ccsync").unwrap();
+        cmd.env("HOME", home.path())
+            .current_dir(project.path())
+            .arg(command)
+            .assert()
+            .success();
+    }
+
+    assert!(!project.path().join(".claude/agents/a.md").exists());
+    assert!(!home.path().join(".claude/.ccsync/undo").exists());
+}
+
+#[test]
+fn test_no_dry_run_overrides_config() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("a.md"), "a").unwrap();
+    std::fs::write(project.path().join(".ccsync.toml"), "dry_run = true\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--no-dry-run", "to-local"])
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/a.md").exists());
+}
+
+#[test]
+fn test_prune_keeps_files_added_by_hand() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::write(global_agents.join("synced.md"), "synced").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local"])
+        .assert()
+        .success();
+    std::fs::write(local_agents.join("mine.md"), "mine").unwrap();
+    std::fs::remove_file(global_agents.join("synced.md")).unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--prune", "to-local"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted:  1"));
+
+    assert!(!local_agents.join("synced.md").exists());
+    assert!(local_agents.join("mine.md").exists());
+}
+
+#[test]
+fn test_undo_restores_overwritten_file() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("reviewer.md"), "global").unwrap();
+    std::fs::write(global_agents.join("helper.md"), "helper").unwrap();
+    std::fs::write(local_agents.join("reviewer.md"), "local original").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--conflict", "overwrite"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(local_agents.join("reviewer.md")).unwrap(),
+        "global"
+    );
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("undo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 file(s) restored, 1 file(s) removed"));
+
+    assert_eq!(
+        std::fs::read_to_string(local_agents.join("reviewer.md")).unwrap(),
+        "local original"
+    );
+    assert!(!local_agents.join("helper.md").exists());
+
+    // The journal is consumed, so a second undo has nothing to do
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("undo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Nothing to undo"));
+}
+
+#[test]
+fn test_clean_removes_only_state_files() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global = home.path().join(".claude");
+    let local = project.path().join(".claude");
+    std::fs::create_dir_all(global.join(".ccsync/undo")).unwrap();
+    std::fs::create_dir_all(global.join("agents")).unwrap();
+    std::fs::create_dir_all(local.join("agents")).unwrap();
+    std::fs::write(global.join(".ccsync/undo/journal.json"), "{}").unwrap();
+    std::fs::write(global.join(".ccsync-state.json"), "{}").unwrap();
+    std::fs::write(global.join("agents/a.md"), "agent").unwrap();
+    std::fs::write(local.join(".ccsync-manifest"), "agents/a.md\n").unwrap();
+    std::fs::write(local.join(".ccsync-state.json"), "{}").unwrap();
+    std::fs::write(local.join("agents/a.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--dry-run", "clean"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[DRY RUN] Would remove 4 state file(s)"));
+    assert!(local.join(".ccsync-manifest").exists());
+    assert!(global.join(".ccsync/undo").exists());
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .arg("clean")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4 state file(s) removed"));
+
+    assert!(!local.join(".ccsync-manifest").exists());
+    assert!(!local.join(".ccsync-state.json").exists());
+    assert!(!global.join(".ccsync-state.json").exists());
+    assert!(!global.join(".ccsync").exists());
+    assert!(local.join("agents/a.md").exists());
+    assert!(global.join("agents/a.md").exists());
+}
+
+#[test]
+fn test_diff_stat_shows_per_file_counts() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("new.md"), "a\nb\n").unwrap();
+    std::fs::write(global_agents.join("changed.md"), "same\nglobal\n").unwrap();
+    std::fs::write(local_agents.join("changed.md"), "same\nlocal\n").unwrap();
+    std::fs::write(global_agents.join("equal.md"), "equal\n").unwrap();
+    std::fs::write(local_agents.join("equal.md"), "equal\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["diff", "--stat"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(" agents/changed.md | +1 -1"))
+        .stdout(predicate::str::contains(" agents/new.md     | +2 -0"))
+        .stdout(predicate::str::contains("equal.md").not())
+        .stdout(predicate::str::contains(
+            "2 files changed, 3 insertion(s)(+), 1 deletion(s)(-)",
+        ));
+}
+
+#[test]
+fn test_diff_format_json_lists_files_and_hunks() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("changed.md"), "same\nglobal\n").unwrap();
+    std::fs::write(local_agents.join("changed.md"), "same\nlocal\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["diff", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""path": "agents/changed.md""#))
+        .stdout(predicate::str::contains(r#""added": 1"#))
+        .stdout(predicate::str::contains(r#""old_start": 1"#))
+        .stdout(predicate::str::contains(r#""tag": "delete""#))
+        .stdout(predicate::str::contains(r#""content": "global""#));
+}
+
+#[test]
+fn test_max_file_size_flag_skips_large_files() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("small.md"), "small").unwrap();
+    std::fs::write(agents.join("large.md"), "x".repeat(2048)).unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--max-file-size", "1K"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("exceeds max-file-size"));
+
+    let local_agents = project.path().join(".claude/agents");
+    assert!(local_agents.join("small.md").exists());
+    assert!(!local_agents.join("large.md").exists());
+}
+
+#[test]
+fn test_quiet_flag_suppresses_output_on_clean_sync() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--quiet", "to-local"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+
+    assert!(project.path().join(".claude/agents/agent.md").exists());
+}
+
+#[test]
+fn test_quiet_conflicts_with_verbose() {
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.args(["--quiet", "-v", "to-local"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_fail_on_conflict_exits_with_code_2() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "global a").unwrap();
+    std::fs::write(local_agents.join("a.md"), "local a").unwrap();
+    std::fs::write(global_agents.join("b.md"), "global b").unwrap();
+    std::fs::write(local_agents.join("b.md"), "local b").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args([
+            "--yes-all",
+            "--fail-on-conflict",
+            "to-local",
+            "--conflict",
+            "skip",
+        ])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("2 conflict(s) left unresolved"));
+
+    assert_eq!(
+        std::fs::read_to_string(local_agents.join("a.md")).unwrap(),
+        "local a"
+    );
+}
+
+#[test]
+fn test_conflict_skip_exits_zero_by_default() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "global").unwrap();
+    std::fs::write(local_agents.join("a.md"), "local").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--conflict", "skip"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_dry_run_show_diff_prints_conflict_diffs() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "shared\nfrom global\n").unwrap();
+    std::fs::write(local_agents.join("a.md"), "shared\nfrom local\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args([
+            "--dry-run",
+            "--show-diff",
+            "to-local",
+            "--conflict",
+            "overwrite",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[DRY RUN] Diff for"))
+        .stderr(predicate::str::contains("-from local"))
+        .stderr(predicate::str::contains("+from global"))
+        .stderr(predicate::str::contains("[DRY RUN] Would overwrite"));
+
+    assert_eq!(
+        std::fs::read_to_string(local_agents.join("a.md")).unwrap(),
+        "shared\nfrom local\n"
+    );
+}
+
+#[test]
+fn test_show_diff_requires_dry_run() {
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.args(["--show-diff", "to-local"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--dry-run"));
+}
+
+#[test]
+fn test_dry_run_plan_out_writes_plan_file() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "from global\n").unwrap();
+    let plan_path = project.path().join("plan.json");
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "--plan-out", "plan.json"])
+        .assert()
+        .success();
+
+    let plan = std::fs::read_to_string(&plan_path).unwrap();
+    assert!(plan.contains(r#""action": "create""#));
+    assert!(plan.contains("a.md"));
+    assert!(!project.path().join(".claude/agents/a.md").exists());
+}
+
+#[test]
+fn test_detect_changes_exits_one_for_drifted_tree() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "from global\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "--detect-changes"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Changes detected: 1"));
+
+    assert!(!project.path().join(".claude/agents/a.md").exists());
+}
+
+#[test]
+fn test_detect_changes_exits_zero_for_in_sync_tree() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    for root in [home.path(), project.path()] {
+        let agents = root.join(".claude/agents");
+        std::fs::create_dir_all(&agents).unwrap();
+        std::fs::write(agents.join("a.md"), "same\n").unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "--detect-changes"])
+        .assert()
+        .code(0);
+
+    // Without --detect-changes a drifted dry run still succeeds
+    std::fs::write(home.path().join(".claude/agents/b.md"), "new\n").unwrap();
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run"])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn test_apply_runs_saved_plan() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "from global\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "--plan-out", "plan.json"])
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["apply", "plan.json"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(project.path().join(".claude/agents/a.md")).unwrap(),
+        "from global\n"
+    );
+}
+
+#[test]
+fn test_apply_rejects_stale_plan() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "from global\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "--plan-out", "plan.json"])
+        .assert()
+        .success();
+    std::fs::write(global_agents.join("a.md"), "edited after review\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["apply", "plan.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Stale plan"));
+
+    assert!(!project.path().join(".claude/agents/a.md").exists());
+}
+
+#[test]
+fn test_apply_rejects_plan_whose_destination_appeared() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "from global\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "--plan-out", "plan.json"])
+        .assert()
+        .success();
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(local_agents.join("a.md"), "written locally\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "apply", "plan.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("was created since the plan was made"));
+
+    assert_eq!(
+        std::fs::read_to_string(local_agents.join("a.md")).unwrap(),
+        "written locally\n"
+    );
+}
+
+#[test]
+fn test_limit_caps_created_files() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    for name in ["a", "b", "c", "d", "e"] {
+        std::fs::write(global_agents.join(format!("{name}.md")), name).unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--yes-all", "--limit", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deferred: 3"));
+
+    let created = std::fs::read_dir(project.path().join(".claude/agents"))
+        .unwrap()
+        .count();
+    assert_eq!(created, 2);
+}
+
+#[test]
+fn test_piped_diff_is_not_colored_by_default() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "shared\nfrom global\n").unwrap();
+    std::fs::write(local_agents.join("a.md"), "shared\nfrom local\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["diff", "--only-conflicts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+from global"))
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["diff", "--only-conflicts", "--color", "always"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[32m+from global"));
+}
+
+#[test]
+fn test_failed_file_does_not_stop_the_rest() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "from global\n").unwrap();
+    std::fs::write(local_agents.join("a.md"), "from local\n").unwrap();
+    std::fs::write(global_agents.join("b.md"), "b\n").unwrap();
+    std::fs::write(global_agents.join("c.md"), "c\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--yes-all", "--keep-going"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 error(s)"));
+
+    assert!(local_agents.join("b.md").exists());
+    assert!(local_agents.join("c.md").exists());
+}
+
+#[test]
+fn test_double_verbose_prints_each_file_decision() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("new.md"), "new\n").unwrap();
+    std::fs::write(global_agents.join("same.md"), "same\n").unwrap();
+    std::fs::write(local_agents.join("same.md"), "same\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "-vv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "  agents/new.md: Create: missing at destination",
+        ))
+        .stdout(predicate::str::contains(
+            "  agents/same.md: Skip: identical content",
+        ));
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["to-local", "--dry-run", "-v"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("agents/new.md: Create").not());
+}
+
+#[test]
+fn test_doctor_passes_in_healthy_environment() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+    std::fs::create_dir_all(project.path().join(".claude")).unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .current_dir(project.path())
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Global directory"))
+        .stdout(predicate::str::contains("✓ Config is valid"))
+        .stdout(predicate::str::contains("All checks passed"));
+}
+
+#[test]
+fn test_doctor_fails_on_invalid_config() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+    std::fs::create_dir_all(project.path().join(".claude")).unwrap();
+    std::fs::write(
+        project.path().join(".ccsync.toml"),
+        "follow_symlinks = true\npreserve_symlinks = true\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .current_dir(project.path())
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("✓ Config parses"))
+        .stdout(predicate::str::contains("✗ Symlink settings"))
+        .stdout(predicate::str::contains("✗ Config is valid"))
+        .stderr(predicate::str::contains("2 check(s) failed"));
+}
+
+#[test]
+fn test_reverse_previews_files_newer_locally() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("a.md"), "global").unwrap();
+    std::fs::write(local_agents.join("a.md"), "local").unwrap();
+    std::fs::write(local_agents.join("b.md"), "local only").unwrap();
+    let an_hour_ago = std::time::SystemTime::now() - std::time::Duration::from_hours(1);
+    std::fs::File::options()
+        .write(true)
+        .open(global_agents.join("a.md"))
+        .unwrap()
+        .set_modified(an_hour_ago)
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--dry-run", "to-local", "--conflict", "skip", "--reverse"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Reverse preview (to-global) ==="))
+        .stdout(predicate::str::contains(
+            "agents/a.md: Conflict: hashes differ, source newer",
+        ))
+        .stdout(predicate::str::contains("agents/b.md: Create"));
+
+    assert_eq!(
+        std::fs::read_to_string(global_agents.join("a.md")).unwrap(),
+        "global"
+    );
+    assert!(!global_agents.join("b.md").exists());
+}
+
+#[test]
+fn test_dry_run_reports_conflicts_that_would_fail() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    for name in ["a.md", "b.md"] {
+        std::fs::write(global_agents.join(name), "global").unwrap();
+        std::fs::write(local_agents.join(name), "local").unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--dry-run", "to-local"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Conflicts: 2"))
+        .stdout(predicate::str::contains("Would fail: 2 conflict(s)"))
+        .stderr(predicate::str::contains("would fail on 2 conflict(s)"));
+
+    assert_eq!(
+        std::fs::read_to_string(local_agents.join("a.md")).unwrap(),
+        "local"
+    );
+}
+
+#[test]
+fn test_diff_only_conflicts_hides_added_and_removed_files() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(global_agents.join("added.md"), "new\n").unwrap();
+    std::fs::write(local_agents.join("removed.md"), "old\n").unwrap();
+    std::fs::write(global_agents.join("changed.md"), "same\nglobal\n").unwrap();
+    std::fs::write(local_agents.join("changed.md"), "same\nlocal\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["diff", "--only-conflicts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("changed.md"))
+        .stdout(predicate::str::contains("-local"))
+        .stdout(predicate::str::contains("+global"))
+        .stdout(predicate::str::contains("added.md").not())
+        .stdout(predicate::str::contains("removed.md").not());
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["diff", "--stat", "--only-conflicts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(" agents/changed.md | +1 -1"))
+        .stdout(predicate::str::contains("added.md").not())
+        .stdout(predicate::str::contains("1 file changed"));
+}
+
+#[test]
+fn test_summary_only_prints_just_the_summary() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let agents = home.path().join(".claude/agents");
+    std::fs::create_dir_all(&agents).unwrap();
+    std::fs::write(agents.join("agent.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["-v", "--yes-all", "--dry-run", "--summary-only", "to-local"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("\n=== Sync Summary ==="))
+        .stdout(predicate::str::contains("Created:  1"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_to_global_copies_new_local_agent() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&local_agents).unwrap();
+    std::fs::write(local_agents.join("new.md"), "new agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-global"])
+        .assert()
+        .success();
+
+    let synced = home.path().join(".claude/agents/new.md");
+    assert_eq!(std::fs::read_to_string(synced).unwrap(), "new agent");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dereference_writes_symlinked_agent_as_regular_file() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global = home.path().join(".claude");
+    std::fs::create_dir_all(global.join("agents")).unwrap();
+    std::fs::write(home.path().join("reviewer.md"), "shared agent").unwrap();
+    std::os::unix::fs::symlink(
+        home.path().join("reviewer.md"),
+        global.join("agents/reviewer.md"),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--dereference", "to-local"])
+        .assert()
+        .success();
+
+    let synced = project.path().join(".claude/agents/reviewer.md");
+    assert!(std::fs::symlink_metadata(&synced).unwrap().is_file());
+    assert_eq!(std::fs::read_to_string(synced).unwrap(), "shared agent");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pre_sync_hook_runs_before_scanning() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+    std::fs::write(
+        project.path().join(".ccsync.toml"),
+        r#"
+pre_sync = 'mkdir -p "$HOME/.claude/agents" && echo pulled > "$HOME/.claude/agents/pulled.md"'
+post_sync = "echo finished"
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--verbose", "to-local"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ran pre_sync hook"))
+        .stdout(predicate::str::contains("Ran post_sync hook\nfinished"));
+
+    // The file the hook wrote was picked up by the scan that followed
+    assert_eq!(
+        std::fs::read_to_string(project.path().join(".claude/agents/pulled.md")).unwrap(),
+        "pulled\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_failing_pre_sync_hook_aborts_sync() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global = home.path().join(".claude");
+    std::fs::create_dir_all(global.join("agents")).unwrap();
+    std::fs::write(global.join("agents/a.md"), "agent").unwrap();
+    std::fs::write(
+        project.path().join(".ccsync.toml"),
+        "pre_sync = \"echo no remote >&2; exit 1\"\npost_sync = \"touch post-ran\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pre_sync hook"))
+        .stderr(predicate::str::contains("no remote"));
+
+    assert!(!project.path().join(".claude/agents/a.md").exists());
+    assert!(!project.path().join("post-ran").exists());
+}
+
+#[test]
+fn test_path_overrides_replace_default_directories() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global = tempfile::TempDir::new().unwrap();
+    let local = project.path().join("elsewhere");
+    std::fs::create_dir_all(local.join("agents")).unwrap();
+    std::fs::write(local.join("agents/new.md"), "new agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--allow-outside-home"])
+        .arg("--global-path")
+        .arg(global.path())
+        .args(["--local-path", "elsewhere", "to-global"])
+        .assert()
+        .success();
+
+    assert!(global.path().join("agents/new.md").exists());
+    assert!(global.path().join(".ccsync/undo").exists());
+    assert!(!home.path().join(".claude").exists());
+}
+
+#[test]
+fn test_destination_outside_home_is_refused() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let outside = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".claude/agents")).unwrap();
+    std::fs::write(home.path().join(".claude/agents/a.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--no-config", "--local-path"])
+        .arg(outside.path())
+        .arg("to-local")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("outside the home and project directories"))
+        .stderr(predicate::str::contains("--allow-outside-home"));
+
+    assert!(!outside.path().join("agents/a.md").exists());
+}
+
+#[test]
+fn test_allow_outside_home_permits_destination() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let outside = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".claude/agents")).unwrap();
+    std::fs::write(home.path().join(".claude/agents/a.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "--no-config", "--allow-outside-home", "--local-path"])
+        .arg(outside.path())
+        .arg("to-local")
+        .assert()
+        .success();
+
+    assert!(outside.path().join("agents/a.md").exists());
+}
+
+#[test]
+fn test_global_path_works_without_home() {
+    let project = tempfile::TempDir::new().unwrap();
+    let global = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(global.path().join("agents")).unwrap();
+    std::fs::write(global.path().join("agents/a.md"), "agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env_remove("HOME")
+        .env_remove("USERPROFILE")
+        .current_dir(project.path())
+        .args(["--yes-all", "--no-config", "--global-path"])
+        .arg(global.path())
+        .arg("to-local")
+        .assert()
+        .success();
+
+    assert!(project.path().join(".claude/agents/a.md").exists());
+}
+
+#[test]
+fn test_type_hooks_syncs_hook_files() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global = home.path().join(".claude");
+    std::fs::create_dir_all(global.join("hooks")).unwrap();
+    std::fs::write(global.join("hooks/pre-commit.md"), "hook").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--type", "hooks"])
+        .assert()
+        .success();
+
+    let synced = project.path().join(".claude/hooks/pre-commit.md");
+    assert_eq!(std::fs::read_to_string(synced).unwrap(), "hook");
+}
+
+#[test]
+fn test_match_syncs_only_paths_containing_text() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global = home.path().join(".claude");
+    std::fs::create_dir_all(global.join("agents")).unwrap();
+    std::fs::write(global.join("agents/git-commit.md"), "commit agent").unwrap();
+    std::fs::write(global.join("agents/other.md"), "other agent").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["--yes-all", "to-local", "--match", "commit", "--type", "agents"])
+        .assert()
+        .success();
+
+    let local = project.path().join(".claude");
+    assert!(local.join("agents/git-commit.md").exists());
+    assert!(!local.join("agents/other.md").exists());
+}
+
+#[test]
+fn test_status_orphans_lists_files_missing_from_global() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global = home.path().join(".claude");
+    let local = project.path().join(".claude");
+    for root in [&global, &local] {
+        std::fs::create_dir_all(root.join("skills/demo")).unwrap();
+        std::fs::write(root.join("skills/demo/SKILL.md"), "skill").unwrap();
+    }
+    std::fs::write(local.join("skills/demo/old.md"), "old").unwrap();
+    std::fs::create_dir_all(local.join("agents")).unwrap();
+    std::fs::write(local.join("agents/stale.md"), "stale").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["status", "--orphans"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "Orphaned in local (not in global):\n  agents/stale.md\n  skills/demo/old.md\n",
+        ));
+
+    // Listing orphans is read-only
+    assert!(local.join("skills/demo/old.md").exists());
+    assert!(local.join("agents/stale.md").exists());
+}
+
+#[test]
+fn test_status_newer_reports_winning_side() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    let global_agents = home.path().join(".claude/agents");
+    let local_agents = project.path().join(".claude/agents");
+    std::fs::create_dir_all(&global_agents).unwrap();
+    std::fs::create_dir_all(&local_agents).unwrap();
+
+    let set_mtime = |path: std::path::PathBuf, secs: u64| {
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+            .unwrap();
+    };
+    for (name, global_secs, local_secs) in [
+        ("global-wins.md", 2_000, 1_000),
+        ("local-wins.md", 1_000, 2_000),
+    ] {
+        std::fs::write(global_agents.join(name), "global").unwrap();
+        std::fs::write(local_agents.join(name), "local").unwrap();
+        set_mtime(global_agents.join(name), global_secs);
+        set_mtime(local_agents.join(name), local_secs);
+    }
+    std::fs::write(global_agents.join("new.md"), "new").unwrap();
+
+    let mut cmd = Command::cargo_bin("ccsync").unwrap();
+    cmd.env("HOME", home.path())
+        .current_dir(project.path())
+        .args(["status", "--newer"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("agents/global-wins.md  source (global)"))
+        .stdout(predicate::str::contains("agents/local-wins.md   dest (local)"))
+        .stdout(predicate::str::is_match(r"new\.md\s+(source|dest)").unwrap().not());
+}
+
+#[test]
+fn test_check_pattern_reports_deciding_pattern() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        project.path().join(".ccsync.toml"),
+        "ignore = [\"agents/git-*\", \"skills/experimental/\"]\ninclude = [\"agents/git-commit.md\"]\n",
+    )
+    .unwrap();
+
+    let check = |args: &[&str], expected: &str| {
+        let mut cmd = Command::cargo_bin("ccsync").unwrap();
+        cmd.env("HOME", home.path())
+            .current_dir(project.path())
+            .arg("check-pattern")
+            .args(args)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(expected));
+    };
+
+    check(
+        &["--path", "agents/git-commit.md"],
+        "agents/git-commit.md: included by include pattern 'agents/git-commit.md'",
+    );
+    check(
+        &["--path", "agents/git-helper.md"],
+        "agents/git-helper.md: excluded by ignore pattern 'agents/git-*'",
+    );
+    check(
+        &["--path", "agents/reviewer.md"],
+        "agents/reviewer.md: included (no pattern matches)",
+    );
+    check(
+        &["--path", "skills/experimental", "--dir"],
+        "skills/experimental: excluded by ignore pattern 'skills/experimental/'",
+    );
+    check(
+        &["--path", "skills/experimental"],
+        "skills/experimental: included (no pattern matches)",
+    );
+}
+
+#[test]
+fn test_check_pattern_applies_cli_patterns() {
+    let home = tempfile::TempDir::new().unwrap();
+    let project = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        project.path().join(".ccsync.toml"),
+        "ignore = [\"agents/git-*\"]\n",
+    )
+    .unwrap();
+
+    let check = |args: &[&str], expected: &str| {
+        let mut cmd = Command::cargo_bin("ccsync").unwrap();
+        cmd.env("HOME", home.path())
+            .current_dir(project.path())
+            .arg("check-pattern")
+            .args(args)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(expected));
+    };
+
+    check(
+        &["--path", "agents/git-helper.md", "--type", "agents"],
+        "agents/git-helper.md: included by include pattern 'agents/**'",
+    );
+    check(
+        &[
+            "--path",
+            "agents/git-helper.md",
+            "--include",
+            "agents/git-helper.md",
+        ],
+        "agents/git-helper.md: included by include pattern 'agents/git-helper.md'",
+    );
+    check(
+        &[
+            "--path",
+            "agents/reviewer.md",
+            "--type",
+            "agents",
+            "--exclude",
+            "agents/rev*",
+        ],
+        "agents/reviewer.md: excluded by exclude pattern 'agents/rev*'",
+    );
+}